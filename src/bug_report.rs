@@ -1,11 +1,15 @@
-use crate::utils::exec_cmd;
+use crate::utils::exec_cmd_with_timeout;
 
 use std::fs;
 use std::path::PathBuf;
+use std::time::Duration;
 
 #[cfg(feature = "http")]
 const GIT_IO_BASE_URL: &str = "https://git.io/";
 
+/// How long the shell may take to print its version
+const SHELL_VERSION_TIMEOUT: Duration = Duration::from_secs(5);
+
 pub fn create() {
     let os_info = os_info::get();
 
@@ -130,7 +134,7 @@ fn get_shell_info() -> ShellInfo {
 
     let shell = shell.unwrap();
 
-    let version = exec_cmd(&shell, &["--version"])
+    let version = exec_cmd_with_timeout(&shell, &["--version"], SHELL_VERSION_TIMEOUT)
         .map(|output| output.stdout.trim().to_string())
         .unwrap_or_else(|| UNKNOWN_VERSION.to_string());
 
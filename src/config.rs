@@ -8,6 +8,7 @@ use std::marker::Sized;
 
 use dirs::home_dir;
 use std::env;
+use std::path::{Path, PathBuf};
 use toml::Value;
 
 /// Root config of a module.
@@ -179,15 +180,25 @@ impl StarshipConfig {
             log::debug!("STARSHIP_CONFIG is set: \n{}", &path);
             path
         } else {
-            // Default to using ~/.config/starship.toml
             log::debug!("STARSHIP_CONFIG is not set");
-            let config_path = home_dir()?.join(".config/starship.toml");
+            let config_dir = home_dir()?.join(".config");
+            let profile_path = env::var("STARSHIP_PROFILE")
+                .ok()
+                .and_then(|profile| get_profile_config_path(&config_dir, &profile));
+
+            // Default to using ~/.config/starship.toml
+            let config_path = profile_path.unwrap_or_else(|| config_dir.join("starship.toml"));
             let config_path_str = config_path.to_str()?.to_owned();
-            log::debug!("Using default config path: {}", config_path_str);
+            log::debug!("Using config path: {}", config_path_str);
             config_path_str
         };
 
-        let toml_content = match utils::read_file(&file_path) {
+        Self::config_from_path(&file_path)
+    }
+
+    /// Read and parse the configuration file at `file_path`
+    fn config_from_path(file_path: &str) -> Option<Value> {
+        let toml_content = match utils::read_file(file_path) {
             Ok(content) => {
                 log::trace!("Config file content: \n{}", &content);
                 Some(content)
@@ -419,6 +430,29 @@ fn parse_color_string(color_string: &str) -> Option<ansi_term::Color> {
     predefined_color
 }
 
+/// Returns the config file of the profile selected by `STARSHIP_PROFILE`, which is
+/// `starship/<profile>.toml` in the config dir, or `None` if there is no such file
+fn get_profile_config_path(config_dir: &Path, profile: &str) -> Option<PathBuf> {
+    if profile.is_empty() || profile.contains(|c| c == '/' || c == '\\') {
+        log::debug!("Invalid STARSHIP_PROFILE: {:?}", profile);
+        return None;
+    }
+
+    let profile_path = config_dir
+        .join("starship")
+        .join(format!("{}.toml", profile));
+    if profile_path.is_file() {
+        Some(profile_path)
+    } else {
+        log::debug!(
+            "No config file for profile {} at {:?}, using the default config",
+            profile,
+            profile_path
+        );
+        None
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -655,4 +689,30 @@ mod tests {
             Style::new().fg(Color::Fixed(125)).on(Color::Fixed(127))
         );
     }
+
+    #[test]
+    fn test_load_profile_config() -> std::io::Result<()> {
+        let config_dir = tempfile::tempdir()?;
+        std::fs::create_dir(config_dir.path().join("starship"))?;
+        std::fs::write(
+            config_dir.path().join("starship/work.toml"),
+            "add_newline = false\n",
+        )?;
+
+        let profile_path = get_profile_config_path(config_dir.path(), "work").unwrap();
+        let config = StarshipConfig::config_from_path(profile_path.to_str().unwrap());
+        assert_eq!(config, Some(toml::toml! { add_newline = false }));
+        config_dir.close()
+    }
+
+    #[test]
+    fn test_missing_profile_config_falls_back() -> std::io::Result<()> {
+        let config_dir = tempfile::tempdir()?;
+        std::fs::create_dir(config_dir.path().join("starship"))?;
+
+        assert_eq!(get_profile_config_path(config_dir.path(), "personal"), None);
+        assert_eq!(get_profile_config_path(config_dir.path(), ""), None);
+        assert_eq!(get_profile_config_path(config_dir.path(), "../work"), None);
+        config_dir.close()
+    }
 }
@@ -0,0 +1,27 @@
+use crate::config::{ModuleConfig, RootModuleConfig, SegmentConfig};
+
+use ansi_term::{Color, Style};
+use starship_module_config_derive::ModuleConfig;
+
+#[derive(Clone, ModuleConfig)]
+pub struct AnsibleConfig<'a> {
+    pub symbol: SegmentConfig<'a>,
+    pub version: SegmentConfig<'a>,
+    pub config: SegmentConfig<'a>,
+    pub show_config: bool,
+    pub style: Style,
+    pub disabled: bool,
+}
+
+impl<'a> RootModuleConfig<'a> for AnsibleConfig<'a> {
+    fn new() -> Self {
+        AnsibleConfig {
+            symbol: SegmentConfig::new("🅰 "),
+            version: SegmentConfig::default(),
+            config: SegmentConfig::default(),
+            show_config: false,
+            style: Color::White.bold(),
+            disabled: false,
+        }
+    }
+}
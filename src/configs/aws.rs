@@ -15,11 +15,33 @@ pub enum AwsItems {
 pub struct AwsConfig<'a> {
     pub symbol: SegmentConfig<'a>,
     pub profile: SegmentConfig<'a>,
+    pub profiles: SegmentConfig<'a>,
     pub region: SegmentConfig<'a>,
+    pub account: SegmentConfig<'a>,
+    pub age: SegmentConfig<'a>,
+    pub vault_backend: SegmentConfig<'a>,
     pub style: Style,
     pub disabled: bool,
     pub displayed_items: AwsItems,
     pub region_aliases: HashMap<String, &'a str>,
+    pub region_styles: HashMap<String, Style>,
+    pub show_account: bool,
+    pub show_age: bool,
+    pub prefer_config_region: bool,
+    pub query_imds: bool,
+    pub show_vault_backend: bool,
+    pub home_region: Option<&'a str>,
+    pub symbol_before_each: bool,
+    pub strip_account_suffix: bool,
+    pub show_all_profiles: bool,
+    pub profile_separator: &'a str,
+    pub separator: Option<&'a str>,
+    pub show_profile_count: bool,
+    pub expired_symbol: SegmentConfig<'a>,
+    pub expired_style: Style,
+    pub detect_files: Vec<&'a str>,
+    pub detect_extensions: Vec<&'a str>,
+    pub detect_folders: Vec<&'a str>,
 }
 
 impl<'a> RootModuleConfig<'a> for AwsConfig<'a> {
@@ -27,11 +49,33 @@ impl<'a> RootModuleConfig<'a> for AwsConfig<'a> {
         AwsConfig {
             symbol: SegmentConfig::new("☁️  "),
             profile: SegmentConfig::default(),
+            profiles: SegmentConfig::default(),
             region: SegmentConfig::default(),
+            account: SegmentConfig::default(),
+            age: SegmentConfig::default(),
+            vault_backend: SegmentConfig::default(),
             style: Color::Yellow.bold(),
             disabled: false,
             displayed_items: AwsItems::All,
             region_aliases: HashMap::new(),
+            region_styles: HashMap::new(),
+            show_account: false,
+            show_age: false,
+            prefer_config_region: false,
+            query_imds: false,
+            show_vault_backend: false,
+            home_region: None,
+            symbol_before_each: false,
+            strip_account_suffix: false,
+            show_all_profiles: false,
+            profile_separator: ", ",
+            separator: None,
+            show_profile_count: false,
+            expired_symbol: SegmentConfig::new("⌛ "),
+            expired_style: Color::Red.bold(),
+            detect_files: vec![],
+            detect_extensions: vec![],
+            detect_folders: vec![],
         }
     }
 }
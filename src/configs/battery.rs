@@ -1,4 +1,5 @@
 use crate::config::{ModuleConfig, RootModuleConfig, SegmentConfig};
+use crate::configs::gauge::GaugeConfig;
 
 use ansi_term::{Color, Style};
 use starship_module_config_derive::ModuleConfig;
@@ -13,6 +14,7 @@ pub struct BatteryConfig<'a> {
     pub display: Vec<BatteryDisplayConfig>,
     pub disabled: bool,
     pub percentage: SegmentConfig<'a>,
+    pub bar: GaugeConfig<'a>,
 }
 
 impl<'a> RootModuleConfig<'a> for BatteryConfig<'a> {
@@ -29,6 +31,7 @@ impl<'a> RootModuleConfig<'a> for BatteryConfig<'a> {
             }],
             disabled: false,
             percentage: SegmentConfig::default(),
+            bar: GaugeConfig::new(),
         }
     }
 }
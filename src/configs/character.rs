@@ -9,6 +9,8 @@ pub struct CharacterConfig<'a> {
     pub error_symbol: SegmentConfig<'a>,
     pub vicmd_symbol: SegmentConfig<'a>,
     pub use_symbol_for_status: bool,
+    pub streak: SegmentConfig<'a>,
+    pub show_streak: bool,
     pub style_success: Style,
     pub style_failure: Style,
     pub disabled: bool,
@@ -21,6 +23,8 @@ impl<'a> RootModuleConfig<'a> for CharacterConfig<'a> {
             error_symbol: SegmentConfig::new("✖"),
             vicmd_symbol: SegmentConfig::new("❮"),
             use_symbol_for_status: false,
+            streak: SegmentConfig::default(),
+            show_streak: false,
             style_success: Color::Green.bold(),
             style_failure: Color::Red.bold(),
             disabled: false,
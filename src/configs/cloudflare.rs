@@ -0,0 +1,25 @@
+use crate::config::{ModuleConfig, RootModuleConfig, SegmentConfig};
+
+use ansi_term::{Color, Style};
+use starship_module_config_derive::ModuleConfig;
+
+#[derive(Clone, ModuleConfig)]
+pub struct CloudflareConfig<'a> {
+    pub symbol: SegmentConfig<'a>,
+    pub name: SegmentConfig<'a>,
+    pub environment: SegmentConfig<'a>,
+    pub style: Style,
+    pub disabled: bool,
+}
+
+impl<'a> RootModuleConfig<'a> for CloudflareConfig<'a> {
+    fn new() -> Self {
+        CloudflareConfig {
+            symbol: SegmentConfig::new("⛅️ "),
+            name: SegmentConfig::default(),
+            environment: SegmentConfig::default(),
+            style: Color::Fixed(208).bold(),
+            disabled: false,
+        }
+    }
+}
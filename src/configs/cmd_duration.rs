@@ -9,6 +9,8 @@ pub struct CmdDurationConfig<'a> {
     pub prefix: &'a str,
     pub style: Style,
     pub show_milliseconds: bool,
+    pub unit: &'a str,
+    pub precision: usize,
     pub disabled: bool,
 }
 
@@ -18,6 +20,8 @@ impl<'a> RootModuleConfig<'a> for CmdDurationConfig<'a> {
             min_time: 2_000,
             prefix: "took ",
             show_milliseconds: false,
+            unit: "auto",
+            precision: 0,
             style: Color::Yellow.bold(),
             disabled: false,
         }
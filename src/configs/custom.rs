@@ -12,6 +12,14 @@ pub struct Extensions<'a>(pub Vec<&'a str>);
 #[derive(Clone, Default, PartialEq)]
 pub struct Directories<'a>(pub Vec<&'a str>);
 
+/// How to render command output spanning multiple lines
+#[derive(Clone, Debug, PartialEq)]
+pub enum Multiline {
+    First,
+    Last,
+    Join,
+}
+
 #[derive(Clone, ModuleConfig)]
 pub struct CustomConfig<'a> {
     pub symbol: Option<SegmentConfig<'a>>,
@@ -26,6 +34,11 @@ pub struct CustomConfig<'a> {
     pub files: Files<'a>,
     pub extensions: Extensions<'a>,
     pub directories: Directories<'a>,
+    pub trim: bool,
+    pub multiline: Multiline,
+    pub env_allow: Vec<&'a str>,
+    pub env_deny: Vec<&'a str>,
+    pub cache_key: Option<&'a str>,
 }
 
 impl<'a> RootModuleConfig<'a> for CustomConfig<'a> {
@@ -43,6 +56,11 @@ impl<'a> RootModuleConfig<'a> for CustomConfig<'a> {
             files: Files::default(),
             extensions: Extensions::default(),
             directories: Directories::default(),
+            trim: true,
+            multiline: Multiline::Join,
+            env_allow: Vec::new(),
+            env_deny: Vec::new(),
+            cache_key: None,
         }
     }
 }
@@ -94,3 +112,14 @@ impl<'a> ModuleConfig<'a> for Directories<'a> {
         Some(Directories(directories))
     }
 }
+
+impl<'a> ModuleConfig<'a> for Multiline {
+    fn from_config(config: &toml::Value) -> Option<Self> {
+        match config.as_str()? {
+            "first" => Some(Multiline::First),
+            "last" => Some(Multiline::Last),
+            "join" => Some(Multiline::Join),
+            _ => None,
+        }
+    }
+}
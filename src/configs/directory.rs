@@ -9,6 +9,8 @@ pub struct DirectoryConfig<'a> {
     pub truncate_to_repo: bool,
     pub fish_style_pwd_dir_length: i64,
     pub use_logical_path: bool,
+    pub use_project_name: bool,
+    pub show_symlink_target: bool,
     pub prefix: &'a str,
     pub style: Style,
     pub disabled: bool,
@@ -21,6 +23,8 @@ impl<'a> RootModuleConfig<'a> for DirectoryConfig<'a> {
             truncate_to_repo: true,
             fish_style_pwd_dir_length: 0,
             use_logical_path: true,
+            use_project_name: false,
+            show_symlink_target: false,
             prefix: "in ",
             style: Color::Cyan.bold(),
             disabled: false,
@@ -0,0 +1,51 @@
+use crate::config::{ModuleConfig, RootModuleConfig, SegmentConfig};
+
+use ansi_term::{Color, Style};
+use starship_module_config_derive::ModuleConfig;
+
+/// A badge shown while the env var has `value`
+#[derive(Clone, PartialEq)]
+pub struct BadgeRule<'a> {
+    pub value: &'a str,
+    pub label: &'a str,
+    pub style: Option<Style>,
+}
+
+#[derive(Clone, ModuleConfig)]
+pub struct EnvBadgeConfig<'a> {
+    pub symbol: SegmentConfig<'a>,
+    pub variable: Option<&'a str>,
+    pub rules: Vec<BadgeRule<'a>>,
+    pub style: Style,
+    pub disabled: bool,
+}
+
+impl<'a> RootModuleConfig<'a> for EnvBadgeConfig<'a> {
+    fn new() -> Self {
+        EnvBadgeConfig {
+            symbol: SegmentConfig::default(),
+            variable: None,
+            rules: Vec::new(),
+            style: Color::White.bold(),
+            disabled: false,
+        }
+    }
+}
+
+impl<'a> ModuleConfig<'a> for BadgeRule<'a> {
+    fn from_config(config: &'a toml::Value) -> Option<Self> {
+        let value = config.get("value")?.as_str()?;
+        // Without a label, the value itself is shown
+        let label = config
+            .get("label")
+            .and_then(toml::Value::as_str)
+            .unwrap_or(value);
+        let style = config.get("style").and_then(Style::from_config);
+
+        Some(BadgeRule {
+            value,
+            label,
+            style,
+        })
+    }
+}
@@ -0,0 +1,25 @@
+use crate::config::{ModuleConfig, RootModuleConfig};
+
+use ansi_term::{Color, Style};
+use starship_module_config_derive::ModuleConfig;
+
+#[derive(Clone, ModuleConfig)]
+pub struct FileAgeConfig<'a> {
+    pub path: Option<&'a str>,
+    pub threshold: i64,
+    pub prefix: &'a str,
+    pub style: Style,
+    pub disabled: bool,
+}
+
+impl<'a> RootModuleConfig<'a> for FileAgeConfig<'a> {
+    fn new() -> Self {
+        FileAgeConfig {
+            path: None,
+            threshold: 0,
+            prefix: "aged ",
+            style: Color::Yellow.bold(),
+            disabled: false,
+        }
+    }
+}
@@ -0,0 +1,31 @@
+use crate::config::{ModuleConfig, RootModuleConfig, SegmentConfig};
+
+use ansi_term::{Color, Style};
+use starship_module_config_derive::ModuleConfig;
+
+#[derive(Clone, ModuleConfig)]
+pub struct FlutterConfig<'a> {
+    pub symbol: SegmentConfig<'a>,
+    pub version: SegmentConfig<'a>,
+    pub channel: SegmentConfig<'a>,
+    pub engine: SegmentConfig<'a>,
+    pub show_channel: bool,
+    pub show_engine: bool,
+    pub style: Style,
+    pub disabled: bool,
+}
+
+impl<'a> RootModuleConfig<'a> for FlutterConfig<'a> {
+    fn new() -> Self {
+        FlutterConfig {
+            symbol: SegmentConfig::new("🐦 "),
+            version: SegmentConfig::default(),
+            channel: SegmentConfig::default(),
+            engine: SegmentConfig::default(),
+            show_channel: true,
+            show_engine: false,
+            style: Color::Blue.bold(),
+            disabled: false,
+        }
+    }
+}
@@ -0,0 +1,50 @@
+use crate::config::{ModuleConfig, RootModuleConfig};
+
+use ansi_term::Style;
+use starship_module_config_derive::ModuleConfig;
+
+/// How a gauge renders its ratio
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum GaugeDisplay {
+    /// A bar of `width` cells, e.g. `[███░░]`
+    Bar,
+    /// A rounded percentage, e.g. `60%`
+    Percent,
+    /// A single sparkline block character, e.g. `▅`
+    Block,
+}
+
+/// Configuration of a gauge shared by the modules displaying a ratio
+#[derive(Clone, ModuleConfig)]
+pub struct GaugeConfig<'a> {
+    pub display: GaugeDisplay,
+    pub width: usize,
+    pub fill_symbol: &'a str,
+    pub empty_symbol: &'a str,
+    pub style: Option<Style>,
+    pub disabled: bool,
+}
+
+impl<'a> RootModuleConfig<'a> for GaugeConfig<'a> {
+    fn new() -> Self {
+        GaugeConfig {
+            display: GaugeDisplay::Bar,
+            width: 5,
+            fill_symbol: "█",
+            empty_symbol: "░",
+            style: None,
+            disabled: true,
+        }
+    }
+}
+
+impl<'a> ModuleConfig<'a> for GaugeDisplay {
+    fn from_config(config: &toml::Value) -> Option<Self> {
+        match config.as_str()? {
+            "bar" => Some(GaugeDisplay::Bar),
+            "percent" => Some(GaugeDisplay::Percent),
+            "block" => Some(GaugeDisplay::Block),
+            _ => None,
+        }
+    }
+}
@@ -9,6 +9,12 @@ pub struct GitBranchConfig<'a> {
     pub truncation_length: i64,
     pub truncation_symbol: &'a str,
     pub branch_name: SegmentConfig<'a>,
+    pub pr: SegmentConfig<'a>,
+    pub pr_patterns: Vec<&'a str>,
+    pub show_pr: bool,
+    pub description: SegmentConfig<'a>,
+    pub description_length: i64,
+    pub show_description: bool,
     pub style: Style,
     pub disabled: bool,
 }
@@ -20,6 +26,12 @@ impl<'a> RootModuleConfig<'a> for GitBranchConfig<'a> {
             truncation_length: std::i64::MAX,
             truncation_symbol: "…",
             branch_name: SegmentConfig::default(),
+            pr: SegmentConfig::default(),
+            pr_patterns: vec![r"^pr-(\d+)$", r"^refs/pull/(\d+)/head$"],
+            show_pr: false,
+            description: SegmentConfig::default(),
+            description_length: 24,
+            show_description: false,
             style: Color::Purple.bold(),
             disabled: false,
         }
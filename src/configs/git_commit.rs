@@ -7,10 +7,12 @@ use starship_module_config_derive::ModuleConfig;
 pub struct GitCommitConfig<'a> {
     pub commit_hash_length: usize,
     pub hash: SegmentConfig<'a>,
+    pub time_ago: SegmentConfig<'a>,
     pub prefix: &'a str,
     pub suffix: &'a str,
     pub style: Style,
     pub only_detached: bool,
+    pub show_time_ago: bool,
     pub disabled: bool,
 }
 
@@ -20,10 +22,12 @@ impl<'a> RootModuleConfig<'a> for GitCommitConfig<'a> {
             // be consistent with git by default, which has DEFAULT_ABBREV set to 7
             commit_hash_length: 7,
             hash: SegmentConfig::default(),
+            time_ago: SegmentConfig::default(),
             prefix: "(",
             suffix: ") ",
             style: Color::Green.bold(),
             only_detached: true,
+            show_time_ago: false,
             disabled: false,
         }
     }
@@ -11,8 +11,19 @@ pub struct GitStatusConfig<'a> {
     pub behind: SegmentConfig<'a>,
     pub diverged: SegmentConfig<'a>,
     pub show_sync_count: bool,
+    pub ahead_threshold: i64,
+    pub nudge_symbol: SegmentConfig<'a>,
+    pub large_repo_threshold: i64,
+    pub large_repo: SegmentConfig<'a>,
+    pub no_commits: SegmentConfig<'a>,
+    pub show_describe: bool,
+    pub describe: SegmentConfig<'a>,
     pub conflicted: SegmentConfig<'a>,
     pub conflicted_count: CountConfig,
+    pub show_conflict_progress: bool,
+    pub conflict_resolved: SegmentConfig<'a>,
+    pub conflict_separator: &'a str,
+    pub conflict_remaining: SegmentConfig<'a>,
     pub deleted: SegmentConfig<'a>,
     pub deleted_count: CountConfig,
     pub renamed: SegmentConfig<'a>,
@@ -23,6 +34,8 @@ pub struct GitStatusConfig<'a> {
     pub staged_count: CountConfig,
     pub untracked: SegmentConfig<'a>,
     pub untracked_count: CountConfig,
+    pub summarize_untracked: bool,
+    pub untracked_summary: SegmentConfig<'a>,
     pub prefix: &'a str,
     pub suffix: &'a str,
     pub style: Style,
@@ -39,7 +52,18 @@ impl<'a> RootModuleConfig<'a> for GitStatusConfig<'a> {
             diverged: SegmentConfig::new("⇕"),
             conflicted: SegmentConfig::new("="),
             show_sync_count: false,
+            ahead_threshold: 0,
+            nudge_symbol: SegmentConfig::new("⚑"),
+            large_repo_threshold: 0,
+            large_repo: SegmentConfig::new("…"),
+            no_commits: SegmentConfig::new("∅"),
+            show_describe: false,
+            describe: SegmentConfig::default(),
             conflicted_count: CountConfig::default(),
+            show_conflict_progress: false,
+            conflict_resolved: SegmentConfig::default(),
+            conflict_separator: "/",
+            conflict_remaining: SegmentConfig::default(),
             deleted: SegmentConfig::new("✘"),
             deleted_count: CountConfig::default(),
             renamed: SegmentConfig::new("»"),
@@ -50,6 +74,8 @@ impl<'a> RootModuleConfig<'a> for GitStatusConfig<'a> {
             staged_count: CountConfig::default(),
             untracked: SegmentConfig::new("?"),
             untracked_count: CountConfig::default(),
+            summarize_untracked: false,
+            untracked_summary: SegmentConfig::default(),
             prefix: "[",
             suffix: "] ",
             style: Color::Red.bold(),
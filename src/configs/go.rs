@@ -7,6 +7,13 @@ use starship_module_config_derive::ModuleConfig;
 pub struct GoConfig<'a> {
     pub symbol: SegmentConfig<'a>,
     pub version: SegmentConfig<'a>,
+    pub cgo: SegmentConfig<'a>,
+    pub build_tags: SegmentConfig<'a>,
+    pub source: SegmentConfig<'a>,
+    pub show_source: bool,
+    pub module_path: SegmentConfig<'a>,
+    pub show_module_path: bool,
+    pub module_name_only: bool,
     pub style: Style,
     pub disabled: bool,
 }
@@ -16,6 +23,13 @@ impl<'a> RootModuleConfig<'a> for GoConfig<'a> {
         GoConfig {
             symbol: SegmentConfig::new("🐹 "),
             version: SegmentConfig::default(),
+            cgo: SegmentConfig::default(),
+            build_tags: SegmentConfig::default(),
+            source: SegmentConfig::default(),
+            show_source: false,
+            module_path: SegmentConfig::default(),
+            show_module_path: false,
+            module_name_only: false,
             style: Color::Cyan.bold(),
             disabled: false,
         }
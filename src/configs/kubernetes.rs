@@ -12,6 +12,8 @@ pub struct KubernetesConfig<'a> {
     pub style: Style,
     pub disabled: bool,
     pub context_aliases: HashMap<String, &'a str>,
+    pub dangerous_contexts: Vec<&'a str>,
+    pub dangerous_style: Style,
 }
 
 impl<'a> RootModuleConfig<'a> for KubernetesConfig<'a> {
@@ -23,6 +25,8 @@ impl<'a> RootModuleConfig<'a> for KubernetesConfig<'a> {
             style: Color::Cyan.bold(),
             disabled: true,
             context_aliases: HashMap::new(),
+            dangerous_contexts: vec![],
+            dangerous_style: Color::White.bold().on(Color::Red),
         }
     }
 }
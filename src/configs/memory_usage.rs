@@ -1,4 +1,5 @@
 use crate::config::{ModuleConfig, RootModuleConfig, SegmentConfig};
+use crate::configs::gauge::GaugeConfig;
 
 use ansi_term::{Color, Style};
 use starship_module_config_derive::ModuleConfig;
@@ -7,11 +8,17 @@ use starship_module_config_derive::ModuleConfig;
 pub struct MemoryConfig<'a> {
     pub show_percentage: bool,
     pub show_swap: bool,
+    pub show_shell_rss: bool,
     pub threshold: i64,
+    pub units: &'a str,
+    pub precision: i64,
+    pub locale: &'a str,
     pub symbol: SegmentConfig<'a>,
     pub separator: SegmentConfig<'a>,
     pub ram: SegmentConfig<'a>,
     pub swap: SegmentConfig<'a>,
+    pub shell_rss: SegmentConfig<'a>,
+    pub ram_bar: GaugeConfig<'a>,
     pub style: Style,
     pub disabled: bool,
 }
@@ -21,11 +28,17 @@ impl<'a> RootModuleConfig<'a> for MemoryConfig<'a> {
         MemoryConfig {
             show_percentage: false,
             show_swap: true,
+            show_shell_rss: false,
             threshold: 75,
+            units: "binary",
+            precision: 0,
+            locale: "",
             symbol: SegmentConfig::new("🐏 "),
             separator: SegmentConfig::new(" | "),
             ram: SegmentConfig::default(),
             swap: SegmentConfig::default(),
+            shell_rss: SegmentConfig::default(),
+            ram_bar: GaugeConfig::new(),
             style: Color::White.bold().dimmed(),
             disabled: true,
         }
@@ -1,6 +1,8 @@
+pub mod ansible;
 pub mod aws;
 pub mod battery;
 pub mod character;
+pub mod cloudflare;
 pub mod cmd_duration;
 pub mod conda;
 pub mod crystal;
@@ -10,11 +12,16 @@ pub mod docker_context;
 pub mod dotnet;
 pub mod elixir;
 pub mod elm;
+pub mod env_badge;
 pub mod env_var;
+pub mod file_age;
+pub mod flutter;
+pub mod gauge;
 pub mod git_branch;
 pub mod git_commit;
 pub mod git_state;
 pub mod git_status;
+pub mod gleam;
 pub mod go;
 pub mod haskell;
 pub mod hg_branch;
@@ -23,16 +30,21 @@ pub mod java;
 pub mod jobs;
 pub mod julia;
 pub mod kubernetes;
+pub mod last_warnings;
 pub mod memory_usage;
 pub mod nix_shell;
 pub mod nodejs;
+pub mod ocaml;
 pub mod package;
 pub mod php;
+pub mod pulumi;
 pub mod python;
+pub mod raku;
 pub mod ruby;
 pub mod rust;
 pub mod singularity;
 mod starship_root;
+pub mod terminal_title;
 pub mod terraform;
 pub mod time;
 pub mod username;
@@ -10,6 +10,7 @@ pub struct NixShellConfig<'a> {
     pub pure_msg: SegmentConfig<'a>,
     pub style: Style,
     pub symbol: SegmentConfig<'a>,
+    pub flake_symbol: SegmentConfig<'a>,
     pub disabled: bool,
 }
 
@@ -21,6 +22,7 @@ impl<'a> RootModuleConfig<'a> for NixShellConfig<'a> {
             pure_msg: SegmentConfig::new("pure"),
             style: Color::Blue.bold(),
             symbol: SegmentConfig::new("❄️  "),
+            flake_symbol: SegmentConfig::new("❆ "),
             disabled: false,
         }
     }
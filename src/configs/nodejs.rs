@@ -6,6 +6,8 @@ use starship_module_config_derive::ModuleConfig;
 #[derive(Clone, ModuleConfig)]
 pub struct NodejsConfig<'a> {
     pub symbol: SegmentConfig<'a>,
+    pub source: SegmentConfig<'a>,
+    pub show_source: bool,
     pub style: Style,
     pub disabled: bool,
 }
@@ -14,6 +16,8 @@ impl<'a> RootModuleConfig<'a> for NodejsConfig<'a> {
     fn new() -> Self {
         NodejsConfig {
             symbol: SegmentConfig::new("⬢ "),
+            source: SegmentConfig::default(),
+            show_source: false,
             style: Color::Green.bold(),
             disabled: false,
         }
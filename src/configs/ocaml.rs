@@ -0,0 +1,31 @@
+use crate::config::{ModuleConfig, RootModuleConfig, SegmentConfig};
+
+use ansi_term::{Color, Style};
+use starship_module_config_derive::ModuleConfig;
+
+#[derive(Clone, ModuleConfig)]
+pub struct OCamlConfig<'a> {
+    pub symbol: SegmentConfig<'a>,
+    pub version: SegmentConfig<'a>,
+    pub switch: SegmentConfig<'a>,
+    pub show_switch: bool,
+    pub project_name: SegmentConfig<'a>,
+    pub show_project_name: bool,
+    pub style: Style,
+    pub disabled: bool,
+}
+
+impl<'a> RootModuleConfig<'a> for OCamlConfig<'a> {
+    fn new() -> Self {
+        OCamlConfig {
+            symbol: SegmentConfig::new("🐫 "),
+            version: SegmentConfig::default(),
+            switch: SegmentConfig::default(),
+            show_switch: true,
+            project_name: SegmentConfig::default(),
+            show_project_name: false,
+            style: Color::Yellow.bold(),
+            disabled: false,
+        }
+    }
+}
@@ -6,6 +6,8 @@ use starship_module_config_derive::ModuleConfig;
 #[derive(Clone, ModuleConfig)]
 pub struct PhpConfig<'a> {
     pub symbol: SegmentConfig<'a>,
+    pub platform_php: SegmentConfig<'a>,
+    pub show_platform_php: bool,
     pub style: Style,
     pub disabled: bool,
 }
@@ -14,6 +16,8 @@ impl<'a> RootModuleConfig<'a> for PhpConfig<'a> {
     fn new() -> Self {
         PhpConfig {
             symbol: SegmentConfig::new("🐘 "),
+            platform_php: SegmentConfig::default(),
+            show_platform_php: false,
             style: Color::Fixed(147).bold(),
             disabled: false,
         }
@@ -0,0 +1,29 @@
+use crate::config::{ModuleConfig, RootModuleConfig, SegmentConfig};
+
+use ansi_term::{Color, Style};
+use starship_module_config_derive::ModuleConfig;
+
+#[derive(Clone, ModuleConfig)]
+pub struct PulumiConfig<'a> {
+    pub symbol: SegmentConfig<'a>,
+    pub version: SegmentConfig<'a>,
+    pub stack: SegmentConfig<'a>,
+    pub show_version: bool,
+    pub show_project: bool,
+    pub style: Style,
+    pub disabled: bool,
+}
+
+impl<'a> RootModuleConfig<'a> for PulumiConfig<'a> {
+    fn new() -> Self {
+        PulumiConfig {
+            symbol: SegmentConfig::new("🧊 "),
+            version: SegmentConfig::default(),
+            stack: SegmentConfig::default(),
+            show_version: false,
+            show_project: true,
+            style: Color::Purple.bold(),
+            disabled: false,
+        }
+    }
+}
@@ -8,6 +8,7 @@ pub struct PythonConfig<'a> {
     pub symbol: SegmentConfig<'a>,
     pub version: SegmentConfig<'a>,
     pub pyenv_prefix: SegmentConfig<'a>,
+    pub broken: SegmentConfig<'a>,
     pub pyenv_version_name: bool,
     pub scan_for_pyfiles: bool,
     pub style: Style,
@@ -20,6 +21,7 @@ impl<'a> RootModuleConfig<'a> for PythonConfig<'a> {
             symbol: SegmentConfig::new("🐍 "),
             version: SegmentConfig::default(),
             pyenv_prefix: SegmentConfig::new("pyenv "),
+            broken: SegmentConfig::new("broken").with_style(Some(Color::Red.bold())),
             pyenv_version_name: false,
             scan_for_pyfiles: true,
             style: Color::Yellow.bold(),
@@ -7,6 +7,10 @@ use starship_module_config_derive::ModuleConfig;
 pub struct RustConfig<'a> {
     pub symbol: SegmentConfig<'a>,
     pub version: SegmentConfig<'a>,
+    pub msrv: SegmentConfig<'a>,
+    pub check_msrv: bool,
+    pub source: SegmentConfig<'a>,
+    pub show_source: bool,
     pub style: Style,
     pub disabled: bool,
 }
@@ -16,6 +20,10 @@ impl<'a> RootModuleConfig<'a> for RustConfig<'a> {
         RustConfig {
             symbol: SegmentConfig::new("🦀 "),
             version: SegmentConfig::default(),
+            msrv: SegmentConfig::default(),
+            check_msrv: false,
+            source: SegmentConfig::default(),
+            show_source: false,
             style: Color::Red.bold(),
             disabled: false,
         }
@@ -1,12 +1,23 @@
 use crate::config::{ModuleConfig, RootModuleConfig};
 
+use ansi_term::{Color, Style};
 use starship_module_config_derive::ModuleConfig;
+use std::collections::HashMap;
 
 #[derive(Clone, ModuleConfig)]
 pub struct StarshipRootConfig<'a> {
     pub add_newline: bool,
     pub prompt_order: Vec<&'a str>,
+    pub night_prompt_order: Vec<&'a str>,
+    pub night_start_hour: u64,
+    pub night_end_hour: u64,
     pub scan_timeout: u64,
+    pub command_timeout: u64,
+    pub parallelism: usize,
+    pub raw_escapes: HashMap<String, &'a str>,
+    pub highlight_root: bool,
+    pub root_style: Style,
+    pub force_color: bool,
 }
 
 impl<'a> RootModuleConfig<'a> for StarshipRootConfig<'a> {
@@ -17,6 +28,7 @@ impl<'a> RootModuleConfig<'a> for StarshipRootConfig<'a> {
             // NOTE: If this const value is changed then Default prompt order subheading inside
             // prompt heading of config docs needs to be updated according to changes made here.
             prompt_order: vec![
+                "terminal_title",
                 "username",
                 "hostname",
                 "singularity",
@@ -31,16 +43,22 @@ impl<'a> RootModuleConfig<'a> for StarshipRootConfig<'a> {
                 "package",
                 // ↓ Toolchain version modules ↓
                 // (Let's keep these sorted alphabetically)
+                "ansible",
                 "dotnet",
                 "elixir",
                 "elm",
+                "flutter",
+                "gleam",
                 "golang",
                 "haskell",
                 "java",
                 "julia",
                 "nodejs",
+                "ocaml",
                 "php",
+                "pulumi",
                 "python",
+                "raku",
                 "ruby",
                 "rust",
                 "terraform",
@@ -49,9 +67,13 @@ impl<'a> RootModuleConfig<'a> for StarshipRootConfig<'a> {
                 "conda",
                 "memory_usage",
                 "aws",
+                "cloudflare",
+                "env_badge",
                 "env_var",
+                "file_age",
                 "cmd_duration",
                 "custom",
+                "last_warnings",
                 "line_break",
                 "jobs",
                 #[cfg(feature = "battery")]
@@ -59,7 +81,16 @@ impl<'a> RootModuleConfig<'a> for StarshipRootConfig<'a> {
                 "time",
                 "character",
             ],
+            night_prompt_order: vec![],
+            night_start_hour: 19,
+            night_end_hour: 7,
             scan_timeout: 30,
+            command_timeout: 2000,
+            parallelism: 0,
+            raw_escapes: HashMap::new(),
+            highlight_root: false,
+            root_style: Color::Red.bold(),
+            force_color: false,
         }
     }
 }
@@ -0,0 +1,18 @@
+use crate::config::{ModuleConfig, RootModuleConfig};
+
+use starship_module_config_derive::ModuleConfig;
+
+#[derive(Clone, ModuleConfig)]
+pub struct TerminalTitleConfig<'a> {
+    pub format: &'a str,
+    pub disabled: bool,
+}
+
+impl<'a> RootModuleConfig<'a> for TerminalTitleConfig<'a> {
+    fn new() -> Self {
+        TerminalTitleConfig {
+            format: "$directory",
+            disabled: true,
+        }
+    }
+}
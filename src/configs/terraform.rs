@@ -6,6 +6,9 @@ use starship_module_config_derive::ModuleConfig;
 #[derive(Clone, ModuleConfig)]
 pub struct TerraformConfig<'a> {
     pub symbol: SegmentConfig<'a>,
+    pub opentofu_symbol: SegmentConfig<'a>,
+    pub tool: SegmentConfig<'a>,
+    pub show_tool: bool,
     pub workspace: SegmentConfig<'a>,
     pub version: SegmentConfig<'a>,
     pub show_version: bool,
@@ -17,6 +20,9 @@ impl<'a> RootModuleConfig<'a> for TerraformConfig<'a> {
     fn new() -> Self {
         TerraformConfig {
             symbol: SegmentConfig::new("💠 "),
+            opentofu_symbol: SegmentConfig::new("🟡 "),
+            tool: SegmentConfig::default(),
+            show_tool: false,
             workspace: SegmentConfig::default(),
             version: SegmentConfig::default(),
             show_version: false,
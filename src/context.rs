@@ -1,16 +1,48 @@
 use crate::config::StarshipConfig;
 use crate::module::Module;
+use crate::utils::{self, CommandOutput};
 
 use crate::modules;
 use clap::ArgMatches;
 use git2::{Repository, RepositoryState};
 use once_cell::sync::OnceCell;
+use std::any::{Any, TypeId};
+use std::cell::Cell;
 use std::collections::{HashMap, HashSet};
 use std::env;
 use std::fs;
+use std::panic::{self, AssertUnwindSafe};
 use std::path::{Path, PathBuf};
+use std::process::Output;
 use std::string::String;
-use std::time::{Duration, SystemTime};
+use std::sync::{Mutex, MutexGuard, Once, PoisonError};
+use std::time::{Duration, Instant, SystemTime};
+
+/// How many parents of the current directory are scanned at most when looking for
+/// the files of a project
+const MAX_PARENT_DIRS: usize = 8;
+
+thread_local! {
+    /// Whether the current thread is inside `Context::compute_module`
+    static COMPUTING_MODULE: Cell<bool> = Cell::new(false);
+}
+
+/// Keeps the messages of panicking modules out of the prompt, as they are left out
+/// by `Context::compute_module`, which warns about them. Other panics still reach
+/// the default hook.
+fn install_module_panic_hook() {
+    static INSTALL: Once = Once::new();
+    INSTALL.call_once(|| {
+        let default_hook = panic::take_hook();
+        panic::set_hook(Box::new(move |info| {
+            if COMPUTING_MODULE.with(Cell::get) {
+                log::debug!("{}", info);
+            } else {
+                default_hook(info);
+            }
+        }));
+    });
+}
 
 /// Context contains data or common methods that may be used by multiple modules.
 /// The data contained within Context will be relevant to this particular rendering
@@ -25,14 +57,71 @@ pub struct Context<'a> {
     /// A struct containing directory contents in a lookup-optimised format.
     dir_contents: OnceCell<DirContents>,
 
+    /// The contents of each parent of the current directory, nearest first.
+    parent_dir_contents: OnceCell<Vec<DirContents>>,
+
     /// Properties to provide to modules.
     pub properties: HashMap<&'a str, String>,
 
     /// Private field to store Git information for modules who need it
     repo: OnceCell<Repo>,
 
+    /// The git repository containing the current directory, opened on first use
+    git_repo: OnceCell<Option<Mutex<Repository>>>,
+
     /// The shell the user is assumed to be running
     pub shell: Shell,
+
+    /// Whether modules are rendered with colors
+    pub color_mode: ColorMode,
+
+    /// The user's home directory, which tests may point somewhere else
+    pub home_dir: Option<PathBuf>,
+
+    /// The env vars read by modules, which tests may mock
+    env: Env,
+
+    /// How long computing each module took, in the order they were computed
+    module_timings: Mutex<Vec<ModuleTiming>>,
+
+    /// Results shared between modules, keyed by their type
+    shared: Mutex<HashMap<TypeId, Box<dyn Any + Send + Sync>>>,
+}
+
+/// The environment of starship, or the vars mocked by a test in its place, so that
+/// tests don't need to change the environment shared by the whole test process
+#[derive(Default)]
+pub struct Env {
+    /// The mocked vars, replacing all of the actual environment when set
+    mocked: Option<HashMap<String, String>>,
+}
+
+impl Env {
+    pub fn get(&self, key: &str) -> Option<String> {
+        match &self.mocked {
+            Some(mocked) => mocked.get(key).cloned(),
+            None => env::var(key).ok(),
+        }
+    }
+
+    /// Mocks the env var `key`. The vars which aren't mocked are then all unset.
+    #[cfg(test)]
+    pub fn insert(&mut self, key: &str, value: &str) {
+        self.mocked
+            .get_or_insert_with(HashMap::new)
+            .insert(key.to_string(), value.to_string());
+    }
+}
+
+/// The `Cargo.toml` of the current directory, as shared between modules
+#[derive(Clone)]
+struct CargoManifest(Option<toml::Value>);
+
+/// The time taken to compute a single module
+#[derive(Clone, Debug)]
+pub struct ModuleTiming {
+    pub name: String,
+    pub elapsed: Duration,
 }
 
 impl<'a> Context<'a> {
@@ -55,6 +144,15 @@ impl<'a> Context<'a> {
 
     /// Create a new instance of Context for the provided directory
     pub fn new_with_dir<T>(arguments: ArgMatches, dir: T) -> Context
+    where
+        T: Into<PathBuf>,
+    {
+        Context::new_with_env(arguments, dir, Env::default())
+    }
+
+    /// Create a new instance of Context for the provided directory, reading env vars
+    /// from `env`
+    pub fn new_with_env<T>(arguments: ArgMatches, dir: T, env: Env) -> Context
     where
         T: Into<PathBuf>,
     {
@@ -73,18 +171,116 @@ impl<'a> Context<'a> {
         // TODO: Currently gets the physical directory. Get the logical directory.
         let current_dir = Context::expand_tilde(dir.into());
 
-        let shell = Context::get_shell();
+        let shell = Shell::from_name(&env.get("STARSHIP_SHELL").unwrap_or_default());
+
+        let color_mode = ColorMode::detect(&env, config.get_root_config().force_color);
 
         Context {
             config,
             properties,
             current_dir,
             dir_contents: OnceCell::new(),
+            parent_dir_contents: OnceCell::new(),
             repo: OnceCell::new(),
+            git_repo: OnceCell::new(),
             shell,
+            color_mode,
+            home_dir: dirs::home_dir(),
+            env,
+            module_timings: Mutex::new(Vec::new()),
+            shared: Mutex::new(HashMap::new()),
         }
     }
 
+    /// Shares a result computed by a module with the other modules of this prompt,
+    /// replacing any previous value of the same type. Use a dedicated type for each
+    /// kind of result, e.g. `struct TruncatedPath(String)`.
+    #[allow(dead_code)] // Not used by any module yet
+    pub fn set_shared<T: Any + Clone + Send + Sync>(&self, value: T) {
+        self.shared
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert(TypeId::of::<T>(), Box::new(value));
+    }
+
+    /// Returns the result of type `T` shared by another module, if any.
+    ///
+    /// Modules are computed in parallel, so the module sharing it may not have run yet.
+    /// Use `get_shared_or_insert_with` to compute it on demand instead.
+    pub fn get_shared<T: Any + Clone + Send + Sync>(&self) -> Option<T> {
+        self.shared
+            .lock()
+            .unwrap()
+            .get(&TypeId::of::<T>())
+            .and_then(|value| value.downcast_ref::<T>())
+            .cloned()
+    }
+
+    /// Returns the shared result of type `T`, computing and sharing it with `init` if
+    /// no module did yet, so that whichever module needs it first computes it once.
+    /// Use a dedicated type for each kind of result, e.g. `struct CargoManifest(..)`.
+    pub fn get_shared_or_insert_with<T, F>(&self, init: F) -> T
+    where
+        T: Any + Clone + Send + Sync,
+        F: FnOnce() -> T,
+    {
+        if let Some(value) = self.get_shared() {
+            return value;
+        }
+
+        // Compute outside of the lock, as `init` may read other shared results
+        let value = init();
+        let mut shared = self.shared.lock().unwrap_or_else(PoisonError::into_inner);
+        shared
+            .entry(TypeId::of::<T>())
+            .or_insert_with(|| Box::new(value))
+            .downcast_ref::<T>()
+            .cloned()
+            .expect("Shared values are stored under their own type")
+    }
+
+    /// Returns the parsed `Cargo.toml` of the current directory, which is read once
+    /// and shared by the `directory` and `package` modules
+    pub fn get_cargo_manifest(&self) -> Option<toml::Value> {
+        let CargoManifest(manifest) = self.get_shared_or_insert_with(|| {
+            let manifest = utils::read_file(self.current_dir.join("Cargo.toml"))
+                .ok()
+                .and_then(|contents| toml::from_str(&contents).ok());
+            CargoManifest(manifest)
+        });
+        manifest
+    }
+
+    /// Returns the user's home directory
+    pub fn get_home(&self) -> Option<PathBuf> {
+        self.home_dir.clone()
+    }
+
+    /// Returns the value of the env var `key`, if it is set
+    pub fn get_env(&self, key: &str) -> Option<String> {
+        self.env.get(key)
+    }
+
+    /// Returns the value of the env var `key`, or `default` when it isn't set
+    pub fn get_env_or(&self, key: &str, default: &str) -> String {
+        self.get_env(key).unwrap_or_else(|| default.to_string())
+    }
+
+    /// Returns the value of the first env var in `keys` which is set, without reading
+    /// the ones after it
+    pub fn get_first_env(&self, keys: &[&str]) -> Option<String> {
+        keys.iter().find_map(|key| self.get_env(key))
+    }
+
+    /// Returns the current directory as the shell sees it, keeping the symlinks it
+    /// was reached through, e.g. to only match files at the root of a symlinked
+    /// worktree. This is `$PWD` when it points at the same directory as
+    /// `current_dir`, which is used otherwise.
+    #[allow(dead_code)] // Not used by any module yet
+    pub fn logical_dir(&self) -> PathBuf {
+        get_logical_dir(self.get_env("PWD").map(PathBuf::from), &self.current_dir)
+    }
+
     /// Convert a `~` in a path to the home directory
     fn expand_tilde(dir: PathBuf) -> PathBuf {
         if dir.starts_with("~") {
@@ -99,7 +295,50 @@ impl<'a> Context<'a> {
         let config = self.config.get_module_config(name);
         let desc = modules::description(name);
 
-        Module::new(name, desc, config)
+        let mut module = Module::new(name, desc, config);
+        module.set_color_mode(self.color_mode);
+        module
+    }
+
+    /// Computes a module by calling `compute`, recording how long it took under `name`
+    pub fn time_module<T>(&self, name: &str, compute: impl FnOnce() -> T) -> T {
+        let start = Instant::now();
+        let result = compute();
+        let elapsed = start.elapsed();
+
+        log::debug!("Module {} took {}µs", name, elapsed.as_micros());
+        if let Ok(mut module_timings) = self.module_timings.lock() {
+            module_timings.push(ModuleTiming {
+                name: name.to_string(),
+                elapsed,
+            });
+        }
+
+        result
+    }
+
+    /// Computes a module like `time_module`. A module panicking, e.g. on unexpected
+    /// command output, is left out instead of taking the whole prompt down with it.
+    pub fn compute_module<T>(&self, name: &str, compute: impl FnOnce() -> Option<T>) -> Option<T> {
+        install_module_panic_hook();
+        self.time_module(name, || {
+            let was_computing = COMPUTING_MODULE.with(|computing| computing.replace(true));
+            let result = panic::catch_unwind(AssertUnwindSafe(compute));
+            COMPUTING_MODULE.with(|computing| computing.set(was_computing));
+
+            result.unwrap_or_else(|_| {
+                log::warn!("Module {} panicked and was left out of the prompt", name);
+                None
+            })
+        })
+    }
+
+    /// Returns how long computing each module took so far
+    pub fn get_module_timings(&self) -> Vec<ModuleTiming> {
+        self.module_timings
+            .lock()
+            .map(|module_timings| module_timings.clone())
+            .unwrap_or_default()
     }
 
     /// Check if `disabled` option of the module is true in configuration file.
@@ -121,11 +360,84 @@ impl<'a> Context<'a> {
         Some(disabled == Some(true))
     }
 
+    /// Return the time limit for commands run by the given module: its own
+    /// `command_timeout` option if set, or else the root `command_timeout`.
+    pub fn get_command_timeout(&self, name: &str) -> Duration {
+        let module_timeout = self
+            .config
+            .get_module_config(name)
+            .and_then(|table| table.as_table()?.get("command_timeout")?.as_integer())
+            .filter(|timeout| *timeout > 0)
+            .map(|timeout| timeout as u64);
+
+        let timeout =
+            module_timeout.unwrap_or_else(|| self.config.get_root_config().command_timeout);
+        Duration::from_millis(timeout)
+    }
+
+    /// Return the width of the terminal, as given by `--width` or else detected
+    pub fn width(&self) -> Option<usize> {
+        self.properties
+            .get("width")
+            .and_then(|width| width.parse().ok())
+            .or_else(|| term_size::dimensions().map(|(width, _)| width))
+    }
+
+    /// Return how many times a failed command run by the given module is retried,
+    /// as set by its `retries` option.
+    pub fn get_command_retries(&self, name: &str) -> u64 {
+        self.config
+            .get_module_config(name)
+            .and_then(|table| table.as_table()?.get("retries")?.as_integer())
+            .filter(|retries| *retries > 0)
+            .map_or(0, |retries| retries as u64)
+    }
+
+    /// Execute a command on behalf of the given module, giving up once the module's
+    /// command timeout is exceeded. A failed command is retried as many times as
+    /// the module's `retries` option allows.
+    pub fn exec_cmd(&self, module: &str, cmd: &str, args: &[&str]) -> Option<CommandOutput> {
+        let time_limit = self.get_command_timeout(module);
+        (0..=self.get_command_retries(module)).find_map(|attempt| {
+            if attempt > 0 {
+                log::debug!(
+                    "Retrying {:?} for module {} (attempt {})",
+                    cmd,
+                    module,
+                    attempt
+                );
+            }
+            utils::exec_cmd_with_timeout(cmd, args, time_limit)
+        })
+    }
+
+    /// Execute a command on behalf of the given module, like `exec_cmd`, but return
+    /// its raw output on stdout and stderr along with its exit status, also when the
+    /// command failed, for modules that read what a command reports on error.
+    pub fn exec_cmd_full(&self, module: &str, cmd: &str, args: &[&str]) -> Option<Output> {
+        utils::exec_cmd_full_with_timeout(cmd, args, self.get_command_timeout(module))
+    }
+
+    /// Execute a command on behalf of the given module, like `exec_cmd`, but return
+    /// its output also when the command failed, with the exit code it failed with
+    #[allow(dead_code)] // Not used by any module yet
+    pub fn exec_cmd_with_status(
+        &self,
+        module: &str,
+        cmd: &str,
+        args: &[&str],
+    ) -> Option<CommandOutput> {
+        self.exec_cmd_full(module, cmd, args)
+            .map(CommandOutput::from)
+    }
+
     // returns a new ScanDir struct with reference to current dir_files of context
     // see ScanDir for methods
     pub fn try_begin_scan(&'a self) -> Option<ScanDir<'a>> {
         Some(ScanDir {
             dir_contents: self.dir_contents().ok()?,
+            context: Some(self),
+            scan_to_repo_root: false,
             files: &[],
             folders: &[],
             extensions: &[],
@@ -136,7 +448,7 @@ impl<'a> Context<'a> {
     pub fn get_repo(&self) -> Result<&Repo, std::io::Error> {
         self.repo
             .get_or_try_init(|| -> Result<Repo, std::io::Error> {
-                let repository = Repository::discover(&self.current_dir).ok();
+                let repository = self.lock_git_repo();
                 let branch = repository
                     .as_ref()
                     .and_then(|repo| get_current_branch(repo));
@@ -153,6 +465,23 @@ impl<'a> Context<'a> {
             })
     }
 
+    /// Returns the git repository containing the current directory, if any. The
+    /// repository is only discovered once and shared by all modules asking for it.
+    /// `Repository` isn't `Sync`, so it has to be locked before use.
+    pub fn git_repo(&self) -> Option<&Mutex<Repository>> {
+        self.git_repo
+            .get_or_init(|| Repository::discover(&self.current_dir).ok().map(Mutex::new))
+            .as_ref()
+    }
+
+    /// Locks the git repository containing the current directory, if any. A lock
+    /// left poisoned by a panicking module is taken over, as a panic can't leave the
+    /// repository handle half-updated.
+    pub fn lock_git_repo(&self) -> Option<MutexGuard<Repository>> {
+        self.git_repo()
+            .map(|git_repo| git_repo.lock().unwrap_or_else(PoisonError::into_inner))
+    }
+
     pub fn dir_contents(&self) -> Result<&DirContents, std::io::Error> {
         self.dir_contents.get_or_try_init(|| {
             let timeout = Duration::from_millis(self.config.get_root_config().scan_timeout);
@@ -160,16 +489,49 @@ impl<'a> Context<'a> {
         })
     }
 
-    fn get_shell() -> Shell {
-        let shell = std::env::var("STARSHIP_SHELL").unwrap_or_default();
-        match shell.as_str() {
-            "bash" => Shell::Bash,
-            "fish" => Shell::Fish,
-            "ion" => Shell::Ion,
-            "powershell" => Shell::PowerShell,
-            "zsh" => Shell::Zsh,
-            _ => Shell::Unknown,
-        }
+    /// Lazily lists the parents of the current directory, nearest first, up to the
+    /// root of the repo containing it and at most `MAX_PARENT_DIRS` levels up.
+    /// Parents which can't be read are skipped.
+    fn parent_dir_contents(&self) -> &[DirContents] {
+        self.parent_dir_contents.get_or_init(|| {
+            if self.dir_contents().map_or(false, is_repo_root) {
+                return Vec::new();
+            }
+
+            let timeout = Duration::from_millis(self.config.get_root_config().scan_timeout);
+            let mut parents = Vec::new();
+            for dir in self.current_dir.ancestors().skip(1).take(MAX_PARENT_DIRS) {
+                if let Ok(dir_contents) = DirContents::from_path_with_timeout(dir, timeout) {
+                    let is_root = is_repo_root(&dir_contents);
+                    parents.push(dir_contents);
+                    if is_root {
+                        break;
+                    }
+                }
+            }
+            parents
+        })
+    }
+
+    /// Returns whether a file whose name matches any of the glob `patterns` exists in
+    /// the current directory or one of its parents, up to the repo root. `*` matches
+    /// any run of characters and `?` any single character.
+    ///
+    /// Directory listings are cached, so modules can cheaply check this before doing
+    /// any other work.
+    pub fn has_any_file(&self, patterns: &[&str]) -> bool {
+        let matches = |contents: &DirContents| {
+            patterns
+                .iter()
+                .any(|pattern| contents.has_file_name_matching(pattern))
+        };
+
+        self.dir_contents().map_or(false, matches) || self.parent_dir_contents().iter().any(matches)
+    }
+
+    /// The shell the prompt is rendered for, to vary the output of modules by shell
+    pub fn shell(&self) -> Shell {
+        self.shell.clone()
     }
 }
 
@@ -191,7 +553,7 @@ impl DirContents {
         Self::from_path_with_timeout(base, Duration::from_secs(30))
     }
 
-    fn from_path_with_timeout(base: &PathBuf, timeout: Duration) -> Result<Self, std::io::Error> {
+    fn from_path_with_timeout(base: &Path, timeout: Duration) -> Result<Self, std::io::Error> {
         let start = SystemTime::now();
 
         let mut folders: HashSet<PathBuf> = HashSet::new();
@@ -247,6 +609,13 @@ impl DirContents {
         names.iter().any(|name| self.has_file_name(name))
     }
 
+    pub fn has_file_name_matching(&self, pattern: &str) -> bool {
+        if !pattern.contains(|c| c == '*' || c == '?') {
+            return self.has_file_name(pattern);
+        }
+        self.file_names.iter().any(|name| glob_match(pattern, name))
+    }
+
     pub fn has_folder(&self, path: &str) -> bool {
         self.folders.contains(Path::new(path))
     }
@@ -281,6 +650,9 @@ pub struct Repo {
 // of X language, criteria can be set via the builder pattern
 pub struct ScanDir<'a> {
     dir_contents: &'a DirContents,
+    /// Gives access to the parent directories, when scanning up to the repo root
+    context: Option<&'a Context<'a>>,
+    scan_to_repo_root: bool,
     files: &'a [&'a str],
     folders: &'a [&'a str],
     extensions: &'a [&'a str],
@@ -302,30 +674,166 @@ impl<'a> ScanDir<'a> {
         self
     }
 
+    /// Also scan the parent directories, up to the root of the git or mercurial
+    /// repo containing the current directory. Outside of a repo, only the current
+    /// directory is scanned.
+    pub const fn set_scan_to_repo_root(mut self, scan_to_repo_root: bool) -> Self {
+        self.scan_to_repo_root = scan_to_repo_root;
+        self
+    }
+
     /// based on the current Pathbuf check to see
     /// if any of this criteria match or exist and returning a boolean
     pub fn is_match(&self) -> bool {
-        self.dir_contents.has_any_extension(self.extensions)
-            || self.dir_contents.has_any_folder(self.folders)
-            || self.dir_contents.has_any_file_name(self.files)
+        self.is_match_in(self.dir_contents)
+            || self
+                .repo_parent_dir_contents()
+                .into_iter()
+                .any(|dir_contents| self.is_match_in(dir_contents))
+    }
+
+    fn is_match_in(&self, dir_contents: &DirContents) -> bool {
+        dir_contents.has_any_extension(self.extensions)
+            || dir_contents.has_any_folder(self.folders)
+            || dir_contents.has_any_file_name(self.files)
+    }
+
+    /// The parent directories up to the repo root, nearest first, or nothing when
+    /// not scanning up to the repo root or outside of a repo
+    fn repo_parent_dir_contents(&self) -> Vec<&'a DirContents> {
+        let context = match self.context {
+            Some(context) if self.scan_to_repo_root => context,
+            _ => return Vec::new(),
+        };
+        if is_repo_root(self.dir_contents) {
+            return Vec::new();
+        }
+
+        let mut parents = Vec::new();
+        for dir_contents in context.parent_dir_contents() {
+            parents.push(dir_contents);
+            if is_repo_root(dir_contents) {
+                return parents;
+            }
+        }
+
+        // No repo root was found, so none of the parents belong to the project
+        Vec::new()
+    }
+}
+
+/// Whether the directory is the root of a git or mercurial repo
+fn is_repo_root(dir_contents: &DirContents) -> bool {
+    dir_contents.has_any_folder(&[".git", ".hg"]) || dir_contents.has_file_name(".git")
+}
+
+/// Matches `name` against a glob `pattern` supporting the `*` and `?` wildcards
+fn glob_match(pattern: &str, name: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let name: Vec<char> = name.chars().collect();
+
+    // Greedy matching, backtracking to the last `*` on a mismatch
+    let (mut p, mut n) = (0, 0);
+    let mut last_star: Option<(usize, usize)> = None;
+    while n < name.len() {
+        match pattern.get(p) {
+            Some('*') => {
+                last_star = Some((p, n));
+                p += 1;
+            }
+            Some(&c) if c == '?' || c == name[n] => {
+                p += 1;
+                n += 1;
+            }
+            _ => match last_star {
+                Some((star_p, star_n)) => {
+                    p = star_p + 1;
+                    n = star_n + 1;
+                    last_star = Some((star_p, star_n + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+/// Returns `pwd` if it resolves to the same directory as `current_dir`, and
+/// `current_dir` otherwise, e.g. when `--path` points elsewhere or `$PWD` is stale
+fn get_logical_dir(pwd: Option<PathBuf>, current_dir: &Path) -> PathBuf {
+    let physical_dir = match fs::canonicalize(current_dir) {
+        Ok(dir) => dir,
+        Err(_) => return current_dir.to_path_buf(),
+    };
+
+    match pwd {
+        Some(pwd)
+            if pwd.is_absolute() && fs::canonicalize(&pwd).ok().as_ref() == Some(&physical_dir) =>
+        {
+            pwd
+        }
+        _ => current_dir.to_path_buf(),
     }
 }
 
 fn get_current_branch(repository: &Repository) -> Option<String> {
-    let head = repository.head().ok()?;
+    let head = match repository.head() {
+        Ok(head) => head,
+        // Right after `git init`, HEAD points to a branch without any commits yet
+        Err(error) if error.code() == git2::ErrorCode::UnbornBranch => {
+            let head = repository.find_reference("HEAD").ok()?;
+            let branch = head.symbolic_target()?.trim_start_matches("refs/heads/");
+            return Some(branch.to_string());
+        }
+        Err(_) => return None,
+    };
     let shorthand = head.shorthand();
 
     shorthand.map(std::string::ToString::to_string)
 }
 
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum Shell {
     Bash,
     Fish,
     Ion,
     PowerShell,
     Zsh,
-    Unknown,
+    /// Any other shell, by the name given in `STARSHIP_SHELL`, which may be empty
+    Unknown(String),
+}
+
+impl Shell {
+    /// Parse the name of a shell as set in `STARSHIP_SHELL`
+    pub fn from_name(name: &str) -> Shell {
+        match name {
+            "bash" => Shell::Bash,
+            "fish" => Shell::Fish,
+            "ion" => Shell::Ion,
+            "powershell" | "pwsh" => Shell::PowerShell,
+            "zsh" => Shell::Zsh,
+            _ => Shell::Unknown(name.to_string()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ColorMode {
+    Enabled,
+    Disabled,
+}
+
+impl ColorMode {
+    /// Colors are disabled by a non-empty `NO_COLOR` env var (see https://no-color.org),
+    /// unless `STARSHIP_FORCE_COLOR` or the `force_color` option turn them back on.
+    fn detect(env: &Env, force_color: bool) -> ColorMode {
+        let is_set = |name| env.get(name).map_or(false, |value| !value.is_empty());
+        if force_color || is_set("STARSHIP_FORCE_COLOR") || !is_set("NO_COLOR") {
+            ColorMode::Enabled
+        } else {
+            ColorMode::Disabled
+        }
+    }
 }
 
 #[cfg(test)]
@@ -344,6 +852,251 @@ mod tests {
         Ok(dir)
     }
 
+    fn context_with_config(config: toml::Value) -> Context<'static> {
+        let mut context = Context::new_with_dir(ArgMatches::default(), "/");
+        context.config = StarshipConfig {
+            config: Some(config),
+        };
+        context
+    }
+
+    #[test]
+    fn test_module_timings() {
+        let context = context_with_config(toml::Value::Table(Default::default()));
+        assert!(context.get_module_timings().is_empty());
+
+        let module = modules::handle("line_break", &context);
+        assert!(module.is_some());
+
+        let timings = context.get_module_timings();
+        assert_eq!(timings.len(), 1);
+        assert_eq!(timings[0].name, "line_break");
+    }
+
+    #[test]
+    fn test_panicking_module_is_left_out() {
+        let context = context_with_config(toml::Value::Table(Default::default()));
+        let names = ["before", "broken", "after"];
+
+        let computed: Vec<&str> = names
+            .iter()
+            .filter_map(|name| {
+                context.compute_module(name, || {
+                    if *name == "broken" {
+                        panic!("Deliberately broken module");
+                    }
+                    Some(*name)
+                })
+            })
+            .collect();
+
+        assert_eq!(computed, vec!["before", "after"]);
+        assert_eq!(context.get_module_timings().len(), 3);
+    }
+
+    #[test]
+    fn test_panic_hook_is_scoped_to_modules() {
+        let context = context_with_config(toml::Value::Table(Default::default()));
+        let computing = context.compute_module("outer", || {
+            let nested: Option<()> = context.compute_module("broken", || panic!("Deliberately"));
+            assert_eq!(nested, None);
+            Some(COMPUTING_MODULE.with(Cell::get))
+        });
+
+        assert_eq!(computing, Some(true));
+        assert!(!COMPUTING_MODULE.with(Cell::get));
+    }
+
+    #[test]
+    fn test_git_repo_usable_after_panic() -> Result<(), Box<dyn std::error::Error>> {
+        let dir = tempfile::tempdir()?;
+        Repository::init(dir.path())?;
+        let context = Context::new_with_dir(ArgMatches::default(), dir.path());
+
+        let broken: Option<()> = context.compute_module("broken", || {
+            let _git_repo = context.lock_git_repo();
+            panic!("Deliberately broken module");
+        });
+        assert_eq!(broken, None);
+        assert!(context.git_repo().unwrap().is_poisoned());
+
+        assert!(context.lock_git_repo().is_some());
+        assert!(context.get_repo().is_ok());
+        dir.close()?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_git_repo_is_opened_once() -> Result<(), Box<dyn std::error::Error>> {
+        let dir = tempfile::tempdir()?;
+        Repository::init(dir.path())?;
+        fs::create_dir(dir.path().join("nested"))?;
+
+        let context = Context::new_with_dir(ArgMatches::default(), dir.path().join("nested"));
+        let first = context.git_repo().expect("repository should be discovered");
+        let second = context.git_repo().expect("repository should be discovered");
+        assert!(std::ptr::eq(first, second));
+        assert_eq!(
+            first
+                .lock()
+                .unwrap()
+                .workdir()
+                .map(fs::canonicalize)
+                .transpose()?,
+            Some(fs::canonicalize(dir.path())?)
+        );
+
+        let outside = tempfile::tempdir()?;
+        let context = Context::new_with_dir(ArgMatches::default(), outside.path());
+        assert!(context.git_repo().is_none());
+        dir.close()?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_width_override() {
+        let mut context = context_with_config(toml::Value::Table(Default::default()));
+        context.properties.insert("width", "42".to_string());
+        assert_eq!(context.width(), Some(42));
+    }
+
+    #[test]
+    fn test_command_timeout() {
+        let context = context_with_config(toml::toml! {
+            command_timeout = 100
+            [golang]
+            command_timeout = 1500
+        });
+        assert_eq!(
+            context.get_command_timeout("golang"),
+            Duration::from_millis(1500)
+        );
+        assert_eq!(
+            context.get_command_timeout("nodejs"),
+            Duration::from_millis(100)
+        );
+    }
+
+    #[test]
+    #[cfg(not(windows))]
+    fn test_exec_cmd_timeout() {
+        let context = context_with_config(toml::toml! {
+            command_timeout = 1
+            [golang]
+            command_timeout = 5000
+        });
+        assert_eq!(context.exec_cmd("nodejs", "sleep", &["1"]), None);
+        assert!(context.exec_cmd("golang", "sleep", &["0.01"]).is_some());
+    }
+
+    #[test]
+    #[cfg(not(windows))]
+    fn test_exec_cmd_retries() -> Result<(), Box<dyn std::error::Error>> {
+        // Fails on the first run only, by creating the file it checks for
+        let dir = tempfile::tempdir()?;
+        let marker = dir.path().join("failed_once");
+        let script = format!(
+            "if [ -e '{0}' ]; then echo ok; else touch '{0}'; exit 1; fi",
+            marker.display()
+        );
+
+        let context = context_with_config(toml::toml! {
+            [golang]
+            retries = 1
+        });
+        assert_eq!(context.get_command_retries("golang"), 1);
+        assert_eq!(context.get_command_retries("nodejs"), 0);
+
+        let output = context.exec_cmd("golang", "sh", &["-c", &script]);
+        assert_eq!(output.map(|output| output.stdout), Some("ok\n".to_string()));
+
+        fs::remove_file(&marker)?;
+        assert_eq!(context.exec_cmd("nodejs", "sh", &["-c", &script]), None);
+
+        dir.close()?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_glob_match() {
+        assert!(glob_match("*.gleam", "main.gleam"));
+        assert!(glob_match("Makefile.?", "Makefile.c"));
+        assert!(glob_match("*file*", "Makefile.PL"));
+        assert!(glob_match("cpanfile", "cpanfile"));
+        assert!(!glob_match("*.gleam", "main.gleam.bak"));
+        assert!(!glob_match("Makefile.?", "Makefile"));
+        assert!(!glob_match("Makefile.?", "Makefile.PL"));
+    }
+
+    #[test]
+    fn test_has_any_file() -> Result<(), Box<dyn std::error::Error>> {
+        let dir = testdir(&["project/Makefile.PL", "project/src/lib.rs"])?;
+        let mut context = context_with_config(toml::Value::Table(Default::default()));
+        context.current_dir = dir.path().join("project/src");
+
+        assert!(context.parent_dir_contents.get().is_none());
+        assert!(context.has_any_file(&["*.toml", "Makefile.*"]));
+        let cached = context.parent_dir_contents.get().unwrap() as *const Vec<DirContents>;
+
+        assert!(context.has_any_file(&["lib.rs"]));
+        assert!(!context.has_any_file(&["*.gleam"]));
+        let still_cached = context.parent_dir_contents.get().unwrap() as *const Vec<DirContents>;
+        assert_eq!(cached, still_cached);
+
+        dir.close()?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_has_any_file_stops_at_repo_root() -> Result<(), Box<dyn std::error::Error>> {
+        let dir = testdir(&["Pulumi.yaml", "repo/.git/HEAD", "repo/src/lib.rs"])?;
+        let mut context = context_with_config(toml::Value::Table(Default::default()));
+        context.current_dir = dir.path().join("repo/src");
+
+        assert!(context.has_any_file(&["lib.rs"]));
+        assert!(!context.has_any_file(&["Pulumi.yaml"]));
+
+        dir.close()?;
+        Ok(())
+    }
+
+    #[test]
+    fn test_scan_to_repo_root() -> Result<(), Box<dyn std::error::Error>> {
+        let dir = testdir(&[
+            "outside.toml",
+            "repo/.git/HEAD",
+            "repo/inside.toml",
+            "repo/nested/src/main.rs",
+            "plain/nested/src/main.rs",
+        ])?;
+        let context_in = |path: &str| {
+            let mut context = context_with_config(toml::Value::Table(Default::default()));
+            context.current_dir = dir.path().join(path);
+            context
+        };
+        let is_match = |context: &Context, files: &[&str], to_repo_root: bool| {
+            context
+                .try_begin_scan()
+                .unwrap()
+                .set_files(files)
+                .set_scan_to_repo_root(to_repo_root)
+                .is_match()
+        };
+
+        // Inside a repo, only the directories up to its root are scanned
+        let in_repo = context_in("repo/nested/src");
+        assert!(is_match(&in_repo, &["inside.toml"], true));
+        assert!(!is_match(&in_repo, &["inside.toml"], false));
+        assert!(!is_match(&in_repo, &["outside.toml"], true));
+
+        // Outside of a repo, only the current directory is scanned
+        let outside_repo = context_in("plain/nested/src");
+        assert!(!is_match(&outside_repo, &["outside.toml"], true));
+
+        dir.close()?;
+        Ok(())
+    }
+
     #[test]
     fn test_scan_dir() -> Result<(), Box<dyn std::error::Error>> {
         let empty = testdir(&[])?;
@@ -351,6 +1104,8 @@ mod tests {
 
         assert_eq!(
             ScanDir {
+                context: None,
+                scan_to_repo_root: false,
                 dir_contents: &empty_dc,
                 files: &["package.json"],
                 extensions: &["js"],
@@ -365,6 +1120,8 @@ mod tests {
         let rust_dc = DirContents::from_path(&PathBuf::from(rust.path()))?;
         assert_eq!(
             ScanDir {
+                context: None,
+                scan_to_repo_root: false,
                 dir_contents: &rust_dc,
                 files: &["package.json"],
                 extensions: &["js"],
@@ -379,6 +1136,8 @@ mod tests {
         let java_dc = DirContents::from_path(&PathBuf::from(java.path()))?;
         assert_eq!(
             ScanDir {
+                context: None,
+                scan_to_repo_root: false,
                 dir_contents: &java_dc,
                 files: &["package.json"],
                 extensions: &["js"],
@@ -393,6 +1152,8 @@ mod tests {
         let node_dc = DirContents::from_path(&PathBuf::from(node.path()))?;
         assert_eq!(
             ScanDir {
+                context: None,
+                scan_to_repo_root: false,
                 dir_contents: &node_dc,
                 files: &["package.json"],
                 extensions: &["js"],
@@ -405,4 +1166,220 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    fn test_get_env_or() {
+        let mut env = Env::default();
+        env.insert("STARSHIP_TEST_GET_ENV_OR", "value");
+        let context = Context::new_with_env(ArgMatches::default(), "/", env);
+
+        assert_eq!(
+            context.get_env_or("STARSHIP_TEST_GET_ENV_OR", "default"),
+            "value"
+        );
+        assert_eq!(
+            context.get_env_or("STARSHIP_TEST_GET_ENV_OR_UNSET", "default"),
+            "default"
+        );
+    }
+
+    #[test]
+    fn test_mocked_env_hides_actual_env() {
+        let mut env = Env::default();
+        env.insert("STARSHIP_SHELL", "fish");
+        let context = Context::new_with_env(ArgMatches::default(), "/", env);
+
+        assert_eq!(context.get_env("STARSHIP_SHELL"), Some("fish".to_string()));
+        assert_eq!(context.get_env("PATH"), None);
+    }
+
+    #[test]
+    fn test_shell() {
+        let mut env = Env::default();
+        env.insert("STARSHIP_SHELL", "fish");
+        let context = Context::new_with_env(ArgMatches::default(), "/", env);
+
+        assert_eq!(context.shell(), Shell::Fish);
+    }
+
+    #[test]
+    #[cfg(not(windows))]
+    fn test_exec_cmd_with_status_of_failing_command() {
+        let context = context_with_config(toml::Value::Table(Default::default()));
+        let output =
+            context.exec_cmd_with_status("test", "/bin/sh", &["-c", "echo failed >&2; exit 3"]);
+        assert_eq!(
+            output,
+            Some(CommandOutput {
+                stdout: String::new(),
+                stderr: String::from("failed\n"),
+                exit_code: Some(3),
+            })
+        );
+        assert_eq!(context.exec_cmd("test", "/bin/sh", &["-c", "exit 3"]), None);
+    }
+
+    #[test]
+    fn test_color_mode_from_env() {
+        let mut env = Env::default();
+        assert_eq!(ColorMode::detect(&env, false), ColorMode::Enabled);
+
+        env.insert("NO_COLOR", "");
+        assert_eq!(ColorMode::detect(&env, false), ColorMode::Enabled);
+        env.insert("NO_COLOR", "1");
+        assert_eq!(ColorMode::detect(&env, false), ColorMode::Disabled);
+        assert_eq!(ColorMode::detect(&env, true), ColorMode::Enabled);
+
+        env.insert("STARSHIP_FORCE_COLOR", "1");
+        let context = Context::new_with_env(ArgMatches::default(), "/", env);
+        assert_eq!(context.color_mode, ColorMode::Enabled);
+    }
+
+    #[test]
+    fn test_shell_from_name() {
+        assert_eq!(Shell::from_name("zsh"), Shell::Zsh);
+        assert_eq!(Shell::from_name("pwsh"), Shell::PowerShell);
+        assert_eq!(Shell::from_name("nu"), Shell::Unknown("nu".to_string()));
+        assert_eq!(Shell::from_name(""), Shell::Unknown(String::new()));
+    }
+
+    #[test]
+    fn test_shared_between_modules() {
+        #[derive(Clone, Debug, PartialEq)]
+        struct TruncatedPath(String);
+        #[derive(Clone, Debug, PartialEq)]
+        struct Unshared(u8);
+
+        let context = Context::new_with_dir(ArgMatches::default(), "/");
+        let writing_module = |context: &Context, path: &str| {
+            context.set_shared(TruncatedPath(path.to_string()));
+        };
+        let reading_module = |context: &Context| context.get_shared::<TruncatedPath>();
+
+        assert_eq!(reading_module(&context), None);
+        writing_module(&context, "starship/engine");
+        assert_eq!(
+            reading_module(&context),
+            Some(TruncatedPath("starship/engine".to_string()))
+        );
+        writing_module(&context, "starship/booster");
+        assert_eq!(
+            reading_module(&context),
+            Some(TruncatedPath("starship/booster".to_string()))
+        );
+        assert_eq!(context.get_shared::<Unshared>(), None);
+    }
+
+    #[test]
+    fn test_shared_computed_once() {
+        #[derive(Clone, Debug, PartialEq)]
+        struct Count(usize);
+
+        let context = Context::new_with_dir(ArgMatches::default(), "/");
+        let computations = std::cell::Cell::new(0);
+        let compute = || {
+            computations.set(computations.get() + 1);
+            Count(42)
+        };
+
+        assert_eq!(context.get_shared_or_insert_with(compute), Count(42));
+        assert_eq!(context.get_shared_or_insert_with(compute), Count(42));
+        assert_eq!(context.get_shared::<Count>(), Some(Count(42)));
+        assert_eq!(computations.get(), 1);
+    }
+
+    #[test]
+    fn test_cargo_manifest_read_once() -> Result<(), std::io::Error> {
+        let dir = tempfile::tempdir()?;
+        fs::write(
+            dir.path().join("Cargo.toml"),
+            "[package]\nname = \"starship\"\n",
+        )?;
+        let context = Context::new_with_dir(ArgMatches::default(), dir.path());
+
+        let name = |manifest: Option<toml::Value>| {
+            Some(manifest?.get("package")?.get("name")?.as_str()?.to_string())
+        };
+        assert_eq!(
+            name(context.get_cargo_manifest()),
+            Some("starship".to_string())
+        );
+        fs::remove_file(dir.path().join("Cargo.toml"))?;
+        assert_eq!(
+            name(context.get_cargo_manifest()),
+            Some("starship".to_string())
+        );
+        dir.close()
+    }
+
+    #[test]
+    fn test_get_first_env() {
+        let mut env = Env::default();
+        env.insert("SECOND", "second");
+        env.insert("THIRD", "third");
+        let context = Context::new_with_env(ArgMatches::default(), "/", env);
+
+        assert_eq!(
+            context.get_first_env(&["UNSET", "SECOND", "THIRD"]),
+            Some("second".to_string())
+        );
+        assert_eq!(
+            context.get_first_env(&["THIRD", "SECOND"]),
+            Some("third".to_string())
+        );
+        assert_eq!(context.get_first_env(&["UNSET"]), None);
+        assert_eq!(context.get_first_env(&[]), None);
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_logical_dir_keeps_symlinks() -> std::io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let physical_dir = fs::canonicalize(dir.path())?.join("worktree");
+        fs::create_dir(&physical_dir)?;
+        let logical_dir = dir.path().join("link");
+        std::os::unix::fs::symlink(&physical_dir, &logical_dir)?;
+
+        assert_eq!(
+            get_logical_dir(Some(logical_dir.clone()), &physical_dir),
+            logical_dir
+        );
+        assert_eq!(
+            get_logical_dir(Some(logical_dir.clone()), &logical_dir),
+            logical_dir
+        );
+        assert_ne!(fs::canonicalize(&logical_dir)?, logical_dir);
+
+        let mut env = Env::default();
+        env.insert("PWD", logical_dir.to_str().unwrap());
+        let context = Context::new_with_env(ArgMatches::default(), &physical_dir, env);
+        assert_eq!(context.logical_dir(), logical_dir);
+        dir.close()
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_logical_dir_ignores_unrelated_pwd() -> std::io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let physical_dir = dir.path().join("worktree");
+        let other_dir = dir.path().join("other");
+        fs::create_dir(&physical_dir)?;
+        fs::create_dir(&other_dir)?;
+        std::os::unix::fs::symlink(&other_dir, dir.path().join("link"))?;
+
+        assert_eq!(
+            get_logical_dir(Some(dir.path().join("link")), &physical_dir),
+            physical_dir
+        );
+        assert_eq!(
+            get_logical_dir(Some(PathBuf::from("worktree")), &physical_dir),
+            physical_dir
+        );
+        assert_eq!(get_logical_dir(None, &physical_dir), physical_dir);
+        assert_eq!(
+            get_logical_dir(Some(physical_dir.clone()), &dir.path().join("missing")),
+            dir.path().join("missing")
+        );
+        dir.close()
+    }
 }
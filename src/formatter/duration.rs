@@ -0,0 +1,130 @@
+const UNITS: [(&str, u128); 4] = [
+    ("d", 24 * 60 * 60 * 1000),
+    ("h", 60 * 60 * 1000),
+    ("m", 60 * 1000),
+    ("s", 1000),
+];
+
+/// Format a duration in milliseconds by its two largest units, e.g. `2h15m` or `45s`,
+/// dropping the smaller ones. Sub-second durations are given in milliseconds, e.g. `250ms`.
+pub fn format_duration(millis: u128) -> String {
+    let largest = match UNITS.iter().position(|(_, size)| millis >= *size) {
+        Some(largest) => largest,
+        None => return format!("{}ms", millis),
+    };
+
+    let (unit, size) = UNITS[largest];
+    let mut formatted = format!("{}{}", millis / size, unit);
+    if let Some((next_unit, next_size)) = UNITS.get(largest + 1) {
+        let next = millis % size / next_size;
+        if next > 0 {
+            formatted.push_str(&format!("{}{}", next, next_unit));
+        }
+    }
+    formatted
+}
+
+/// Format a duration like `format_duration`, unless it is shorter than `min_millis`,
+/// like the `min_time` of `cmd_duration`
+#[allow(dead_code)] // Not used by any module yet
+pub fn format_duration_above(millis: u128, min_millis: u128) -> Option<String> {
+    if millis < min_millis {
+        return None;
+    }
+    Some(format_duration(millis))
+}
+
+/// Format a duration in milliseconds with all of its units, e.g. `2h48m30s`, like
+/// `cmd_duration` does. Milliseconds are only given with `show_millis`, or for
+/// sub-second durations.
+pub fn format_duration_full(raw_millis: u128, show_millis: bool) -> String {
+    // Calculate a simple breakdown into days/hours/minutes/seconds/milliseconds
+    let (millis, raw_seconds) = (raw_millis % 1000, raw_millis / 1000);
+    let (seconds, raw_minutes) = (raw_seconds % 60, raw_seconds / 60);
+    let (minutes, raw_hours) = (raw_minutes % 60, raw_minutes / 60);
+    let (hours, days) = (raw_hours % 24, raw_hours / 24);
+
+    let components = [days, hours, minutes, seconds];
+    let suffixes = ["d", "h", "m", "s"];
+
+    let mut rendered_components: Vec<String> = components
+        .iter()
+        .zip(&suffixes)
+        .map(format_component)
+        .collect();
+    if show_millis || raw_millis < 1000 {
+        rendered_components.push(format_component((&millis, &"ms")));
+    }
+    rendered_components.join("")
+}
+
+/// Render a single component of the time string, giving an empty string if component is zero
+fn format_component((component, suffix): (&u128, &&str)) -> String {
+    match component {
+        0 => String::new(),
+        n => format!("{}{}", n, suffix),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_duration_sub_second() {
+        assert_eq!(format_duration(0), "0ms");
+        assert_eq!(format_duration(250), "250ms");
+        assert_eq!(format_duration(999), "999ms");
+    }
+
+    #[test]
+    fn test_format_duration_seconds_and_minutes() {
+        assert_eq!(format_duration(1_500), "1s");
+        assert_eq!(format_duration(45_000), "45s");
+        assert_eq!(format_duration(90_000), "1m30s");
+        assert_eq!(format_duration(600_000), "10m");
+    }
+
+    #[test]
+    fn test_format_duration_hours_and_days() {
+        assert_eq!(format_duration(8_130_000), "2h15m");
+        assert_eq!(format_duration(3_605_000), "1h");
+        assert_eq!(format_duration(90_061_000), "1d1h");
+        assert_eq!(format_duration(30 * 24 * 60 * 60 * 1000), "30d");
+    }
+
+    #[test]
+    fn test_format_duration_above() {
+        assert_eq!(format_duration_above(1_500, 2_000), None);
+        assert_eq!(format_duration_above(2_000, 2_000), Some("2s".to_string()));
+        assert_eq!(format_duration_above(250, 0), Some("250ms".to_string()));
+    }
+
+    #[test]
+    fn test_500ms() {
+        assert_eq!(format_duration_full(500 as u128, true), "500ms")
+    }
+    #[test]
+    fn test_10s() {
+        assert_eq!(format_duration_full(10_000 as u128, true), "10s")
+    }
+    #[test]
+    fn test_90s() {
+        assert_eq!(format_duration_full(90_000 as u128, true), "1m30s")
+    }
+    #[test]
+    fn test_10110s() {
+        assert_eq!(format_duration_full(10_110_000 as u128, true), "2h48m30s")
+    }
+    #[test]
+    fn test_1d() {
+        assert_eq!(format_duration_full(86_400_000 as u128, true), "1d")
+    }
+    #[test]
+    fn test_show_millis() {
+        assert_eq!(format_duration_full(250, false), "250ms");
+        assert_eq!(format_duration_full(1_500, false), "1s");
+        assert_eq!(format_duration_full(1_500, true), "1s500ms");
+        assert_eq!(format_duration_full(3_725_000, true), "1h2m5s");
+    }
+}
@@ -1,5 +1,8 @@
+pub mod duration;
 pub mod model;
+pub mod number;
 mod parser;
 pub mod string_formatter;
+pub mod version;
 
 pub use string_formatter::StringFormatter;
@@ -7,7 +7,7 @@ pub struct TextGroup<'a> {
 
 pub enum FormatElement<'a> {
     Text(Cow<'a, str>),
-    Variable(Cow<'a, str>),
+    Variable(Cow<'a, str>, Vec<Modifier<'a>>),
     TextGroup(TextGroup<'a>),
 }
 
@@ -15,3 +15,9 @@ pub enum StyleElement<'a> {
     Text(Cow<'a, str>),
     Variable(Cow<'a, str>),
 }
+
+/// A modifier applied to the value of a variable, e.g. `pad(15)` in `${region:pad(15)}`
+pub struct Modifier<'a> {
+    pub name: Cow<'a, str>,
+    pub args: Vec<Cow<'a, str>>,
+}
@@ -0,0 +1,96 @@
+/// How a locale writes numbers, e.g. `1,234.5` in English or `1.234,5` in German
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct NumberLocale {
+    group_separator: &'static str,
+    decimal_separator: &'static str,
+    /// Only the last three digits form a group, the others being grouped by two
+    indian_grouping: bool,
+}
+
+impl NumberLocale {
+    pub const ENGLISH: NumberLocale = NumberLocale {
+        group_separator: ",",
+        decimal_separator: ".",
+        indian_grouping: false,
+    };
+
+    /// Returns the conventions of a locale among `en`, `de`, `es`, `it`, `nl`, `fr`,
+    /// `ch` and `in`
+    pub fn from_name(locale: &str) -> Option<NumberLocale> {
+        let (group_separator, decimal_separator, indian_grouping) = match locale {
+            "en" => return Some(NumberLocale::ENGLISH),
+            "de" | "es" | "it" | "nl" => (".", ",", false),
+            "fr" => ("\u{202f}", ",", false),
+            "ch" => ("'", ".", false),
+            "in" => (",", ".", true),
+            _ => return None,
+        };
+        Some(NumberLocale {
+            group_separator,
+            decimal_separator,
+            indian_grouping,
+        })
+    }
+
+    /// Format a decimal number such as `-1234567.5` following the locale, e.g.
+    /// `-1.234.567,5`. Returns `None` if `value` isn't a decimal number.
+    pub fn format(&self, value: &str) -> Option<String> {
+        let (sign, unsigned) = if value.starts_with('-') {
+            ("-", &value[1..])
+        } else {
+            ("", value)
+        };
+        let mut parts = unsigned.splitn(2, '.');
+        let integer = parts.next()?;
+        let fraction = parts.next();
+
+        let is_digits = |part: &str| !part.is_empty() && part.bytes().all(|b| b.is_ascii_digit());
+        if !is_digits(integer) || !fraction.map_or(true, is_digits) {
+            return None;
+        }
+
+        let mut groups: Vec<&str> = Vec::new();
+        let mut end = integer.len();
+        let mut group_len = 3;
+        while end > group_len {
+            groups.push(&integer[end - group_len..end]);
+            end -= group_len;
+            if self.indian_grouping {
+                group_len = 2;
+            }
+        }
+        groups.push(&integer[..end]);
+        groups.reverse();
+
+        let mut result = format!("{}{}", sign, groups.join(self.group_separator));
+        if let Some(fraction) = fraction {
+            result.push_str(self.decimal_separator);
+            result.push_str(fraction);
+        }
+        Some(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_in_locales() {
+        let format = |locale: &str, value: &str| NumberLocale::from_name(locale)?.format(value);
+        assert_eq!(format("en", "1234567.25"), Some("1,234,567.25".to_string()));
+        assert_eq!(format("de", "1234567.25"), Some("1.234.567,25".to_string()));
+        assert_eq!(format("in", "1234567"), Some("12,34,567".to_string()));
+        assert_eq!(format("en", "-1000"), Some("-1,000".to_string()));
+        assert_eq!(format("en", "999"), Some("999".to_string()));
+        assert_eq!(format("xx", "1000"), None);
+    }
+
+    #[test]
+    fn test_format_non_numbers() {
+        assert_eq!(NumberLocale::ENGLISH.format("12GiB"), None);
+        assert_eq!(NumberLocale::ENGLISH.format("1."), None);
+        assert_eq!(NumberLocale::ENGLISH.format(""), None);
+        assert_eq!(NumberLocale::ENGLISH.format("1.2.3"), None);
+    }
+}
@@ -17,8 +17,44 @@ fn _parse_textgroup(textgroup: Pair<Rule>) -> TextGroup {
     }
 }
 
-fn _parse_variable(variable: Pair<Rule>) -> &str {
-    variable.into_inner().next().unwrap().as_str()
+fn _parse_variable(variable: Pair<Rule>) -> (&str, Vec<Modifier>) {
+    let mut inner_rules = variable.into_inner();
+    let name = inner_rules.next().unwrap().as_str();
+    let mut modifiers: Vec<Modifier> = Vec::new();
+    for pair in inner_rules {
+        match pair.as_rule() {
+            Rule::modifier => modifiers.push(_parse_modifier(pair)),
+            // The default value is written last, but has to be applied before any
+            // other modifier so that they also apply to it
+            Rule::default => modifiers.insert(0, _parse_default(pair)),
+            _ => unreachable!(),
+        }
+    }
+
+    (name, modifiers)
+}
+
+fn _parse_default(default: Pair<Rule>) -> Modifier {
+    Modifier {
+        name: "default".into(),
+        args: vec![_parse_text(default).into()],
+    }
+}
+
+fn _parse_modifier(modifier: Pair<Rule>) -> Modifier {
+    let mut inner_rules = modifier.into_inner();
+    let name = inner_rules.next().unwrap().as_str();
+    let args = inner_rules.map(|arg| arg.as_str().trim().into()).collect();
+
+    Modifier {
+        name: name.into(),
+        args,
+    }
+}
+
+fn _new_variable(variable: Pair<Rule>) -> FormatElement {
+    let (name, modifiers) = _parse_variable(variable);
+    FormatElement::Variable(name.into(), modifiers)
 }
 
 fn _parse_text(text: Pair<Rule>) -> String {
@@ -35,7 +71,7 @@ fn _parse_format(format: Pair<Rule>) -> Vec<FormatElement> {
     for pair in format.into_inner() {
         match pair.as_rule() {
             Rule::text => result.push(FormatElement::Text(_parse_text(pair).into())),
-            Rule::variable => result.push(FormatElement::Variable(_parse_variable(pair).into())),
+            Rule::variable => result.push(_new_variable(pair)),
             Rule::textgroup => result.push(FormatElement::TextGroup(_parse_textgroup(pair))),
             _ => unreachable!(),
         }
@@ -50,7 +86,7 @@ fn _parse_style(style: Pair<Rule>) -> Vec<StyleElement> {
     for pair in style.into_inner() {
         match pair.as_rule() {
             Rule::text => result.push(StyleElement::Text(_parse_text(pair).into())),
-            Rule::variable => result.push(StyleElement::Variable(_parse_variable(pair).into())),
+            Rule::variable => result.push(StyleElement::Variable(_parse_variable(pair).0.into())),
             _ => unreachable!(),
         }
     }
@@ -66,7 +102,7 @@ pub fn parse(format: &str) -> Result<Vec<FormatElement>, Error<Rule>> {
     for pair in pairs.take_while(|pair| pair.as_rule() != Rule::EOI) {
         match pair.as_rule() {
             Rule::text => result.push(FormatElement::Text(_parse_text(pair).into())),
-            Rule::variable => result.push(FormatElement::Variable(_parse_variable(pair).into())),
+            Rule::variable => result.push(_new_variable(pair)),
             Rule::textgroup => result.push(FormatElement::TextGroup(_parse_textgroup(pair))),
             _ => unreachable!(),
         }
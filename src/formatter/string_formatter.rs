@@ -2,17 +2,23 @@ use ansi_term::Style;
 use pest::error::Error;
 use rayon::prelude::*;
 use std::collections::BTreeMap;
+use unicode_width::UnicodeWidthStr;
 
 use crate::config::parse_style_string;
 use crate::segment::Segment;
+use crate::utils::format_bytes;
 
 use super::model::*;
+use super::number::NumberLocale;
 use super::parser::{parse, Rule};
+use super::version::normalize_components;
 
 #[derive(Clone)]
 enum VariableValue {
     Plain(String),
     Styled(Vec<Segment>),
+    /// Only rendered along with the non-empty value of another variable of its group
+    Meta(String),
 }
 
 impl Default for VariableValue {
@@ -23,6 +29,14 @@ impl Default for VariableValue {
 
 type VariableMapType = BTreeMap<String, Option<VariableValue>>;
 
+/// The widest `pad` applied, as no terminal is wider. This keeps a mistyped width
+/// from allocating a huge padding on every prompt.
+const MAX_PAD_WIDTH: u64 = 1000;
+
+/// The name of the empty segment emitted for an unmapped `$fill` variable. The renderer
+/// pads it so that what follows on the same line is pushed to the right edge.
+pub const FILL_SEGMENT: &str = "_fill";
+
 pub struct StringFormatter<'a> {
     format: Vec<FormatElement<'a>>,
     variables: VariableMapType,
@@ -47,6 +61,59 @@ impl<'a> StringFormatter<'a> {
         self
     }
 
+    /// Maps the variables that are still unset, passing the mapper the plain text
+    /// values already set by previous calls to `map`. This lets a variable depend on
+    /// another one, e.g. a symbol chosen by the current region.
+    pub fn map_with_variables(
+        mut self,
+        mapper: impl Fn(&str, &BTreeMap<String, String>) -> Option<String> + Sync,
+    ) -> Self {
+        let resolved: BTreeMap<String, String> = self
+            .variables
+            .iter()
+            .filter_map(|(key, value)| match value {
+                Some(VariableValue::Plain(text)) => Some((key.clone(), text.clone())),
+                _ => None,
+            })
+            .collect();
+
+        self.variables
+            .par_iter_mut()
+            .filter(|(_, value)| value.is_none())
+            .for_each(|(key, value)| {
+                *value = mapper(key, &resolved).map(VariableValue::Plain);
+            });
+        self
+    }
+
+    /// Maps variable name to its value and, optionally, a style chosen by the value,
+    /// e.g. to color production regions. That style overrides the one of the group.
+    pub fn map_with_style(
+        mut self,
+        mapper: impl Fn(&str) -> Option<(String, Option<Style>)> + Sync,
+    ) -> Self {
+        self.variables.par_iter_mut().for_each(|(key, value)| {
+            *value = mapper(key).map(|(text, style)| {
+                VariableValue::Styled(vec![_new_segment(key.clone(), text, style)])
+            });
+        });
+        self
+    }
+
+    /// Maps the variables that are still unset to meta values, such as a symbol, which
+    /// follow the data of their text group: they are hidden when every other variable
+    /// of the group is empty, e.g. the symbol of `[$symbol$region](yellow)` without a
+    /// region. Groups without any other variable always show their meta values.
+    pub fn map_meta(mut self, mapper: impl Fn(&str) -> Option<String> + Sync) -> Self {
+        self.variables
+            .par_iter_mut()
+            .filter(|(_, value)| value.is_none())
+            .for_each(|(key, value)| {
+                *value = mapper(key).map(VariableValue::Meta);
+            });
+        self
+    }
+
     /// Maps variable name to an array of segments
     pub fn map_variables_to_segments(
         mut self,
@@ -91,6 +158,7 @@ impl<'a> StringFormatter<'a> {
             variables: &'a VariableMapType,
         ) -> Vec<Segment> {
             let mut result: Vec<Segment> = Vec::new();
+            let show_meta = _has_data(&format, variables) != Some(false);
 
             format.reverse();
             while let Some(el) = format.pop() {
@@ -105,11 +173,17 @@ impl<'a> StringFormatter<'a> {
                         };
                         _parse_textgroup(textgroup, &variables)
                     }
-                    FormatElement::Variable(name) => variables
+                    FormatElement::Variable(ref name, _)
+                        if &**name == "fill"
+                            && variables.get("fill").map_or(false, Option::is_none) =>
+                    {
+                        vec![_new_segment(FILL_SEGMENT.into(), String::new(), style)]
+                    }
+                    FormatElement::Variable(name, modifiers) => variables
                         .get(name.as_ref())
                         .map(|segments| {
                             let value = segments.clone().unwrap_or_default();
-                            match value {
+                            let segments = match value {
                                 VariableValue::Styled(segments) => segments
                                     .into_iter()
                                     .map(|mut segment| {
@@ -124,7 +198,12 @@ impl<'a> StringFormatter<'a> {
                                 VariableValue::Plain(text) => {
                                     vec![_new_segment(name.to_string(), text, style)]
                                 }
-                            }
+                                VariableValue::Meta(text) if show_meta => {
+                                    vec![_new_segment(name.to_string(), text, style)]
+                                }
+                                VariableValue::Meta(_) => Vec::new(),
+                            };
+                            _apply_modifiers(segments, &modifiers)
                         })
                         .unwrap_or_default(),
                 };
@@ -138,6 +217,31 @@ impl<'a> StringFormatter<'a> {
     }
 }
 
+/// Whether any variable of `format`, other than meta variables, has a non-empty value.
+/// Returns `None` when there are no such variables.
+fn _has_data(format: &[FormatElement], variables: &VariableMapType) -> Option<bool> {
+    let mut has_data = None;
+    for el in format {
+        let el_has_data = match el {
+            FormatElement::Variable(name, _) => match variables.get(name.as_ref()) {
+                Some(Some(VariableValue::Meta(_))) => None,
+                Some(None) if &**name == "fill" => None,
+                Some(Some(VariableValue::Plain(text))) => Some(!text.is_empty()),
+                Some(Some(VariableValue::Styled(segments))) => {
+                    Some(segments.iter().any(|segment| !segment.is_empty()))
+                }
+                _ => Some(false),
+            },
+            FormatElement::TextGroup(textgroup) => _has_data(&textgroup.format, variables),
+            FormatElement::Text(_) => None,
+        };
+        if el_has_data.is_some() {
+            has_data = Some(has_data == Some(true) || el_has_data == Some(true));
+        }
+    }
+    has_data
+}
+
 /// Extract variable names from an array of `FormatElement` into a `BTreeMap`
 fn _get_variables<'a>(format: &[FormatElement<'a>]) -> VariableMapType {
     let mut variables: VariableMapType = Default::default();
@@ -148,7 +252,7 @@ fn _get_variables<'a>(format: &[FormatElement<'a>]) -> VariableMapType {
     ) {
         for el in &textgroup.format {
             match el {
-                FormatElement::Variable(name) => _push_variable(variables, name.as_ref()),
+                FormatElement::Variable(name, _) => _push_variable(variables, name.as_ref()),
                 FormatElement::TextGroup(textgroup) => {
                     _push_variables_from_textgroup(variables, &textgroup)
                 }
@@ -168,7 +272,7 @@ fn _get_variables<'a>(format: &[FormatElement<'a>]) -> VariableMapType {
 
     for el in format {
         match el {
-            FormatElement::Variable(name) => _push_variable(&mut variables, name.as_ref()),
+            FormatElement::Variable(name, _) => _push_variable(&mut variables, name.as_ref()),
             FormatElement::TextGroup(textgroup) => {
                 _push_variables_from_textgroup(&mut variables, &textgroup)
             }
@@ -179,6 +283,123 @@ fn _get_variables<'a>(format: &[FormatElement<'a>]) -> VariableMapType {
     variables
 }
 
+/// Apply the modifiers of a variable, in order, to its rendered segments
+fn _apply_modifiers(mut segments: Vec<Segment>, modifiers: &[Modifier]) -> Vec<Segment> {
+    for modifier in modifiers {
+        segments = match (modifier.name.as_ref(), modifier.args.as_slice()) {
+            ("default", [value]) => _default(segments, value),
+            ("bytes", []) => _bytes(segments, "binary"),
+            ("bytes", [units]) => _bytes(segments, units),
+            ("number", []) => _number(segments, "en"),
+            ("number", [locale]) => _number(segments, locale),
+            (components, []) if components.parse::<usize>().is_ok() => {
+                _components(segments, components.parse().unwrap_or_default())
+            }
+            ("pad", [width]) => match width.parse::<i64>() {
+                Ok(width) => _pad(segments, width),
+                Err(_) => {
+                    log::warn!("Invalid width `{}` given to the `pad` modifier", width);
+                    segments
+                }
+            },
+            (name, args) => {
+                log::warn!(
+                    "Unknown modifier `{}` with {} argument(s), ignoring it",
+                    name,
+                    args.len()
+                );
+                segments
+            }
+        }
+    }
+    segments
+}
+
+/// Replace the value of a variable by `value` when it renders to nothing
+fn _default(mut segments: Vec<Segment>, value: &str) -> Vec<Segment> {
+    if segments.iter().any(|segment| !segment.value.is_empty()) {
+        return segments;
+    }
+
+    match segments.first_mut() {
+        Some(segment) => segment.value = value.to_owned(),
+        None => segments.push(_new_segment("_default".into(), value.to_owned(), None)),
+    }
+    segments
+}
+
+/// Render segments holding a byte count in a human-readable unit, using either
+/// `binary` (KiB, MiB, ...) or `decimal` (kB, MB, ...) units. Segments which aren't
+/// numbers are left untouched.
+fn _bytes(mut segments: Vec<Segment>, units: &str) -> Vec<Segment> {
+    let binary = match units {
+        "binary" => true,
+        "decimal" => false,
+        _ => {
+            log::warn!("Unknown units `{}` given to the `bytes` modifier", units);
+            return segments;
+        }
+    };
+
+    for segment in &mut segments {
+        if let Ok(bytes) = segment.value.trim().parse::<f64>() {
+            segment.value = format_bytes(bytes, binary);
+        }
+    }
+    segments
+}
+
+/// Group the digits of numeric segments by thousands, following the conventions of
+/// `locale`. Segments which aren't numbers are left untouched.
+fn _number(mut segments: Vec<Segment>, locale: &str) -> Vec<Segment> {
+    let locale = match NumberLocale::from_name(locale) {
+        Some(locale) => locale,
+        None => {
+            log::warn!("Unknown locale `{}` given to the `number` modifier", locale);
+            return segments;
+        }
+    };
+
+    for segment in &mut segments {
+        if let Some(grouped) = locale.format(&segment.value) {
+            segment.value = grouped;
+        }
+    }
+    segments
+}
+
+/// Normalize versions to exactly `components` dotted components, e.g. `${raw:3}`
+fn _components(mut segments: Vec<Segment>, components: usize) -> Vec<Segment> {
+    for segment in &mut segments {
+        segment.value = normalize_components(&segment.value, components);
+    }
+    segments
+}
+
+/// Pad segments with spaces up to `width` display columns. The padding is added at the
+/// end for a positive width, and at the start for a negative one. Longer values are
+/// left untouched, and widths beyond `MAX_PAD_WIDTH` are cut down to it.
+fn _pad(mut segments: Vec<Segment>, width: i64) -> Vec<Segment> {
+    let current_width: usize = segments.iter().map(|segment| segment.value.width()).sum();
+    let target_width = width.unsigned_abs().min(MAX_PAD_WIDTH) as usize;
+    if current_width >= target_width {
+        return segments;
+    }
+
+    let padding = " ".repeat(target_width - current_width);
+    let edge_segment = if width > 0 {
+        segments.last_mut()
+    } else {
+        segments.first_mut()
+    };
+    match edge_segment {
+        Some(segment) if width > 0 => segment.value.push_str(&padding),
+        Some(segment) => segment.value.insert_str(0, &padding),
+        None => segments.push(_new_segment("_pad".into(), padding, None)),
+    }
+    segments
+}
+
 /// Helper function to create a new segment
 fn _new_segment(name: String, value: String, style: Option<Style>) -> Segment {
     Segment {
@@ -251,6 +472,62 @@ mod tests {
         match_next!(result_iter, r#"\[$text](red bold)"#, None);
     }
 
+    #[test]
+    fn test_each_escaped_char() {
+        for escaped in &["$", "(", ")", "[", "]", "\\"] {
+            let format = format!("a\\{}b", escaped);
+            let formatter = StringFormatter::new(&format).unwrap().map(empty_mapper);
+            let result = formatter.parse(None);
+            let mut result_iter = result.iter();
+            assert_eq!(result_iter.next().unwrap().value, format!("a{}b", escaped));
+            assert!(result_iter.next().is_none());
+        }
+    }
+
+    #[test]
+    fn test_escaped_chars_in_textgroup() {
+        const FORMAT_STR: &str = r"[\$\(x\)\[y\]](red)";
+        let formatter = StringFormatter::new(FORMAT_STR).unwrap().map(empty_mapper);
+        let result = formatter.parse(None);
+        let mut result_iter = result.iter();
+        match_next!(result_iter, "$(x)[y]", Some(Color::Red.normal()));
+    }
+
+    #[test]
+    fn test_escaped_chars_around_variables() {
+        const FORMAT_STR: &str = r"\$$cost \[$tag\]";
+        let formatter = StringFormatter::new(FORMAT_STR)
+            .unwrap()
+            .map(|variable| match variable {
+                "cost" => Some("42".to_owned()),
+                "tag" => Some("v1".to_owned()),
+                _ => None,
+            });
+        let result = formatter.parse(None);
+        let value: String = result
+            .iter()
+            .map(|segment| segment.value.as_str())
+            .collect();
+        assert_eq!(value, "$42 [v1]");
+    }
+
+    #[test]
+    fn test_unknown_escapes_are_kept() {
+        const FORMAT_STR: &str = r"C:\new\table\";
+        let formatter = StringFormatter::new(FORMAT_STR).unwrap().map(empty_mapper);
+        let result = formatter.parse(None);
+        let mut result_iter = result.iter();
+        match_next!(result_iter, r"C:\new\table\", None);
+
+        const CONTROL_CHARS: &str = "a\nb\tc";
+        let formatter = StringFormatter::new(CONTROL_CHARS)
+            .unwrap()
+            .map(empty_mapper);
+        let result = formatter.parse(None);
+        let mut result_iter = result.iter();
+        match_next!(result_iter, "a\nb\tc", None);
+    }
+
     #[test]
     fn test_nested_textgroup() {
         const FORMAT_STR: &str = "outer [middle [inner](blue)](red bold)";
@@ -310,6 +587,264 @@ mod tests {
         match_next!(result_iter, "styled_no_modifier", styled_no_modifier_style);
     }
 
+    #[test]
+    fn test_value_style_overrides_group_style() {
+        const FORMAT_STR: &str = "[$region $profile](red bold)";
+        let group_style = Some(Color::Red.bold());
+        let region_style = Some(Color::Green.underline());
+
+        let formatter = StringFormatter::new(FORMAT_STR)
+            .unwrap()
+            .map_with_style(|variable| match variable {
+                "region" => Some(("us-east-1".to_owned(), region_style)),
+                "profile" => Some(("astronauts".to_owned(), None)),
+                _ => None,
+            });
+        let result = formatter.parse(None);
+        let mut result_iter = result.iter();
+        match_next!(result_iter, "us-east-1", region_style);
+        match_next!(result_iter, " ", group_style);
+        match_next!(result_iter, "astronauts", group_style);
+        assert!(result_iter.next().is_none());
+    }
+
+    #[test]
+    fn test_value_style_with_modifiers() {
+        const FORMAT_STR: &str = "${region:pad(6)}";
+        let region_style = Some(Color::Green.normal());
+
+        let formatter = StringFormatter::new(FORMAT_STR)
+            .unwrap()
+            .map_with_style(|variable| match variable {
+                "region" => Some(("eu".to_owned(), region_style)),
+                _ => None,
+            });
+        let result = formatter.parse(Some(Color::Red.normal()));
+        let value: String = result
+            .iter()
+            .map(|segment| segment.value.as_str())
+            .collect();
+        assert_eq!(value, "eu    ");
+        assert_eq!(result[0].style, region_style);
+    }
+
+    #[test]
+    fn test_pad_short_value() {
+        const FORMAT_STR: &str = "${var:pad(6)}|${var:pad(-6)}";
+
+        let formatter = StringFormatter::new(FORMAT_STR)
+            .unwrap()
+            .map(|variable| match variable {
+                "var" => Some("text".to_owned()),
+                _ => None,
+            });
+        let result = formatter.parse(None);
+        let mut result_iter = result.iter();
+        match_next!(result_iter, "text  ", None);
+        match_next!(result_iter, "|", None);
+        match_next!(result_iter, "  text", None);
+    }
+
+    #[test]
+    fn test_pad_wide_chars() {
+        const FORMAT_STR: &str = "${var:pad(5)}";
+
+        let formatter = StringFormatter::new(FORMAT_STR)
+            .unwrap()
+            .map(|variable| match variable {
+                "var" => Some("강남".to_owned()),
+                _ => None,
+            });
+        let result = formatter.parse(None);
+        let mut result_iter = result.iter();
+        match_next!(result_iter, "강남 ", None);
+    }
+
+    #[test]
+    fn test_pad_long_value() {
+        const FORMAT_STR: &str = "${var:pad(3)}";
+
+        let formatter = StringFormatter::new(FORMAT_STR)
+            .unwrap()
+            .map(|variable| match variable {
+                "var" => Some("long text".to_owned()),
+                _ => None,
+            });
+        let result = formatter.parse(None);
+        let mut result_iter = result.iter();
+        match_next!(result_iter, "long text", None);
+    }
+
+    #[test]
+    fn test_pad_huge_width() {
+        let segments = vec![_new_segment("var".to_owned(), "abc".to_owned(), None)];
+        let padded = _pad(segments.clone(), 1_000_000_000);
+        assert_eq!(padded[0].value.len(), MAX_PAD_WIDTH as usize);
+        assert!(padded[0].value.starts_with("abc"));
+
+        let padded = _pad(segments, i64::MIN);
+        assert_eq!(padded[0].value.len(), MAX_PAD_WIDTH as usize);
+        assert!(padded[0].value.ends_with("abc"));
+    }
+
+    #[test]
+    fn test_pad_styled_segments() {
+        const FORMAT_STR: &str = "${var:pad(-8)}";
+        let styled_style = Some(Color::Green.italic());
+
+        let formatter = StringFormatter::new(FORMAT_STR)
+            .unwrap()
+            .map_variables_to_segments(|variable| match variable {
+                "var" => Some(vec![
+                    _new_segment("_1".to_owned(), "abc".to_owned(), styled_style),
+                    _new_segment("_2".to_owned(), "de".to_owned(), None),
+                ]),
+                _ => None,
+            });
+        let result = formatter.parse(None);
+        let mut result_iter = result.iter();
+        match_next!(result_iter, "   abc", styled_style);
+        match_next!(result_iter, "de", None);
+    }
+
+    #[test]
+    fn test_default_for_present_variable() {
+        const FORMAT_STR: &str = "${region:-unknown}";
+
+        let formatter = StringFormatter::new(FORMAT_STR)
+            .unwrap()
+            .map(|variable| match variable {
+                "region" => Some("us-east-1".to_owned()),
+                _ => None,
+            });
+        let result = formatter.parse(None);
+        let mut result_iter = result.iter();
+        match_next!(result_iter, "us-east-1", None);
+    }
+
+    #[test]
+    fn test_default_for_absent_variable() {
+        const FORMAT_STR: &str = "[${region:-unknown}](red)";
+
+        let formatter = StringFormatter::new(FORMAT_STR).unwrap().map(empty_mapper);
+        let result = formatter.parse(None);
+        let mut result_iter = result.iter();
+        match_next!(result_iter, "unknown", Some(Color::Red.normal()));
+    }
+
+    #[test]
+    fn test_default_for_empty_variable() {
+        const FORMAT_STR: &str = "${region:-}";
+
+        let formatter = StringFormatter::new(FORMAT_STR)
+            .unwrap()
+            .map(|_| Some(String::new()));
+        let result = formatter.parse(None);
+        let mut result_iter = result.iter();
+        match_next!(result_iter, "", None);
+    }
+
+    #[test]
+    fn test_default_with_escapes_and_modifiers() {
+        const FORMAT_STR: &str = r"${region:pad(8):-{x\}\\}";
+
+        let formatter = StringFormatter::new(FORMAT_STR).unwrap().map(empty_mapper);
+        let result = formatter.parse(None);
+        let mut result_iter = result.iter();
+        match_next!(result_iter, r"{x}\    ", None);
+    }
+
+    #[test]
+    fn test_default_with_each_escaped_char() {
+        for escaped in &["$", "(", ")", "[", "]", "}", "\\"] {
+            let format = format!("${{var:-a\\{}b}}", escaped);
+            let formatter = StringFormatter::new(&format).unwrap().map(empty_mapper);
+            let result = formatter.parse(None);
+            let mut result_iter = result.iter();
+            assert_eq!(result_iter.next().unwrap().value, format!("a{}b", escaped));
+        }
+    }
+
+    #[test]
+    fn test_default_keeps_unknown_escapes() {
+        const FORMAT_STR: &str = r"${dir:-C:\new\tmp}";
+
+        let formatter = StringFormatter::new(FORMAT_STR).unwrap().map(empty_mapper);
+        let result = formatter.parse(None);
+        let mut result_iter = result.iter();
+        match_next!(result_iter, r"C:\new\tmp", None);
+    }
+
+    #[test]
+    fn test_number_locales() {
+        const FORMAT_STR: &str =
+            "${var:number} ${var:number(de)} ${var:number(ch)} ${var:number(in)} ${var:number(fr)}";
+
+        let formatter = StringFormatter::new(FORMAT_STR)
+            .unwrap()
+            .map(|variable| match variable {
+                "var" => Some("1234567.25".to_owned()),
+                _ => None,
+            });
+        let result = formatter.parse(None);
+        let values: Vec<&str> = result
+            .iter()
+            .map(|segment| segment.value.as_str())
+            .collect();
+        assert_eq!(
+            values,
+            vec![
+                "1,234,567.25",
+                " ",
+                "1.234.567,25",
+                " ",
+                "1'234'567.25",
+                " ",
+                "12,34,567.25",
+                " ",
+                "1\u{202f}234\u{202f}567,25",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_bytes() {
+        const FORMAT_STR: &str =
+            "${size:bytes}|${size:bytes(binary)}|${size:bytes(decimal)}|${name:bytes}";
+
+        let formatter = StringFormatter::new(FORMAT_STR)
+            .unwrap()
+            .map(|variable| match variable {
+                "size" => Some("1536".to_owned()),
+                "name" => Some("rocket".to_owned()),
+                _ => None,
+            });
+        let result = formatter.parse(None);
+        let mut result_iter = result.iter().filter(|segment| segment.value != "|");
+        match_next!(result_iter, "1.5 KiB", None);
+        match_next!(result_iter, "1.5 KiB", None);
+        match_next!(result_iter, "1.5 kB", None);
+        match_next!(result_iter, "rocket", None);
+    }
+
+    #[test]
+    fn test_version_components() {
+        const FORMAT_STR: &str = "${raw:3} ${raw:1} ${tag:2}";
+
+        let formatter = StringFormatter::new(FORMAT_STR)
+            .unwrap()
+            .map(|variable| match variable {
+                "raw" => Some("1.2".to_owned()),
+                "tag" => Some("1.2.3-beta".to_owned()),
+                _ => None,
+            });
+        let result = formatter.parse(None);
+        let mut result_iter = result.iter().filter(|segment| segment.value != " ");
+        match_next!(result_iter, "1.2.0", None);
+        match_next!(result_iter, "1", None);
+        match_next!(result_iter, "1.2.3-beta", None);
+    }
+
     #[test]
     fn test_parse_error() {
         // brackets without escape
@@ -322,5 +857,166 @@ mod tests {
             const FORMAT_STR: &str = "$ ";
             assert!(StringFormatter::new(FORMAT_STR).is_err());
         }
+        // Unclosed modifier list
+        {
+            const FORMAT_STR: &str = "${var:pad(3)";
+            assert!(StringFormatter::new(FORMAT_STR).is_err());
+        }
+    }
+
+    #[test]
+    fn test_map_with_variables() {
+        const FORMAT_STR: &str = "$symbol$region";
+        let symbols: BTreeMap<&str, &str> = vec![("us-east-1", "🇺🇸 "), ("eu-west-1", "🇪🇺 ")]
+            .into_iter()
+            .collect();
+
+        let render = |region: &'static str| {
+            StringFormatter::new(FORMAT_STR)
+                .unwrap()
+                .map(|variable| match variable {
+                    "region" => Some(region.to_string()),
+                    _ => None,
+                })
+                .map_with_variables(|variable, resolved| match variable {
+                    "symbol" => symbols
+                        .get(resolved.get("region")?.as_str())
+                        .map(|symbol| (*symbol).to_string()),
+                    _ => None,
+                })
+                .parse(None)
+                .iter()
+                .map(|segment| segment.value.clone())
+                .collect::<String>()
+        };
+
+        assert_eq!(render("us-east-1"), "🇺🇸 us-east-1");
+        assert_eq!(render("eu-west-1"), "🇪🇺 eu-west-1");
+        assert_eq!(render("ap-south-1"), "ap-south-1");
+    }
+
+    #[test]
+    fn test_map_with_variables_keeps_mapped_values() {
+        const FORMAT_STR: &str = "$var1 $var2";
+        let formatter = StringFormatter::new(FORMAT_STR)
+            .unwrap()
+            .map(|variable| match variable {
+                "var1" => Some("one".to_string()),
+                _ => None,
+            })
+            .map_with_variables(|variable, resolved| match variable {
+                "var2" => Some(format!("after {}", resolved.get("var1")?)),
+                _ => Some("overwritten".to_string()),
+            });
+        let result = formatter.parse(None);
+        let mut result_iter = result.iter();
+        match_next!(result_iter, "one", None);
+        match_next!(result_iter, " ", None);
+        match_next!(result_iter, "after one", None);
+    }
+
+    #[test]
+    fn test_meta_follows_data() {
+        const FORMAT_STR: &str = "on [$symbol$region](yellow)";
+
+        let render = |region: &'static str| {
+            StringFormatter::new(FORMAT_STR)
+                .unwrap()
+                .map(|variable| match variable {
+                    "region" => Some(region.to_string()),
+                    _ => None,
+                })
+                .map_meta(|variable| match variable {
+                    "symbol" => Some("☁️ ".to_string()),
+                    _ => None,
+                })
+                .parse(None)
+                .iter()
+                .map(|segment| segment.value.clone())
+                .collect::<String>()
+        };
+
+        assert_eq!(render("us-east-1"), "on ☁️ us-east-1");
+        assert_eq!(render(""), "on ");
+    }
+
+    #[test]
+    fn test_meta_follows_any_sibling() {
+        const FORMAT_STR: &str = "[$symbol$profile[\\($region\\)](bold)](yellow)";
+
+        let render = |profile: &'static str, region: &'static str| {
+            StringFormatter::new(FORMAT_STR)
+                .unwrap()
+                .map(|variable| match variable {
+                    "profile" => Some(profile.to_string()),
+                    "region" => Some(region.to_string()),
+                    _ => None,
+                })
+                .map_meta(|variable| match variable {
+                    "symbol" => Some("☁️ ".to_string()),
+                    _ => None,
+                })
+                .parse(None)
+                .iter()
+                .map(|segment| segment.value.clone())
+                .collect::<String>()
+        };
+
+        assert_eq!(render("dev", ""), "☁️ dev()");
+        assert_eq!(render("", "us-east-1"), "☁️ (us-east-1)");
+        assert_eq!(render("", ""), "()");
+    }
+
+    #[test]
+    fn test_meta_without_data_variables() {
+        const FORMAT_STR: &str = "[$symbol](red) $var";
+        let formatter = StringFormatter::new(FORMAT_STR)
+            .unwrap()
+            .map(empty_mapper)
+            .map_meta(|variable| match variable {
+                "symbol" => Some("❯".to_string()),
+                _ => None,
+            });
+        let result = formatter.parse(None);
+        let mut result_iter = result.iter();
+        match_next!(result_iter, "❯", Some(Color::Red.normal()));
+        match_next!(result_iter, " ", None);
+    }
+
+    #[test]
+    fn test_fill() {
+        const FORMAT_STR: &str = "$dir[$fill](red)$time";
+        let formatter = StringFormatter::new(FORMAT_STR)
+            .unwrap()
+            .map(|variable| match variable {
+                "dir" => Some("~/rocket".to_string()),
+                "time" => Some("12:00".to_string()),
+                _ => None,
+            });
+        let result = formatter.parse(None);
+        let mut result_iter = result.iter();
+        match_next!(result_iter, "~/rocket", None);
+        let fill = result_iter.next().unwrap();
+        assert_eq!(fill._name, FILL_SEGMENT);
+        assert_eq!(fill.value, "");
+        assert_eq!(fill.style, Some(Color::Red.normal()));
+        match_next!(result_iter, "12:00", None);
+        assert!(result_iter.next().is_none());
+    }
+
+    #[test]
+    fn test_fill_mapped_as_variable() {
+        const FORMAT_STR: &str = "$fill";
+        let formatter = StringFormatter::new(FORMAT_STR)
+            .unwrap()
+            .map(|variable| match variable {
+                "fill" => Some("fuel".to_string()),
+                _ => None,
+            });
+        let result = formatter.parse(None);
+        let mut result_iter = result.iter();
+        let segment = result_iter.next().unwrap();
+        assert_eq!(segment._name, "fill");
+        assert_eq!(segment.value, "fuel");
     }
 }
@@ -0,0 +1,102 @@
+/// Normalize a dotted version such as `1.2` or `v1.2.3.4` to exactly `components`
+/// numeric components, zero-filling missing ones and dropping extra ones.
+///
+/// Versions with anything else than numeric components after an optional leading
+/// `v`, e.g. `1.2.3-beta`, are returned untouched.
+pub fn normalize_components(version: &str, components: usize) -> String {
+    let (prefix, numbers) = if version.starts_with('v') {
+        version.split_at(1)
+    } else {
+        ("", version)
+    };
+
+    let parts: Vec<&str> = numbers.split('.').collect();
+    let is_numeric = |part: &&str| !part.is_empty() && part.bytes().all(|b| b.is_ascii_digit());
+    if components == 0 || !parts.iter().all(is_numeric) {
+        return version.to_string();
+    }
+
+    let normalized: Vec<&str> = parts
+        .into_iter()
+        .chain(std::iter::repeat("0"))
+        .take(components)
+        .collect();
+    format!("{}{}", prefix, normalized.join("."))
+}
+
+/// Format the version printed by a tool as `v<version>`, without doubling the `v`
+/// of tools already printing one, e.g. both `1.2.3` and `v1.2.3` become `v1.2.3`.
+pub fn format_version(raw: &str) -> String {
+    format!("v{}", strip_version_prefix(raw.trim(), "v"))
+}
+
+/// Strip `prefix` from `version` only when a dotted version number directly follows
+/// it, so that `v1.2.3` loses its `v` but names such as `v8` are kept whole.
+pub fn strip_version_prefix<'a>(version: &'a str, prefix: &str) -> &'a str {
+    if prefix.is_empty() || !version.starts_with(prefix) {
+        return version;
+    }
+
+    let rest = &version[prefix.len()..];
+    if starts_with_dotted_number(rest) {
+        rest
+    } else {
+        version
+    }
+}
+
+fn starts_with_dotted_number(text: &str) -> bool {
+    let digits = text.bytes().take_while(u8::is_ascii_digit).count();
+    digits > 0
+        && text[digits..].starts_with('.')
+        && text[digits + 1..]
+            .bytes()
+            .next()
+            .map_or(false, |b| b.is_ascii_digit())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_format_version() {
+        assert_eq!(format_version("v1.2.3"), "v1.2.3");
+        assert_eq!(format_version("1.2.3\n"), "v1.2.3");
+        assert_eq!(format_version("8"), "v8");
+    }
+
+    #[test]
+    fn test_strip_version_prefix() {
+        assert_eq!(strip_version_prefix("v1.2.3", "v"), "1.2.3");
+        assert_eq!(strip_version_prefix("1.2.3", "v"), "1.2.3");
+        assert_eq!(strip_version_prefix("release-0.19.1", "release-"), "0.19.1");
+        assert_eq!(strip_version_prefix("v8", "v"), "v8");
+        assert_eq!(strip_version_prefix("v1.", "v"), "v1.");
+        assert_eq!(strip_version_prefix("vendor", "v"), "vendor");
+        assert_eq!(strip_version_prefix("v1.2", ""), "v1.2");
+        assert_eq!(strip_version_prefix("", "v"), "");
+    }
+
+    #[test]
+    fn test_pad_components() {
+        assert_eq!(normalize_components("1.2", 3), "1.2.0");
+        assert_eq!(normalize_components("1", 3), "1.0.0");
+        assert_eq!(normalize_components("v12.0", 4), "v12.0.0.0");
+    }
+
+    #[test]
+    fn test_truncate_components() {
+        assert_eq!(normalize_components("1.2.3.4", 3), "1.2.3");
+        assert_eq!(normalize_components("v1.2.3", 2), "v1.2");
+        assert_eq!(normalize_components("1.2.3", 3), "1.2.3");
+    }
+
+    #[test]
+    fn test_non_numeric_untouched() {
+        assert_eq!(normalize_components("1.2.3-beta", 2), "1.2.3-beta");
+        assert_eq!(normalize_components("1.2rc1", 3), "1.2rc1");
+        assert_eq!(normalize_components("", 3), "");
+        assert_eq!(normalize_components("1.2", 0), "1.2");
+    }
+}
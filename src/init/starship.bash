@@ -78,3 +78,7 @@ fi
 # Set up the start time and STARSHIP_SHELL, which controls shell-specific sequences
 STARSHIP_START_TIME=$(::STARSHIP:: time)
 export STARSHIP_SHELL="bash"
+
+# Set up a session key, which identifies the state kept between prompts of this shell
+STARSHIP_SESSION_KEY="$RANDOM$RANDOM$RANDOM$RANDOM$RANDOM0000000000000000"
+export STARSHIP_SESSION_KEY=${STARSHIP_SESSION_KEY:0:16}
@@ -16,3 +16,6 @@ set VIRTUAL_ENV_DISABLE_PROMPT 1
 
 function fish_mode_prompt; end
 export STARSHIP_SHELL="fish"
+
+# Set up a session key, which identifies the state kept between prompts of this shell
+set -gx STARSHIP_SESSION_KEY (random 10000000000000 9999999999999999)
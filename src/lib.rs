@@ -6,8 +6,10 @@ pub mod config;
 pub mod configs;
 pub mod context;
 pub mod formatter;
+pub mod logger;
 pub mod module;
 pub mod modules;
 pub mod print;
 pub mod segment;
+pub mod session;
 mod utils;
@@ -0,0 +1,54 @@
+use log::{LevelFilter, Log, Metadata, Record};
+use std::env;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Number of warnings logged by this process, whether they were printed or not
+static WARNING_COUNT: AtomicUsize = AtomicUsize::new(0);
+
+/// Prints log records like `pretty_env_logger`, as filtered by `RUST_LOG`, while
+/// counting all the warnings, so that they can be hinted at in the next prompt
+struct WarningCountingLogger {
+    inner: Box<dyn Log>,
+}
+
+impl Log for WarningCountingLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= LevelFilter::Warn || self.inner.enabled(metadata)
+    }
+
+    fn log(&self, record: &Record) {
+        if record.level() <= LevelFilter::Warn {
+            WARNING_COUNT.fetch_add(1, Ordering::Relaxed);
+        }
+        if self.inner.enabled(record.metadata()) {
+            self.inner.log(record);
+        }
+    }
+
+    fn flush(&self) {
+        self.inner.flush();
+    }
+}
+
+/// Initializes the global logger
+pub fn init() {
+    let mut builder = pretty_env_logger::formatted_builder();
+    if let Ok(filters) = env::var("RUST_LOG") {
+        builder.parse_filters(&filters);
+    }
+    let inner = builder.build();
+
+    // Warnings have to reach the logger to be counted, even if they aren't printed
+    let max_level = inner.filter().max(LevelFilter::Warn);
+    let logger = WarningCountingLogger {
+        inner: Box::new(inner),
+    };
+    if log::set_boxed_logger(Box::new(logger)).is_ok() {
+        log::set_max_level(max_level);
+    }
+}
+
+/// Returns the number of warnings logged so far
+pub fn warning_count() -> usize {
+    WARNING_COUNT.load(Ordering::Relaxed)
+}
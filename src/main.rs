@@ -12,17 +12,19 @@ mod configure;
 mod context;
 mod formatter;
 mod init;
+mod logger;
 mod module;
 mod modules;
 mod print;
 mod segment;
+mod session;
 mod utils;
 
 use crate::module::ALL_MODULES;
 use clap::{App, AppSettings, Arg, SubCommand};
 
 fn main() {
-    pretty_env_logger::init();
+    logger::init();
 
     let status_code_arg = Arg::with_name("status_code")
         .short("s")
@@ -67,6 +69,13 @@ fn main() {
         .help("The number of currently running jobs")
         .takes_value(true);
 
+    let width_arg = Arg::with_name("width")
+        .short("w")
+        .long("width")
+        .value_name("WIDTH")
+        .help("The width of the terminal, instead of detecting it")
+        .takes_value(true);
+
     let init_scripts_arg = Arg::with_name("print_full_init")
         .long("print-full-init")
         .help("Print the main initialization script (as opposed to the init stub)");
@@ -93,7 +102,8 @@ fn main() {
                     .arg(&path_arg)
                     .arg(&cmd_duration_arg)
                     .arg(&keymap_arg)
-                    .arg(&jobs_arg),
+                    .arg(&jobs_arg)
+                    .arg(&width_arg),
             )
             .subcommand(
                 SubCommand::with_name("module")
@@ -114,7 +124,8 @@ fn main() {
                     .arg(&path_arg)
                     .arg(&cmd_duration_arg)
                     .arg(&keymap_arg)
-                    .arg(&jobs_arg),
+                    .arg(&jobs_arg)
+                    .arg(&width_arg),
             )
             .subcommand(SubCommand::with_name("configure").about("Edit the starship configuration"))
             .subcommand(SubCommand::with_name("bug-report").about(
@@ -126,7 +137,9 @@ fn main() {
                     .settings(&[AppSettings::Hidden]),
             )
             .subcommand(
-                SubCommand::with_name("explain").about("Explains the currently showing modules"),
+                SubCommand::with_name("explain")
+                    .about("Explains the currently showing modules")
+                    .arg(&width_arg),
             )
             .get_matches();
 
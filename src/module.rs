@@ -1,5 +1,5 @@
 use crate::config::SegmentConfig;
-use crate::context::Shell;
+use crate::context::{ColorMode, Shell};
 use crate::segment::Segment;
 use crate::utils::wrap_colorseq_for_shell;
 use ansi_term::Style;
@@ -10,10 +10,12 @@ use std::fmt;
 // Keep these ordered alphabetically.
 // Default ordering is handled in configs/mod.rs
 pub const ALL_MODULES: &[&str] = &[
+    "ansible",
     "aws",
     #[cfg(feature = "battery")]
     "battery",
     "character",
+    "cloudflare",
     "cmd_duration",
     "conda",
     "directory",
@@ -21,11 +23,16 @@ pub const ALL_MODULES: &[&str] = &[
     "dotnet",
     "elixir",
     "elm",
+    "env_badge",
     "env_var",
+    "file_age",
+    "fill",
+    "flutter",
     "git_branch",
     "git_commit",
     "git_state",
     "git_status",
+    "gleam",
     "golang",
     "haskell",
     "hg_branch",
@@ -34,16 +41,21 @@ pub const ALL_MODULES: &[&str] = &[
     "jobs",
     "julia",
     "kubernetes",
+    "last_warnings",
     "line_break",
     "memory_usage",
     "nix_shell",
     "nodejs",
+    "ocaml",
     "package",
     "python",
+    "raku",
     "ruby",
     "crystal",
     "rust",
     "php",
+    "pulumi",
+    "terminal_title",
     "terraform",
     "singularity",
     "time",
@@ -73,6 +85,9 @@ pub struct Module<'a> {
 
     /// The suffix used to separate the current module from the next one.
     suffix: Affix,
+
+    /// Whether the module is rendered with its styles or as plain text.
+    color_mode: ColorMode,
 }
 
 impl<'a> Module<'a> {
@@ -86,6 +101,7 @@ impl<'a> Module<'a> {
             prefix: Affix::default_prefix(name),
             segments: Vec::new(),
             suffix: Affix::default_suffix(name),
+            color_mode: ColorMode::Enabled,
         }
     }
 
@@ -144,10 +160,22 @@ impl<'a> Module<'a> {
         self
     }
 
+    /// Sets the style of all the segments already in the module, replacing their own styles.
+    pub fn override_segment_styles(&mut self, style: Style) {
+        for segment in &mut self.segments {
+            segment.set_style(style);
+        }
+    }
+
+    /// Sets whether the module is rendered with its styles or as plain text.
+    pub fn set_color_mode(&mut self, color_mode: ColorMode) {
+        self.color_mode = color_mode;
+    }
+
     /// Returns a vector of colored ANSIString elements to be later used with
     /// `ANSIStrings()` to optimize ANSI codes
     pub fn ansi_strings(&self) -> Vec<ANSIString> {
-        self.ansi_strings_for_shell(Shell::Unknown)
+        self.ansi_strings_for_shell(Shell::Unknown(String::new()))
     }
 
     pub fn ansi_strings_for_shell(&self, shell: Shell) -> Vec<ANSIString> {
@@ -160,6 +188,13 @@ impl<'a> Module<'a> {
         ansi_strings.insert(0, self.prefix.ansi_string());
         ansi_strings.push(self.suffix.ansi_string());
 
+        if self.color_mode == ColorMode::Disabled {
+            ansi_strings = ansi_strings
+                .iter()
+                .map(|ansi| ANSIString::from(String::from(&**ansi)))
+                .collect();
+        }
+
         ansi_strings = match shell {
             Shell::Bash => ansi_strings_modified(ansi_strings, shell),
             Shell::Zsh => ansi_strings_modified(ansi_strings, shell),
@@ -185,7 +220,7 @@ fn ansi_strings_modified(ansi_strings: Vec<ANSIString>, shell: Shell) -> Vec<ANS
     ansi_strings
         .into_iter()
         .map(|ansi| {
-            let wrapped = wrap_colorseq_for_shell(ansi.to_string(), shell);
+            let wrapped = wrap_colorseq_for_shell(ansi.to_string(), shell.clone());
             ANSIString::from(wrapped)
         })
         .collect::<Vec<ANSIString>>()
@@ -240,6 +275,11 @@ impl Affix {
         self
     }
 
+    /// Gets the value of the affix.
+    pub fn get_value(&self) -> &str {
+        &self.value
+    }
+
     /// Generates the colored ANSIString output.
     pub fn ansi_string(&self) -> ANSIString {
         self.style.paint(&self.value)
@@ -268,6 +308,7 @@ mod tests {
             prefix: Affix::default_prefix(name),
             segments: Vec::new(),
             suffix: Affix::default_suffix(name),
+            color_mode: ColorMode::Enabled,
         };
 
         assert!(module.is_empty());
@@ -285,6 +326,7 @@ mod tests {
             prefix: Affix::default_prefix(name),
             segments: vec![Segment::new("test_segment")],
             suffix: Affix::default_suffix(name),
+            color_mode: ColorMode::Enabled,
         };
 
         assert!(module.is_empty());
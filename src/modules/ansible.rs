@@ -0,0 +1,133 @@
+use super::{Context, Module, RootModuleConfig};
+
+use crate::configs::ansible::AnsibleConfig;
+use crate::formatter::version::format_version;
+
+/// Creates a module with the current Ansible core version
+///
+/// Will display the Ansible version if any of the following criteria are met:
+///     - Current directory contains an `ansible.cfg` or `playbook.yml` file
+///     - Current directory contains a `roles` directory
+///
+/// With `show_config`, the config file set in `ANSIBLE_CONFIG` is displayed as well.
+pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
+    let is_ansible_project = context
+        .try_begin_scan()?
+        .set_files(&["ansible.cfg", "playbook.yml"])
+        .set_folders(&["roles"])
+        .is_match();
+
+    if !is_ansible_project {
+        return None;
+    }
+
+    let ansible_stdout = context
+        .exec_cmd("ansible", "ansible", &["--version"])?
+        .stdout;
+    let ansible_version = parse_ansible_version(&ansible_stdout)?;
+
+    let mut module = context.new_module("ansible");
+    let config = AnsibleConfig::try_load(module.config);
+    module.set_style(config.style);
+
+    module.create_segment("symbol", &config.symbol);
+    module.create_segment(
+        "version",
+        &config.version.with_value(&format_version(&ansible_version)),
+    );
+
+    if config.show_config {
+        if let Some(ansible_config) = context.get_env("ANSIBLE_CONFIG").filter(|c| !c.is_empty()) {
+            module.create_segment(
+                "config",
+                &config.config.with_value(&format!(" ({})", ansible_config)),
+            );
+        }
+    }
+
+    Some(module)
+}
+
+fn parse_ansible_version(ansible_stdout: &str) -> Option<String> {
+    // ansible --version output looks like this:
+    // ansible [core 2.15.0]
+    //   config file = /etc/ansible/ansible.cfg
+    // Before ansible-core 2.11, the first line looks like `ansible 2.9.6` instead
+    let first_line = ansible_stdout.lines().next()?.trim();
+    if !first_line.starts_with("ansible ") {
+        return None;
+    }
+    let version = first_line.trim_start_matches("ansible ").trim();
+    let version = if version.starts_with("[core ") {
+        if !version.ends_with(']') {
+            return None;
+        }
+        version
+            .trim_start_matches("[core ")
+            .trim_end_matches(']')
+            .trim()
+    } else {
+        version
+    };
+
+    if version.is_empty() {
+        return None;
+    }
+    Some(version.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::modules::utils::test::render_module;
+    use ansi_term::Color;
+    use std::fs::{self, File};
+    use std::io;
+
+    #[test]
+    fn folder_without_ansible_files() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let actual = render_module("ansible", dir.path());
+        let expected = None;
+        assert_eq!(expected, actual);
+        dir.close()
+    }
+
+    #[test]
+    fn folder_with_ansible_cfg() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        File::create(dir.path().join("ansible.cfg"))?.sync_all()?;
+        let actual = render_module("ansible", dir.path());
+        let expected = Some(format!("via {} ", Color::White.bold().paint("🅰 v2.15.0")));
+        assert_eq!(expected, actual);
+        dir.close()
+    }
+
+    #[test]
+    fn folder_with_roles() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        fs::create_dir(dir.path().join("roles"))?;
+        let actual = render_module("ansible", dir.path());
+        let expected = Some(format!("via {} ", Color::White.bold().paint("🅰 v2.15.0")));
+        assert_eq!(expected, actual);
+        dir.close()
+    }
+
+    #[test]
+    fn test_parse_ansible_version() {
+        let input = "\
+ansible [core 2.15.0]
+  config file = /etc/ansible/ansible.cfg
+  configured module search path = ['/home/user/.ansible/plugins/modules']
+  python version = 3.11.3 (main, Apr  5 2023, 00:00:00) [GCC 13.0.1]
+  jinja version = 3.1.2
+  libyaml = True";
+        assert_eq!(parse_ansible_version(input), Some("2.15.0".to_string()));
+
+        let input = "ansible 2.9.6\n  config file = /etc/ansible/ansible.cfg";
+        assert_eq!(parse_ansible_version(input), Some("2.9.6".to_string()));
+
+        assert_eq!(parse_ansible_version("ansible [core 2.15.0"), None);
+        assert_eq!(parse_ansible_version("Unexpected output"), None);
+    }
+}
@@ -1,82 +1,211 @@
 use std::collections::HashMap;
-use std::env;
-use std::fs::File;
-use std::io::{BufRead, BufReader};
-use std::path::PathBuf;
+use std::fs;
+use std::path::{Path, PathBuf};
 use std::str::FromStr;
+use std::time::{Duration, SystemTime};
 
-use dirs::home_dir;
+use ansi_term::Style;
+use chrono::{DateTime, Utc};
+use regex::Regex;
 
-use super::{Context, Module, RootModuleConfig};
+use super::{Context, Module, RootModuleConfig, SegmentConfig};
 
 use crate::configs::aws::{AwsConfig, AwsItems};
+use crate::formatter::duration::format_duration;
 
 type Profile = String;
 type Region = String;
+type AccountId = String;
 
-fn get_aws_region_from_config(aws_profile: Option<&str>) -> Option<Region> {
-    let config_location = env::var("AWS_CONFIG_FILE")
-        .ok()
+/// Reads the AWS config file, `~/.aws/config` unless `AWS_CONFIG_FILE` points elsewhere
+fn read_aws_config(context: &Context) -> Option<String> {
+    let config_location = context
+        .get_env("AWS_CONFIG_FILE")
         .and_then(|path| PathBuf::from_str(&path).ok())
         .or_else(|| {
-            let mut home = home_dir()?;
+            let mut home = context.get_home()?;
             home.push(".aws/config");
             Some(home)
         })?;
+    crate::utils::read_file(&config_location).ok()
+}
+
+fn get_aws_region_from_config(context: &Context, aws_profile: Option<&str>) -> Option<Region> {
+    let config = read_aws_config(context)?;
 
-    let file = File::open(&config_location).ok()?;
-    let reader = BufReader::new(file);
-    let lines = reader.lines().filter_map(Result::ok);
+    let section = get_profile_section(aws_profile);
+
+    // Profiles using AWS SSO may only configure a region for their `sso-session`
+    get_config_value(&config, &section, "region").or_else(|| {
+        let sso_session = get_config_value(&config, &section, "sso_session")?;
+        get_config_value(
+            &config,
+            &format!("sso-session {}", sso_session),
+            "sso_region",
+        )
+    })
+}
 
-    let region_line = if let Some(ref aws_profile) = aws_profile {
-        lines
-            .skip_while(|line| line != &format!("[profile {}]", aws_profile))
-            .skip(1)
-            .take_while(|line| !line.starts_with('['))
-            .find(|line| line.starts_with("region"))
+/// Returns the name of the section configuring `aws_profile` in an AWS config file
+fn get_profile_section(aws_profile: Option<&str>) -> String {
+    match aws_profile {
+        Some(aws_profile) => format!("profile {}", aws_profile),
+        None => "default".to_string(),
+    }
+}
+
+/// Returns the value of `key` in the `[section]` of an AWS config file
+fn get_config_value(config: &str, section: &str, key: &str) -> Option<String> {
+    let header = format!("[{}]", section);
+    config
+        .lines()
+        .skip_while(|line| line.trim() != header)
+        .skip(1)
+        .take_while(|line| !line.trim_start().starts_with('['))
+        .find_map(|line| {
+            let mut parts = line.splitn(2, '=');
+            if parts.next()?.trim() == key {
+                Some(parts.next()?.trim().to_string())
+            } else {
+                None
+            }
+        })
+}
+
+/// Counts the profiles in an AWS config file, including the default one
+fn count_config_profiles(config: &str) -> usize {
+    config
+        .lines()
+        .map(str::trim)
+        .filter(|line| *line == "[default]" || line.starts_with("[profile "))
+        .count()
+}
+
+/// Returns a hint like `3 profiles` when several profiles are configured, so that
+/// one gets picked before running commands against the default one
+fn get_profile_count_hint(context: &Context, config: &AwsConfig) -> Option<String> {
+    if !config.show_profile_count {
+        return None;
+    }
+
+    let count = count_config_profiles(&read_aws_config(context)?);
+    if count > 1 {
+        Some(format!("{} profiles", count))
     } else {
-        lines
-            .skip_while(|line| line != "[default]")
-            .skip(1)
-            .take_while(|line| !line.starts_with('['))
-            .find(|line| line.starts_with("region"))
-    }?;
-
-    let region = region_line.split('=').nth(1)?;
-    let region = region.trim();
+        None
+    }
+}
 
-    Some(region.to_string())
+/// Returns the active profiles, primary first. Several profiles can be active at once,
+/// e.g. with role chaining, which some tools export as `AWS_PROFILES=base,admin`.
+fn get_aws_profiles(context: &Context) -> Vec<Profile> {
+    context
+        .get_first_env(&["AWS_VAULT", "AWS_PROFILE", "AWS_PROFILES"])
+        .map(|profiles| split_profiles(&profiles))
+        .unwrap_or_default()
+}
+
+fn split_profiles(profiles: &str) -> Vec<Profile> {
+    profiles
+        .split(',')
+        .map(str::trim)
+        .filter(|profile| !profile.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+fn get_aws_profile(context: &Context) -> Option<Profile> {
+    get_aws_profiles(context).into_iter().next()
+}
+
+/// Returns the current profile and region. The region is taken from the env vars
+/// first, unless `prefer_config_region` is set and the profile configures one.
+fn get_aws_profile_and_region(
+    context: &Context,
+    prefer_config_region: bool,
+) -> (Option<Profile>, Option<Region>) {
+    let aws_profile = get_aws_profile(context);
+    if prefer_config_region {
+        if let Some(region) = get_aws_region_from_config(context, aws_profile.as_deref()) {
+            return (aws_profile, Some(region));
+        }
+    }
+
+    let aws_region = get_aws_region_from_env(context)
+        .or_else(|| get_aws_region_from_config(context, aws_profile.as_deref()));
+    (aws_profile, aws_region)
 }
 
-fn get_aws_profile_and_region() -> (Option<Profile>, Option<Region>) {
-    match (
-        env::var("AWS_VAULT")
-            .or_else(|_| env::var("AWS_PROFILE"))
-            .ok(),
-        env::var("AWS_REGION").ok(),
-        env::var("AWS_DEFAULT_REGION").ok(),
-    ) {
-        (Some(p), Some(_), Some(dr)) => (Some(p), Some(dr)),
-        (Some(p), Some(r), None) => (Some(p), Some(r)),
-        (None, Some(r), None) => (None, Some(r)),
-        (Some(p), None, Some(dr)) => (Some(p), Some(dr)),
-        (Some(ref p), None, None) => (Some(p.to_owned()), get_aws_region_from_config(Some(p))),
-        (None, None, Some(dr)) => (None, Some(dr)),
-        (None, Some(_), Some(dr)) => (None, Some(dr)),
-        (None, None, None) => (None, get_aws_region_from_config(None)),
+fn get_aws_region(context: &Context, prefer_config_region: bool) -> Option<Region> {
+    if prefer_config_region {
+        if let Some(region) =
+            get_aws_region_from_config(context, get_aws_profile(context).as_deref())
+        {
+            return Some(region);
+        }
     }
+
+    get_aws_region_from_env(context).or_else(|| get_aws_region_from_config(context, None))
 }
 
-fn get_aws_region() -> Option<Region> {
-    match (
-        env::var("AWS_REGION").ok(),
-        env::var("AWS_DEFAULT_REGION").ok(),
-    ) {
-        (Some(r), None) => Some(r),
-        (None, Some(dr)) => Some(dr),
-        (Some(_), Some(dr)) => Some(dr),
-        (None, None) => get_aws_region_from_config(None),
+/// `AWS_DEFAULT_REGION` takes precedence over `AWS_REGION` when both are set
+fn get_aws_region_from_env(context: &Context) -> Option<Region> {
+    context.get_first_env(&["AWS_DEFAULT_REGION", "AWS_REGION"])
+}
+
+/// The base URL of the EC2 instance metadata service, which the AWS SDKs let
+/// `AWS_EC2_METADATA_SERVICE_ENDPOINT` override
+const IMDS_BASE_URL: &str = "http://169.254.169.254";
+const IMDS_TIMEOUT: Duration = Duration::from_millis(100);
+
+/// Returns the region of the EC2 instance starship runs on, as a last resort when
+/// `query_imds` is set. Off EC2 the metadata service can't be reached, so it is only
+/// given `IMDS_TIMEOUT` to answer.
+fn get_aws_region_from_imds(context: &Context, config: &AwsConfig) -> Option<Region> {
+    if !config.query_imds || context.get_env("AWS_EC2_METADATA_DISABLED").as_deref() == Some("true")
+    {
+        return None;
     }
+
+    let base_url = context.get_env_or("AWS_EC2_METADATA_SERVICE_ENDPOINT", IMDS_BASE_URL);
+    query_imds_region(base_url.trim_end_matches('/'), IMDS_TIMEOUT)
+}
+
+/// Queries the metadata service at `base_url` for the region. A session token is
+/// requested first, as instances may require IMDSv2, falling back to IMDSv1 without it.
+#[cfg(feature = "http")]
+fn query_imds_region(base_url: &str, timeout: Duration) -> Option<Region> {
+    let token = attohttpc::put(format!("{}/latest/api/token", base_url))
+        .header("X-aws-ec2-metadata-token-ttl-seconds", "60")
+        .connect_timeout(timeout)
+        .timeout(timeout)
+        .send()
+        .ok()
+        .filter(attohttpc::Response::is_success)
+        .and_then(|response| response.text().ok());
+
+    let mut request = attohttpc::get(format!("{}/latest/meta-data/placement/region", base_url))
+        .connect_timeout(timeout)
+        .timeout(timeout);
+    if let Some(token) = token {
+        request = request.header("X-aws-ec2-metadata-token", token.trim());
+    }
+
+    let response = request
+        .send()
+        .ok()
+        .filter(attohttpc::Response::is_success)?;
+    let region = response.text().ok()?;
+    let region = region.trim();
+    if region.is_empty() {
+        return None;
+    }
+    Some(region.to_string())
+}
+
+#[cfg(not(feature = "http"))]
+fn query_imds_region(_base_url: &str, _timeout: Duration) -> Option<Region> {
+    None
 }
 
 fn alias_region(region: &str, aliases: &HashMap<String, &str>) -> String {
@@ -86,40 +215,427 @@ fn alias_region(region: &str, aliases: &HashMap<String, &str>) -> String {
     }
 }
 
+/// Returns the id of the account in use, taken from `AWS_ACCOUNT_ID` or, failing
+/// that, from the `sso_account_id` of the active profile
+fn get_aws_account_id(context: &Context) -> Option<AccountId> {
+    context
+        .get_env("AWS_ACCOUNT_ID")
+        .filter(|account_id| !account_id.is_empty())
+        .or_else(|| {
+            let config = read_aws_config(context)?;
+            let section = get_profile_section(get_aws_profile(context).as_deref());
+            get_config_value(&config, &section, "sso_account_id")
+        })
+}
+
+/// Returns the most recently modified JSON file in the cache directory `dir`,
+/// with its modification time
+fn get_newest_cache_file(dir: &Path) -> Option<(SystemTime, PathBuf)> {
+    fs::read_dir(dir)
+        .ok()?
+        .filter_map(Result::ok)
+        .filter(|entry| entry.path().extension().map_or(false, |ext| ext == "json"))
+        .filter_map(|entry| Some((entry.metadata().ok()?.modified().ok()?, entry.path())))
+        .max()
+}
+
+/// Returns how long ago the current credentials were issued, going by the newest
+/// credentials cached by the AWS CLI in `~/.aws/cli/cache`, or else the newest SSO
+/// token in `~/.aws/sso/cache`
+fn get_credentials_age(context: &Context, now: SystemTime) -> Option<Duration> {
+    let aws_dir = context.get_home()?.join(".aws");
+    let (issued, _) = get_newest_cache_file(&aws_dir.join("cli/cache"))
+        .or_else(|| get_newest_cache_file(&aws_dir.join("sso/cache")))?;
+
+    // Files modified in the future (clock skew) are treated as brand new
+    Some(now.duration_since(issued).unwrap_or_default())
+}
+
+/// Returns when the current credentials expire, as exported by e.g. aws-vault.
+/// Without a parseable expiration, the credentials are assumed to be valid.
+fn get_credentials_expiration(context: &Context) -> Option<DateTime<Utc>> {
+    let expiration =
+        context.get_first_env(&["AWS_CREDENTIAL_EXPIRATION", "AWS_SESSION_EXPIRATION"])?;
+    DateTime::parse_from_rfc3339(expiration.trim())
+        .ok()
+        .map(|expiration| expiration.with_timezone(&Utc))
+}
+
+/// Returns the style configured for `region`, looked up first by its name and then by its alias
+fn get_region_style(region: &str, config: &AwsConfig) -> Option<Style> {
+    config
+        .region_styles
+        .get(region)
+        .or_else(|| {
+            let alias = config.region_aliases.get(region)?;
+            config.region_styles.get(*alias)
+        })
+        .copied()
+}
+
+/// SSO profiles are often named after the role and account, e.g. `Admin-123456789012`,
+/// so with `strip_account_suffix` set, a trailing dash and 12 digit account id is dropped
+fn format_profile(profile: Profile, config: &AwsConfig) -> Profile {
+    if !config.strip_account_suffix {
+        return profile;
+    }
+
+    let account_suffix = Regex::new(r"-\d{12}$").unwrap();
+    account_suffix.replace(&profile, "").into_owned()
+}
+
+/// Returns the primary profile and, with `show_all_profiles` set, the other active
+/// profiles, each preceded by `profile_separator`
+fn format_profiles(profiles: Vec<Profile>, config: &AwsConfig) -> Option<(Profile, String)> {
+    let mut profiles = profiles
+        .into_iter()
+        .map(|profile| format_profile(profile, config));
+    let primary = profiles.next()?;
+
+    let others = if config.show_all_profiles {
+        profiles
+            .map(|profile| format!("{}{}", config.profile_separator, profile))
+            .collect()
+    } else {
+        String::new()
+    };
+    Some((primary, others))
+}
+
+/// Drop items repeating the text of the previous one, e.g. a profile named after its region
+fn collapse_duplicates(items: &mut Vec<(&str, String)>) {
+    items.dedup_by(|item, previous| item.1 == previous.1);
+}
+
+/// Whether `region`, or its alias, is the configured home region, which is not displayed
+fn is_home_region(region: &str, config: &AwsConfig) -> bool {
+    config.home_region.map_or(false, |home_region| {
+        region == home_region || alias_region(region, &config.region_aliases) == home_region
+    })
+}
+
+/// Whether the current directory matches one of the configured detection criteria.
+/// Without any criteria, the module is shown everywhere.
+fn is_in_aws_directory(context: &Context, config: &AwsConfig) -> Option<bool> {
+    if config.detect_files.is_empty()
+        && config.detect_extensions.is_empty()
+        && config.detect_folders.is_empty()
+    {
+        return Some(true);
+    }
+
+    let is_match = context
+        .try_begin_scan()?
+        .set_files(&config.detect_files)
+        .set_extensions(&config.detect_extensions)
+        .set_folders(&config.detect_folders)
+        .is_match();
+    Some(is_match)
+}
+
 pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     const AWS_PREFIX: &str = "on ";
 
     let mut module = context.new_module("aws");
     let config: AwsConfig = AwsConfig::try_load(module.config);
 
-    module.set_style(config.style);
+    if !is_in_aws_directory(context, &config)? {
+        return None;
+    }
 
     module.get_prefix().set_value(AWS_PREFIX);
 
-    module.create_segment("symbol", &config.symbol);
-    match config.displayed_items {
+    let (items, other_profiles, aws_region) = match config.displayed_items {
         AwsItems::All => {
-            let (aws_profile, aws_region) = get_aws_profile_and_region();
+            let (_, aws_region) = get_aws_profile_and_region(context, config.prefer_config_region);
+            let aws_region = aws_region.or_else(|| get_aws_region_from_imds(context, &config));
+            let (aws_profile, mut others) =
+                match format_profiles(get_aws_profiles(context), &config) {
+                    Some((primary, others)) => (Some(primary), others),
+                    None => (get_profile_count_hint(context, &config), String::new()),
+                };
 
-            let aws_segment = match (&aws_profile, &aws_region) {
-                (None, None) => return None,
-                (Some(p), Some(r)) => format!("{}({})", p, alias_region(r, &config.region_aliases)),
-                (Some(p), None) => p.to_string(),
-                (None, Some(r)) => alias_region(r, &config.region_aliases),
-            };
-            module.create_segment("all", &config.region.with_value(&aws_segment));
+            let aws_region = aws_region.filter(|r| !is_home_region(r, &config));
+
+            let mut items = Vec::new();
+            if let Some(p) = aws_profile {
+                items.push(("profile", p));
+            }
+            if let Some(r) = &aws_region {
+                items.push(("region", alias_region(r, &config.region_aliases)));
+            }
+            collapse_duplicates(&mut items);
+
+            if items.is_empty() {
+                return None;
+            }
+            if !config.symbol_before_each {
+                // The separator is only displayed when both profile and region are
+                let all = match (items.as_slice(), config.separator) {
+                    ([(_, p), (_, r)], Some(separator)) => {
+                        format!("{}{}{}{}", p, others, separator, r)
+                    }
+                    ([(_, p), (_, r)], None) => format!("{}{}({})", p, others, r),
+                    ([("profile", p)], _) => format!("{}{}", p, others),
+                    _ => items[0].1.clone(),
+                };
+                items = vec![("all", all)];
+                others.clear();
+            }
+            (items, others, aws_region)
         }
         AwsItems::Profile => {
-            let aws_profile = env::var("AWS_PROFILE").ok()?;
-
-            module.create_segment("profile", &config.profile.with_value(&aws_profile));
+            let (aws_profile, others) = match context.get_env("AWS_PROFILE") {
+                Some(profiles) => format_profiles(split_profiles(&profiles), &config)?,
+                None => (get_profile_count_hint(context, &config)?, String::new()),
+            };
+            (vec![("profile", aws_profile)], others, None)
         }
         AwsItems::Region => {
-            let aws_region = alias_region(&get_aws_region()?, &config.region_aliases);
-
-            module.create_segment("region", &config.region.with_value(&aws_region));
+            let aws_region = get_aws_region(context, config.prefer_config_region)
+                .or_else(|| get_aws_region_from_imds(context, &config))
+                .filter(|r| !is_home_region(r, &config))?;
+            let aws_segment = alias_region(&aws_region, &config.region_aliases);
+            (
+                vec![("region", aws_segment)],
+                String::new(),
+                Some(aws_region),
+            )
         }
     };
 
+    // Expired credentials stand out, so they can be renewed before a command fails
+    let is_expired =
+        get_credentials_expiration(context).map_or(false, |expiration| expiration <= Utc::now());
+
+    // A style configured for the region takes precedence over the module style
+    let style = if is_expired {
+        config.expired_style
+    } else {
+        aws_region
+            .as_deref()
+            .and_then(|region| get_region_style(region, &config))
+            .unwrap_or(config.style)
+    };
+    module.set_style(style);
+
+    let symbol = if is_expired {
+        &config.expired_symbol
+    } else {
+        &config.symbol
+    };
+
+    for (i, (segment_name, aws_segment)) in items.iter().enumerate() {
+        if i > 0 {
+            let separator = config.separator.unwrap_or(" ");
+            module.create_segment("separator", &SegmentConfig::new(separator));
+        }
+        module.create_segment("symbol", symbol);
+
+        let segment_config = match *segment_name {
+            "profile" => &config.profile,
+            _ => &config.region,
+        };
+        module.create_segment(segment_name, &segment_config.with_value(aws_segment));
+
+        if *segment_name == "profile" && !other_profiles.is_empty() {
+            module.create_segment("profiles", &config.profiles.with_value(&other_profiles));
+        }
+    }
+
+    if config.show_vault_backend {
+        // Set by aws-vault to the keyring backend it got the credentials from
+        if let Some(backend) = context.get_env("AWS_VAULT_BACKEND") {
+            if !backend.is_empty() {
+                module.create_segment(
+                    "vault_backend",
+                    &config.vault_backend.with_value(&format!(" [{}]", backend)),
+                );
+            }
+        }
+    }
+
+    if config.show_account {
+        if let Some(account_id) = get_aws_account_id(context) {
+            module.create_segment(
+                "account",
+                &config.account.with_value(&format!(" [{}]", account_id)),
+            );
+        }
+    }
+
+    if config.show_age {
+        if let Some(age) = get_credentials_age(context, SystemTime::now()) {
+            let age = format_duration(age.as_millis());
+            module.create_segment("age", &config.age.with_value(&format!(" ({})", age)));
+        }
+    }
+
     Some(module)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io;
+
+    /// Serves the requests of `query_imds_region` like the EC2 metadata service,
+    /// returning its base URL
+    #[cfg(feature = "http")]
+    fn serve_imds(region: &'static str) -> io::Result<String> {
+        use std::io::{BufRead, BufReader, Write};
+        use std::net::TcpListener;
+
+        let listener = TcpListener::bind("127.0.0.1:0")?;
+        let base_url = format!("http://{}", listener.local_addr()?);
+        std::thread::spawn(move || {
+            for stream in listener.incoming().take(2) {
+                let mut stream = stream.unwrap();
+                // Read the whole request, which has no body, before answering
+                let request: Vec<String> = BufReader::new(&stream)
+                    .lines()
+                    .map(Result::unwrap)
+                    .take_while(|line| !line.trim().is_empty())
+                    .collect();
+                let request_line = &request[0];
+                let body = if request_line.starts_with("PUT /latest/api/token ") {
+                    "token"
+                } else if request_line.starts_with("GET /latest/meta-data/placement/region ") {
+                    region
+                } else {
+                    ""
+                };
+                write!(
+                    stream,
+                    "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    body.len(),
+                    body
+                )
+                .unwrap();
+            }
+        });
+        Ok(base_url)
+    }
+
+    #[test]
+    #[cfg(feature = "http")]
+    fn region_from_imds() -> io::Result<()> {
+        let base_url = serve_imds("eu-west-3")?;
+        assert_eq!(
+            query_imds_region(&base_url, Duration::from_secs(5)),
+            Some("eu-west-3".to_string())
+        );
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "http")]
+    fn region_from_unreachable_imds() -> io::Result<()> {
+        // Nothing listens on the port of a dropped listener
+        let base_url = {
+            let listener = std::net::TcpListener::bind("127.0.0.1:0")?;
+            format!("http://{}", listener.local_addr()?)
+        };
+        assert_eq!(
+            query_imds_region(&base_url, Duration::from_millis(100)),
+            None
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn region_from_config_in_home_dir() -> io::Result<()> {
+        let home = tempfile::tempdir()?;
+        fs::create_dir(home.path().join(".aws"))?;
+        fs::write(
+            home.path().join(".aws/config"),
+            "[default]\nregion = us-east-2\n\n[profile astronauts]\nregion = us-east-1\n",
+        )?;
+
+        let mut context = Context::new_with_dir(clap::ArgMatches::default(), home.path());
+        context.home_dir = Some(home.path().to_path_buf());
+
+        assert_eq!(
+            get_aws_region_from_config(&context, None),
+            Some("us-east-2".to_string())
+        );
+        assert_eq!(
+            get_aws_region_from_config(&context, Some("astronauts")),
+            Some("us-east-1".to_string())
+        );
+        assert_eq!(get_aws_region_from_config(&context, Some("missing")), None);
+        home.close()
+    }
+
+    #[test]
+    fn region_from_linked_sso_session() -> io::Result<()> {
+        let home = tempfile::tempdir()?;
+        fs::create_dir(home.path().join(".aws"))?;
+        fs::write(
+            home.path().join(".aws/config"),
+            "[profile astronauts]\n\
+             sso_session = launchpad\n\
+             sso_account_id = 123456789012\n\
+             \n\
+             [sso-session launchpad]\n\
+             sso_start_url = https://example.awsapps.com/start\n\
+             sso_region = eu-central-1\n",
+        )?;
+
+        let mut context = Context::new_with_dir(clap::ArgMatches::default(), home.path());
+        context.home_dir = Some(home.path().to_path_buf());
+
+        assert_eq!(
+            get_aws_region_from_config(&context, Some("astronauts")),
+            Some("eu-central-1".to_string())
+        );
+        home.close()
+    }
+
+    #[test]
+    fn credentials_age_from_cli_cache() -> io::Result<()> {
+        let home = tempfile::tempdir()?;
+        let mut context = Context::new_with_dir(clap::ArgMatches::default(), home.path());
+        context.home_dir = Some(home.path().to_path_buf());
+        assert_eq!(get_credentials_age(&context, SystemTime::now()), None);
+
+        let cache_dir = home.path().join(".aws/cli/cache");
+        fs::create_dir_all(&cache_dir)?;
+        let cache_file = cache_dir.join("0123abcd.json");
+        fs::write(&cache_file, "{}")?;
+        let issued = fs::metadata(&cache_file)?.modified()?;
+
+        let age = get_credentials_age(&context, issued + Duration::from_secs(90));
+        assert_eq!(age, Some(Duration::from_secs(90)));
+        assert_eq!(format_duration(age.unwrap().as_millis()), "1m30s");
+        assert_eq!(
+            get_credentials_age(&context, issued - Duration::from_secs(5)),
+            Some(Duration::from_secs(0))
+        );
+        home.close()
+    }
+
+    #[test]
+    fn test_count_config_profiles() {
+        let config = "[default]\nregion = us-east-1\n\n[profile astronauts]\n\
+                      [sso-session launchpad]\n[profile cosmonauts]\n";
+        assert_eq!(count_config_profiles(config), 3);
+        assert_eq!(count_config_profiles(""), 0);
+    }
+
+    #[test]
+    fn test_get_config_value() {
+        let config =
+            "[default]\nregion_alias = au\nregion = us-east-2\n[profile x]\nregion=eu-west-1\n";
+        assert_eq!(
+            get_config_value(config, "default", "region"),
+            Some("us-east-2".to_string())
+        );
+        assert_eq!(
+            get_config_value(config, "profile x", "region"),
+            Some("eu-west-1".to_string())
+        );
+        assert_eq!(get_config_value(config, "profile x", "sso_session"), None);
+        assert_eq!(get_config_value(config, "profile y", "region"), None);
+    }
+}
@@ -1,4 +1,6 @@
+use super::utils::gauge::render_gauge;
 use super::{Context, Module, RootModuleConfig, Shell};
+use crate::config::SegmentConfig;
 use crate::configs::battery::BatteryConfig;
 
 /// Creates a module for the battery percentage and charging state
@@ -65,6 +67,15 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
                 .with_value(percent_string.join("").as_ref()),
         );
 
+        let bar_config = &battery_config.bar;
+        if !bar_config.disabled {
+            let gauge = render_gauge(percentage.into(), bar_config, percentage_char);
+            module.create_segment(
+                "bar",
+                &SegmentConfig::new(&format!(" {}", gauge)).with_style(bar_config.style),
+            );
+        }
+
         Some(module)
     } else {
         None
@@ -1,5 +1,11 @@
+use std::io;
+
 use super::{Context, Module, RootModuleConfig, Shell};
 use crate::configs::character::CharacterConfig;
+use crate::session::SessionStore;
+
+/// The session store key holding the number of consecutive successful commands
+const STREAK_KEY: &str = "success_streak";
 
 /// Creates a module for the prompt character
 ///
@@ -9,6 +15,9 @@ use crate::configs::character::CharacterConfig;
 /// (green by default)
 /// - If the exit-code was anything else, the arrow will be formatted with
 /// `style_failure` (red by default)
+///
+/// With `show_streak` enabled, the number of consecutive successful commands of the
+/// shell session is shown before the arrow.
 pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     enum ShellEditMode {
         Normal,
@@ -44,6 +53,15 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
         module.set_style(config.style_failure);
     };
 
+    if config.show_streak {
+        let streak = SessionStore::new().map_or(0, |store| {
+            next_streak(get_streak(&store), exit_success, has_run_command(context))
+        });
+        if streak > 0 {
+            module.create_segment("streak", &config.streak.with_value(&format!("{} ", streak)));
+        }
+    }
+
     /* If an error symbol is set in the config, use symbols to indicate
     success/failure, in addition to color */
     if config.use_symbol_for_status && !exit_success {
@@ -57,3 +75,101 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
 
     Some(module)
 }
+
+/// Whether a command ran since the previous prompt. Shells only pass its duration when
+/// one did, so that redrawing the prompt, e.g. on an empty line, doesn't count.
+fn has_run_command(context: &Context) -> bool {
+    context.properties.contains_key("cmd_duration")
+}
+
+/// Returns the streak of successful commands stored by the previous prompt
+fn get_streak(store: &SessionStore) -> usize {
+    store
+        .get(STREAK_KEY)
+        .and_then(|streak| streak.trim().parse().ok())
+        .unwrap_or(0)
+}
+
+/// Returns the streak including the last command, which resets it when it failed
+fn next_streak(previous: usize, exit_success: bool, command_ran: bool) -> usize {
+    match (command_ran, exit_success) {
+        (false, _) => previous,
+        (true, true) => previous + 1,
+        (true, false) => 0,
+    }
+}
+
+/// Stores the streak of successful commands including the last one, for the next prompt
+/// to build on. Nothing is stored unless `show_streak` is enabled.
+pub fn record_prompt_streak(context: &Context) {
+    let config = CharacterConfig::try_load(context.config.get_module_config("character"));
+    if config.disabled || !config.show_streak {
+        return;
+    }
+
+    let exit_success = context
+        .properties
+        .get("status_code")
+        .map_or(true, |exit_code| exit_code == "0");
+    if let Some(store) = SessionStore::new() {
+        if let Err(error) = record_streak(&store, exit_success, has_run_command(context)) {
+            log::debug!("Unable to store the success streak: {}", error);
+        }
+    }
+}
+
+fn record_streak(store: &SessionStore, exit_success: bool, command_ran: bool) -> io::Result<()> {
+    let previous = get_streak(store);
+    let streak = next_streak(previous, exit_success, command_ran);
+    if streak == previous && store.get(STREAK_KEY).is_some() {
+        return Ok(());
+    }
+    store.set(STREAK_KEY, &streak.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn streak_counts_successes() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let store = SessionStore::in_dir(dir.path());
+        assert_eq!(get_streak(&store), 0);
+
+        for _ in 0..3 {
+            record_streak(&store, true, true)?;
+        }
+        assert_eq!(get_streak(&store), 3);
+        assert_eq!(next_streak(get_streak(&store), true, true), 4);
+        dir.close()
+    }
+
+    #[test]
+    fn streak_reset_by_failure() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let store = SessionStore::in_dir(dir.path());
+
+        record_streak(&store, true, true)?;
+        record_streak(&store, true, true)?;
+        record_streak(&store, false, true)?;
+        assert_eq!(get_streak(&store), 0);
+
+        record_streak(&store, true, true)?;
+        assert_eq!(get_streak(&store), 1);
+        dir.close()
+    }
+
+    #[test]
+    fn streak_kept_without_command() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let store = SessionStore::in_dir(dir.path());
+
+        record_streak(&store, true, true)?;
+        record_streak(&store, true, false)?;
+        // The status of the last command is reported again on an empty line
+        record_streak(&store, false, false)?;
+        assert_eq!(get_streak(&store), 1);
+        dir.close()
+    }
+}
@@ -0,0 +1,186 @@
+use super::{Context, Module, RootModuleConfig};
+
+use crate::configs::cloudflare::CloudflareConfig;
+use crate::utils;
+
+/// Creates a module with the Cloudflare Workers project and its wrangler environment
+///
+/// Will display the name of the worker and the environment if any of the following criteria are met:
+///     - Current directory contains a `wrangler.toml` file
+///     - Current directory contains a `wrangler.json` or `wrangler.jsonc` file
+pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
+    let is_wrangler_project = context
+        .try_begin_scan()?
+        .set_files(&["wrangler.toml", "wrangler.json", "wrangler.jsonc"])
+        .is_match();
+
+    if !is_wrangler_project {
+        return None;
+    }
+
+    let worker_name = get_worker_name(context);
+    let environment = context
+        .get_env("CLOUDFLARE_ENV")
+        .filter(|environment| !environment.is_empty());
+    if worker_name.is_none() && environment.is_none() {
+        return None;
+    }
+
+    let mut module = context.new_module("cloudflare");
+    let config: CloudflareConfig = CloudflareConfig::try_load(module.config);
+
+    module.set_style(config.style);
+    module.get_prefix().set_value("on ");
+    module.create_segment("symbol", &config.symbol);
+
+    if let Some(worker_name) = &worker_name {
+        module.create_segment("name", &config.name.with_value(worker_name));
+    }
+    if let Some(environment) = environment {
+        let separator = if worker_name.is_some() { " " } else { "" };
+        module.create_segment(
+            "environment",
+            &config
+                .environment
+                .with_value(&format!("{}({})", separator, environment)),
+        );
+    }
+
+    Some(module)
+}
+
+/// Returns the `name` of the worker from its wrangler manifest
+fn get_worker_name(context: &Context) -> Option<String> {
+    let dir = &context.current_dir;
+    if let Ok(manifest) = utils::read_file(dir.join("wrangler.toml")) {
+        return parse_toml_worker_name(&manifest);
+    }
+
+    ["wrangler.json", "wrangler.jsonc"]
+        .iter()
+        .find_map(|file| utils::read_file(dir.join(file)).ok())
+        .and_then(|manifest| parse_json_worker_name(&manifest))
+}
+
+fn parse_toml_worker_name(manifest: &str) -> Option<String> {
+    let manifest: toml::Value = toml::from_str(manifest).ok()?;
+    let name = manifest.get("name")?.as_str()?;
+    Some(name.to_string())
+}
+
+fn parse_json_worker_name(manifest: &str) -> Option<String> {
+    let manifest: serde_json::Value = serde_json::from_str(&strip_json_comments(manifest)).ok()?;
+    let name = manifest.get("name")?.as_str()?;
+    Some(name.to_string())
+}
+
+/// Removes the `//` and `/* */` comments allowed in `.jsonc` files, leaving strings untouched
+fn strip_json_comments(jsonc: &str) -> String {
+    let mut json = String::with_capacity(jsonc.len());
+    let mut chars = jsonc.chars().peekable();
+    let mut in_string = false;
+
+    while let Some(c) = chars.next() {
+        if in_string {
+            json.push(c);
+            match c {
+                '\\' => json.extend(chars.next()),
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+
+        match (c, chars.peek()) {
+            ('"', _) => {
+                in_string = true;
+                json.push(c);
+            }
+            ('/', Some('/')) => {
+                while chars.peek().map_or(false, |&c| c != '\n') {
+                    chars.next();
+                }
+            }
+            ('/', Some('*')) => {
+                chars.next();
+                let mut previous = ' ';
+                for c in &mut chars {
+                    if previous == '*' && c == '/' {
+                        break;
+                    }
+                    previous = c;
+                }
+            }
+            _ => json.push(c),
+        }
+    }
+
+    json
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::modules::utils::test::render_module;
+    use ansi_term::Color;
+    use std::fs;
+    use std::io;
+
+    #[test]
+    fn folder_without_wrangler_manifest() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+
+        let actual = render_module("cloudflare", dir.path());
+
+        let expected = None;
+        assert_eq!(expected, actual);
+        dir.close()
+    }
+
+    #[test]
+    fn folder_with_wrangler_toml() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        fs::write(
+            dir.path().join("wrangler.toml"),
+            "name = \"my-worker\"\nmain = \"src/index.js\"\n\n[env.staging]\nname = \"my-worker-staging\"\n",
+        )?;
+
+        let actual = render_module("cloudflare", dir.path());
+
+        let expected = Some(format!(
+            "on {} ",
+            Color::Fixed(208).bold().paint("⛅️ my-worker")
+        ));
+        assert_eq!(expected, actual);
+        dir.close()
+    }
+
+    #[test]
+    fn folder_with_wrangler_jsonc() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        fs::write(
+            dir.path().join("wrangler.jsonc"),
+            "{\n  // The worker's name\n  \"name\": \"my-worker\",\n  /* Entry point */\n  \"main\": \"src/index.ts\"\n}\n",
+        )?;
+
+        let actual = render_module("cloudflare", dir.path());
+
+        let expected = Some(format!(
+            "on {} ",
+            Color::Fixed(208).bold().paint("⛅️ my-worker")
+        ));
+        assert_eq!(expected, actual);
+        dir.close()
+    }
+
+    #[test]
+    fn test_strip_json_comments() {
+        let jsonc = r#"{
+  // comment with "quotes"
+  "url": "https://example.com/*path*/", /* inline */ "a": 1
+}"#;
+        let json: serde_json::Value = serde_json::from_str(&strip_json_comments(jsonc)).unwrap();
+        assert_eq!(json["url"], "https://example.com/*path*/");
+        assert_eq!(json["a"], 1);
+    }
+}
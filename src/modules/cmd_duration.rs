@@ -2,6 +2,7 @@ use super::{Context, Module, SegmentConfig};
 
 use crate::config::RootModuleConfig;
 use crate::configs::cmd_duration::CmdDurationConfig;
+use crate::formatter::duration::format_duration_full;
 
 /// Outputs the time it took the last command to execute
 ///
@@ -35,44 +36,33 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
         _ => config.style,
     };
 
+    let rendered_time = match config.unit {
+        "auto" => format_duration_full(elapsed, config.show_milliseconds),
+        unit => render_time_in_unit(elapsed, unit, config.precision).unwrap_or_else(|| {
+            log::warn!("Unknown unit in [cmd_duration]: {}", unit);
+            format_duration_full(elapsed, config.show_milliseconds)
+        }),
+    };
+
     module.set_style(module_color);
-    module.create_segment(
-        "cmd_duration",
-        &SegmentConfig::new(&render_time(elapsed, config.show_milliseconds)),
-    );
+    module.create_segment("cmd_duration", &SegmentConfig::new(&rendered_time));
     module.get_prefix().set_value(config.prefix);
 
     Some(module)
 }
 
-// Render the time into a nice human-readable string
-fn render_time(raw_millis: u128, show_millis: bool) -> String {
-    // Calculate a simple breakdown into days/hours/minutes/seconds/milliseconds
-    let (millis, raw_seconds) = (raw_millis % 1000, raw_millis / 1000);
-    let (seconds, raw_minutes) = (raw_seconds % 60, raw_seconds / 60);
-    let (minutes, raw_hours) = (raw_minutes % 60, raw_minutes / 60);
-    let (hours, days) = (raw_hours % 24, raw_hours / 24);
-
-    let components = [days, hours, minutes, seconds];
-    let suffixes = ["d", "h", "m", "s"];
-
-    let mut rendered_components: Vec<String> = components
-        .iter()
-        .zip(&suffixes)
-        .map(render_time_component)
-        .collect();
-    if show_millis || raw_millis < 1000 {
-        rendered_components.push(render_time_component((&millis, &"ms")));
-    }
-    rendered_components.join("")
-}
-
-/// Render a single component of the time string, giving an empty string if component is zero
-fn render_time_component((component, suffix): (&u128, &&str)) -> String {
-    match component {
-        0 => String::new(),
-        n => format!("{}{}", n, suffix),
-    }
+/// Render the time in a single unit (`ms`, `s`, `m` or `h`), with `precision` decimal
+/// places for the units above milliseconds. Returns `None` for an unknown unit.
+fn render_time_in_unit(raw_millis: u128, unit: &str, precision: usize) -> Option<String> {
+    let millis_per_unit = match unit {
+        "ms" => return Some(format!("{}ms", raw_millis)),
+        "s" => 1_000,
+        "m" => 60_000,
+        "h" => 3_600_000,
+        _ => return None,
+    };
+    let value = raw_millis as f64 / f64::from(millis_per_unit);
+    Some(format!("{:.*}{}", precision, value, unit))
 }
 
 #[cfg(test)]
@@ -80,23 +70,33 @@ mod tests {
     use super::*;
 
     #[test]
-    fn test_500ms() {
-        assert_eq!(render_time(500 as u128, true), "500ms")
-    }
-    #[test]
-    fn test_10s() {
-        assert_eq!(render_time(10_000 as u128, true), "10s")
-    }
-    #[test]
-    fn test_90s() {
-        assert_eq!(render_time(90_000 as u128, true), "1m30s")
+    fn test_unit_ms() {
+        assert_eq!(render_time_in_unit(250, "ms", 2), Some("250ms".to_string()));
+        assert_eq!(
+            render_time_in_unit(1_500, "ms", 0),
+            Some("1500ms".to_string())
+        );
+        assert_eq!(
+            render_time_in_unit(3_725_000, "ms", 0),
+            Some("3725000ms".to_string())
+        );
     }
     #[test]
-    fn test_10110s() {
-        assert_eq!(render_time(10_110_000 as u128, true), "2h48m30s")
+    fn test_unit_with_precision() {
+        assert_eq!(render_time_in_unit(250, "s", 2), Some("0.25s".to_string()));
+        assert_eq!(render_time_in_unit(1_500, "s", 1), Some("1.5s".to_string()));
+        assert_eq!(render_time_in_unit(1_500, "s", 0), Some("2s".to_string()));
+        assert_eq!(
+            render_time_in_unit(3_725_000, "m", 1),
+            Some("62.1m".to_string())
+        );
+        assert_eq!(
+            render_time_in_unit(3_725_000, "h", 2),
+            Some("1.03h".to_string())
+        );
     }
     #[test]
-    fn test_1d() {
-        assert_eq!(render_time(86_400_000 as u128, true), "1d")
+    fn test_unknown_unit() {
+        assert_eq!(render_time_in_unit(1_500, "d", 0), None);
     }
 }
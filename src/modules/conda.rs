@@ -1,5 +1,3 @@
-use std::env;
-
 use super::{Context, Module};
 
 use super::utils::directory::truncate;
@@ -11,7 +9,7 @@ use crate::configs::conda::CondaConfig;
 /// Will display the Conda environment iff `$CONDA_DEFAULT_ENV` is set.
 pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     // Reference implementation: https://github.com/denysdovhan/spaceship-prompt/blob/master/sections/conda.zsh
-    let conda_env = env::var("CONDA_DEFAULT_ENV").unwrap_or_else(|_| "".into());
+    let conda_env = context.get_env_or("CONDA_DEFAULT_ENV", "");
     if conda_env.trim().is_empty() {
         return None;
     }
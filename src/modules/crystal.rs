@@ -1,7 +1,6 @@
 use super::{Context, Module, RootModuleConfig, SegmentConfig};
 
 use crate::configs::crystal::CrystalConfig;
-use crate::utils;
 
 /// Creates a module with the current Crystal version
 ///
@@ -19,7 +18,9 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
         return None;
     }
 
-    let crystal_version = utils::exec_cmd("crystal", &["--version"])?.stdout;
+    let crystal_version = context
+        .exec_cmd("crystal", "crystal", &["--version"])?
+        .stdout;
     let formatted_version = format_crystal_version(&crystal_version)?;
 
     let mut module = context.new_module("crystal");
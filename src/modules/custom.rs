@@ -1,10 +1,19 @@
 use ansi_term::Color;
+use regex::{Captures, Regex};
+use std::env;
+use std::fs;
 use std::io::Write;
+use std::path::Path;
 use std::process::{Command, Output, Stdio};
+use std::time::UNIX_EPOCH;
 
 use super::{Context, Module, RootModuleConfig};
 
-use crate::{config::SegmentConfig, configs::custom::CustomConfig};
+use crate::{
+    config::SegmentConfig,
+    configs::custom::{CustomConfig, Multiline},
+    session::SessionStore,
+};
 
 /// Creates a custom module with some configuration
 ///
@@ -33,9 +42,15 @@ pub fn module<'a>(name: &'a str, context: &'a Context) -> Option<Module<'a>> {
 
     let mut is_match = scan_dir.is_match();
 
+    let env = EnvFilter {
+        allow: &config.env_allow,
+        deny: &config.env_deny,
+        source: None,
+    };
+
     if !is_match {
         if let Some(when) = config.when {
-            is_match = exec_when(when, config.shell);
+            is_match = exec_when(when, config.shell, &env);
         }
 
         if !is_match {
@@ -44,6 +59,7 @@ pub fn module<'a>(name: &'a str, context: &'a Context) -> Option<Module<'a>> {
     }
 
     let mut module = Module::new(name, config.description, Some(toml_config));
+    module.set_color_mode(context.color_mode);
     let style = config.style.unwrap_or_else(|| Color::Green.bold());
 
     if let Some(prefix) = config.prefix {
@@ -53,20 +69,29 @@ pub fn module<'a>(name: &'a str, context: &'a Context) -> Option<Module<'a>> {
         module.get_suffix().set_value(suffix);
     }
 
-    if let Some(symbol) = config.symbol {
-        module.create_segment("symbol", &symbol);
+    if let Some(symbol) = &config.symbol {
+        module.create_segment("symbol", symbol);
     }
 
-    if let Some(output) = exec_command(config.command, config.shell) {
-        let trimmed = output.trim();
+    let run_command = || exec_command(config.command, config.shell, &env);
+    let output = match (config.cache_key, SessionStore::new()) {
+        (Some(cache_key), Some(store)) => {
+            let cache_key = eval_cache_key(cache_key, &context.current_dir);
+            cached_output(&store, name, &cache_key, run_command)
+        }
+        _ => run_command(),
+    };
 
-        if trimmed.is_empty() {
+    if let Some(output) = output {
+        let rendered = render_output(&output, config.trim, &config.multiline);
+
+        if rendered.is_empty() {
             return None;
         }
 
         module.create_segment(
             "output",
-            &SegmentConfig::new(&trimmed).with_style(Some(style)),
+            &SegmentConfig::new(&rendered).with_style(Some(style)),
         );
 
         Some(module)
@@ -75,6 +100,116 @@ pub fn module<'a>(name: &'a str, context: &'a Context) -> Option<Module<'a>> {
     }
 }
 
+/// Expands `$cwd` to the current directory and `$mtime(<path>)` to the modification
+/// time of the file at `<path>`, relative to the current directory
+fn eval_cache_key(cache_key: &str, current_dir: &Path) -> String {
+    let mtime_pattern = Regex::new(r"\$mtime\(([^)]*)\)").unwrap();
+    let cache_key = mtime_pattern.replace_all(cache_key, |captures: &Captures| {
+        fs::metadata(current_dir.join(&captures[1]))
+            .and_then(|metadata| metadata.modified())
+            .ok()
+            .and_then(|modified| modified.duration_since(UNIX_EPOCH).ok())
+            .map_or_else(String::new, |mtime| mtime.as_nanos().to_string())
+    });
+
+    cache_key.replace("$cwd", &current_dir.to_string_lossy())
+}
+
+/// Returns the output cached for the custom module `name` while its cache key is still
+/// `cache_key`, or runs the command and caches its output otherwise
+fn cached_output(
+    store: &SessionStore,
+    name: &str,
+    cache_key: &str,
+    run_command: impl FnOnce() -> Option<String>,
+) -> Option<String> {
+    let store_key = format!("custom_{}", name);
+
+    // The key and the output are stored together, so they can't get out of sync
+    if let Some(cached) = store.get(&store_key) {
+        let mut parts = cached.splitn(2, '\0');
+        if let (Some(cached_key), Some(cached_output)) = (parts.next(), parts.next()) {
+            if cached_key == cache_key {
+                return Some(cached_output.to_string());
+            }
+        }
+    }
+
+    let output = run_command()?;
+    if let Err(error) = store.set(&store_key, &format!("{}\0{}", cache_key, output)) {
+        log::debug!("Unable to cache the output of {}: {}", name, error);
+    }
+    Some(output)
+}
+
+/// Fits the output of a command on a single line
+///
+/// When `trim` is set, surrounding whitespace is removed from every line and
+/// blank lines are dropped before `multiline` picks the lines to render.
+fn render_output(output: &str, trim: bool, multiline: &Multiline) -> String {
+    let lines: Vec<&str> = if trim {
+        output
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .collect()
+    } else {
+        output.lines().collect()
+    };
+
+    match multiline {
+        Multiline::First => lines.first().copied().unwrap_or_default().to_string(),
+        Multiline::Last => lines.last().copied().unwrap_or_default().to_string(),
+        Multiline::Join => lines.join(" "),
+    }
+}
+
+/// The env vars passed on to the commands of a custom module
+///
+/// When `allow` is not empty, only the vars it lists are passed on, along with
+/// `PATH` and `HOME` which commands can hardly run without. The vars listed in `deny`
+/// are never passed on.
+struct EnvFilter<'a> {
+    allow: &'a [&'a str],
+    deny: &'a [&'a str],
+    /// The vars to pick from, which are those of starship when `None`
+    source: Option<&'a [(&'a str, &'a str)]>,
+}
+
+impl<'a> EnvFilter<'a> {
+    const ALWAYS_ALLOWED: &'static [&'static str] = &["PATH", "HOME"];
+
+    fn apply<'c>(&self, command: &'c mut Command) -> &'c mut Command {
+        let is_allowed = |key: &str| {
+            self.allow.is_empty()
+                || self.allow.contains(&key)
+                || Self::ALWAYS_ALLOWED.contains(&key)
+        };
+
+        if let Some(source) = self.source {
+            command.env_clear();
+            for (key, value) in source {
+                if is_allowed(key) {
+                    command.env(key, value);
+                }
+            }
+        } else if !self.allow.is_empty() {
+            command.env_clear();
+            for (key, value) in env::vars_os() {
+                if key.to_str().map_or(false, is_allowed) {
+                    command.env(key, value);
+                }
+            }
+        }
+
+        for key in self.deny {
+            command.env_remove(key);
+        }
+
+        command
+    }
+}
+
 /// Return the invoking shell, using `shell` and fallbacking in order to STARSHIP_SHELL and "sh"
 #[cfg(not(windows))]
 fn get_shell(shell: Option<&str>) -> std::borrow::Cow<str> {
@@ -89,8 +224,9 @@ fn get_shell(shell: Option<&str>) -> std::borrow::Cow<str> {
 
 /// Attempt to run the given command in a shell by passing it as `stdin` to `get_shell()`
 #[cfg(not(windows))]
-fn shell_command(cmd: &str, shell: Option<&str>) -> Option<Output> {
-    let command = Command::new(get_shell(shell).as_ref())
+fn shell_command(cmd: &str, shell: Option<&str>, env: &EnvFilter) -> Option<Output> {
+    let command = env
+        .apply(&mut Command::new(get_shell(shell).as_ref()))
         .stdin(Stdio::piped())
         .stdout(Stdio::piped())
         .stderr(Stdio::piped())
@@ -103,7 +239,7 @@ fn shell_command(cmd: &str, shell: Option<&str>) -> Option<Output> {
                 "Could not launch command with given shell or STARSHIP_SHELL env variable, retrying with /bin/env sh"
             );
 
-            Command::new("/bin/env")
+            env.apply(&mut Command::new("/bin/env"))
                 .arg("sh")
                 .stdin(Stdio::piped())
                 .stdout(Stdio::piped())
@@ -120,7 +256,7 @@ fn shell_command(cmd: &str, shell: Option<&str>) -> Option<Output> {
 /// Attempt to run the given command in a shell by passing it as `stdin` to `get_shell()`,
 /// or by invoking cmd.exe /C.
 #[cfg(windows)]
-fn shell_command(cmd: &str, shell: Option<&str>) -> Option<Output> {
+fn shell_command(cmd: &str, shell: Option<&str>, env: &EnvFilter) -> Option<Output> {
     let shell = if let Some(shell) = shell {
         Some(std::borrow::Cow::Borrowed(shell))
     } else if let Ok(env_shell) = std::env::var("STARSHIP_SHELL") {
@@ -130,7 +266,8 @@ fn shell_command(cmd: &str, shell: Option<&str>) -> Option<Output> {
     };
 
     if let Some(forced_shell) = shell {
-        let command = Command::new(forced_shell.as_ref())
+        let command = env
+            .apply(&mut Command::new(forced_shell.as_ref()))
             .stdin(Stdio::piped())
             .stdout(Stdio::piped())
             .stderr(Stdio::piped())
@@ -147,7 +284,8 @@ fn shell_command(cmd: &str, shell: Option<&str>) -> Option<Output> {
         );
     }
 
-    let command = Command::new("cmd.exe")
+    let command = env
+        .apply(&mut Command::new("cmd.exe"))
         .arg("/C")
         .arg(cmd)
         .stdin(Stdio::piped())
@@ -159,10 +297,10 @@ fn shell_command(cmd: &str, shell: Option<&str>) -> Option<Output> {
 }
 
 /// Execute the given command capturing all output, and return whether it return 0
-fn exec_when(cmd: &str, shell: Option<&str>) -> bool {
+fn exec_when(cmd: &str, shell: Option<&str>, env: &EnvFilter) -> bool {
     log::trace!("Running '{}'", cmd);
 
-    if let Some(output) = shell_command(cmd, shell) {
+    if let Some(output) = shell_command(cmd, shell, env) {
         if !output.status.success() {
             log::trace!("non-zero exit code '{:?}'", output.status.code());
             log::trace!(
@@ -184,10 +322,10 @@ fn exec_when(cmd: &str, shell: Option<&str>) -> bool {
 }
 
 /// Execute the given command, returning its output on success
-fn exec_command(cmd: &str, shell: Option<&str>) -> Option<String> {
+fn exec_command(cmd: &str, shell: Option<&str>, env: &EnvFilter) -> Option<String> {
     log::trace!("Running '{}'", cmd);
 
-    if let Some(output) = shell_command(cmd, shell) {
+    if let Some(output) = shell_command(cmd, shell, env) {
         if !output.status.success() {
             log::trace!("Non-zero exit code '{:?}'", output.status.code());
             log::trace!(
@@ -221,25 +359,127 @@ mod tests {
     #[cfg(windows)]
     const FAILING_COMMAND: &str = "color 00";
 
+    const NO_ENV_FILTER: EnvFilter = EnvFilter {
+        allow: &[],
+        deny: &[],
+        source: None,
+    };
+
     const UNKNOWN_COMMAND: &str = "ydelsyiedsieudleylse dyesdesl";
 
+    const MULTILINE_OUTPUT: &str = "  first \n\n second\nlast  \n";
+
+    #[test]
+    fn output_is_trimmed() {
+        assert_eq!(render_output(" hello \n", true, &Multiline::Join), "hello");
+        assert_eq!(
+            render_output(" hello \n", false, &Multiline::Join),
+            " hello "
+        );
+        assert_eq!(render_output(" \n\n", true, &Multiline::Join), "");
+    }
+
+    #[test]
+    fn multiline_output_first() {
+        assert_eq!(
+            render_output(MULTILINE_OUTPUT, true, &Multiline::First),
+            "first"
+        );
+        assert_eq!(
+            render_output(MULTILINE_OUTPUT, false, &Multiline::First),
+            "  first "
+        );
+    }
+
+    #[test]
+    fn multiline_output_last() {
+        assert_eq!(
+            render_output(MULTILINE_OUTPUT, true, &Multiline::Last),
+            "last"
+        );
+        assert_eq!(
+            render_output(MULTILINE_OUTPUT, false, &Multiline::Last),
+            "last  "
+        );
+    }
+
+    #[test]
+    fn multiline_output_join() {
+        assert_eq!(
+            render_output(MULTILINE_OUTPUT, true, &Multiline::Join),
+            "first second last"
+        );
+        assert_eq!(
+            render_output(MULTILINE_OUTPUT, false, &Multiline::Join),
+            "  first    second last  "
+        );
+    }
+
+    #[test]
+    fn cache_key_expands_cwd_and_mtime() -> std::io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        fs::write(dir.path().join("VERSION"), "1.0")?;
+
+        let cwd_key = eval_cache_key("$cwd", dir.path());
+        assert_eq!(cwd_key, dir.path().to_string_lossy());
+
+        let mtime_key = eval_cache_key("version-$mtime(VERSION)", dir.path());
+        assert_ne!(mtime_key, "version-");
+        assert_eq!(
+            eval_cache_key("version-$mtime(VERSION)", dir.path()),
+            mtime_key
+        );
+        assert_eq!(eval_cache_key("$mtime(missing)", dir.path()), "");
+        dir.close()
+    }
+
+    #[test]
+    fn output_cached_while_key_unchanged() -> std::io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let store = SessionStore::in_dir(dir.path());
+
+        let output = cached_output(&store, "foo", "key", || Some("first".to_string()));
+        assert_eq!(output, Some("first".to_string()));
+
+        let output = cached_output(&store, "foo", "key", || panic!("command re-run"));
+        assert_eq!(output, Some("first".to_string()));
+        dir.close()
+    }
+
+    #[test]
+    fn command_rerun_once_key_changed() -> std::io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let store = SessionStore::in_dir(dir.path());
+
+        cached_output(&store, "foo", "key", || Some("first".to_string()));
+        let output = cached_output(&store, "foo", "other-key", || Some("second".to_string()));
+        assert_eq!(output, Some("second".to_string()));
+
+        let output = cached_output(&store, "foo", "other-key", || panic!("command re-run"));
+        assert_eq!(output, Some("second".to_string()));
+        dir.close()
+    }
+
     #[test]
     fn when_returns_right_value() {
-        assert!(exec_when("echo hello", SHELL));
-        assert!(!exec_when(FAILING_COMMAND, SHELL));
+        assert!(exec_when("echo hello", SHELL, &NO_ENV_FILTER));
+        assert!(!exec_when(FAILING_COMMAND, SHELL, &NO_ENV_FILTER));
     }
 
     #[test]
     fn when_returns_false_if_invalid_command() {
-        assert!(!exec_when(UNKNOWN_COMMAND, SHELL));
+        assert!(!exec_when(UNKNOWN_COMMAND, SHELL, &NO_ENV_FILTER));
     }
 
     #[test]
     #[cfg(not(windows))]
     fn command_returns_right_string() {
-        assert_eq!(exec_command("echo hello", SHELL), Some("hello\n".into()));
         assert_eq!(
-            exec_command("echo 강남스타일", SHELL),
+            exec_command("echo hello", SHELL, &NO_ENV_FILTER),
+            Some("hello\n".into())
+        );
+        assert_eq!(
+            exec_command("echo 강남스타일", SHELL, &NO_ENV_FILTER),
             Some("강남스타일\n".into())
         );
     }
@@ -247,9 +487,12 @@ mod tests {
     #[test]
     #[cfg(windows)]
     fn command_returns_right_string() {
-        assert_eq!(exec_command("echo hello", SHELL), Some("hello\r\n".into()));
         assert_eq!(
-            exec_command("echo 강남스타일", SHELL),
+            exec_command("echo hello", SHELL, &NO_ENV_FILTER),
+            Some("hello\r\n".into())
+        );
+        assert_eq!(
+            exec_command("echo 강남스타일", SHELL, &NO_ENV_FILTER),
             Some("강남스타일\r\n".into())
         );
     }
@@ -258,11 +501,11 @@ mod tests {
     #[cfg(not(windows))]
     fn command_ignores_stderr() {
         assert_eq!(
-            exec_command("echo foo 1>&2; echo bar", SHELL),
+            exec_command("echo foo 1>&2; echo bar", SHELL, &NO_ENV_FILTER),
             Some("bar\n".into())
         );
         assert_eq!(
-            exec_command("echo foo; echo bar 1>&2", SHELL),
+            exec_command("echo foo; echo bar 1>&2", SHELL, &NO_ENV_FILTER),
             Some("foo\n".into())
         );
     }
@@ -271,18 +514,85 @@ mod tests {
     #[cfg(windows)]
     fn command_ignores_stderr() {
         assert_eq!(
-            exec_command("echo foo 1>&2 & echo bar", SHELL),
+            exec_command("echo foo 1>&2 & echo bar", SHELL, &NO_ENV_FILTER),
             Some("bar\r\n".into())
         );
         assert_eq!(
-            exec_command("echo foo& echo bar 1>&2", SHELL),
+            exec_command("echo foo& echo bar 1>&2", SHELL, &NO_ENV_FILTER),
             Some("foo\r\n".into())
         );
     }
 
     #[test]
     fn command_can_fail() {
-        assert_eq!(exec_command(FAILING_COMMAND, SHELL), None);
-        assert_eq!(exec_command(UNKNOWN_COMMAND, SHELL), None);
+        assert_eq!(exec_command(FAILING_COMMAND, SHELL, &NO_ENV_FILTER), None);
+        assert_eq!(exec_command(UNKNOWN_COMMAND, SHELL, &NO_ENV_FILTER), None);
+    }
+
+    #[test]
+    #[cfg(not(windows))]
+    fn command_env_can_be_filtered() {
+        let source = &[
+            ("STARSHIP_CUSTOM_ENV_ALLOWED", "allowed"),
+            ("STARSHIP_CUSTOM_ENV_OTHER", "other"),
+        ];
+        const COMMAND: &str = "echo \"$STARSHIP_CUSTOM_ENV_ALLOWED,$STARSHIP_CUSTOM_ENV_OTHER\"";
+
+        let unfiltered = EnvFilter {
+            allow: &[],
+            deny: &[],
+            source: Some(source),
+        };
+        assert_eq!(
+            exec_command(COMMAND, SHELL, &unfiltered),
+            Some("allowed,other\n".into())
+        );
+
+        let allow_only = EnvFilter {
+            allow: &["STARSHIP_CUSTOM_ENV_ALLOWED"],
+            deny: &[],
+            source: Some(source),
+        };
+        assert_eq!(
+            exec_command(COMMAND, SHELL, &allow_only),
+            Some("allowed,\n".into())
+        );
+
+        let deny = EnvFilter {
+            allow: &[],
+            deny: &["STARSHIP_CUSTOM_ENV_ALLOWED"],
+            source: Some(source),
+        };
+        assert_eq!(exec_command(COMMAND, SHELL, &deny), Some(",other\n".into()));
+    }
+
+    #[test]
+    #[cfg(not(windows))]
+    fn command_env_keeps_path_and_home() {
+        let path = env::var("PATH").unwrap_or_default();
+        let source = &[
+            ("PATH", path.as_str()),
+            ("HOME", "/home/rocket"),
+            ("STARSHIP_CUSTOM_ENV_OTHER", "other"),
+        ];
+        const COMMAND: &str = "echo \"$PATH\" \"$HOME\" \"$STARSHIP_CUSTOM_ENV_OTHER\"";
+
+        let allow_only = EnvFilter {
+            allow: &["STARSHIP_CUSTOM_ENV_ALLOWED"],
+            deny: &[],
+            source: Some(source),
+        };
+        assert_eq!(
+            exec_command(COMMAND, SHELL, &allow_only),
+            Some(format!("{} /home/rocket \n", path))
+        );
+
+        let deny_home = EnvFilter {
+            allow: &["STARSHIP_CUSTOM_ENV_ALLOWED"],
+            deny: &["HOME"],
+            source: Some(source),
+        };
+        let output = exec_command("echo \"$HOME\"", SHELL, &deny_home);
+        assert_eq!(output, Some("\n".into()));
     }
 }
@@ -1,5 +1,7 @@
 use path_slash::PathExt;
-use std::path::Path;
+use serde_json as json;
+use std::fs;
+use std::path::{Path, PathBuf};
 use unicode_segmentation::UnicodeSegmentation;
 
 use super::{Context, Module};
@@ -7,6 +9,7 @@ use super::{Context, Module};
 use super::utils::directory::truncate;
 use crate::config::{RootModuleConfig, SegmentConfig};
 use crate::configs::directory::DirectoryConfig;
+use crate::utils;
 
 /// Creates a module with the current directory
 ///
@@ -18,6 +21,15 @@ use crate::configs::directory::DirectoryConfig;
 ///
 /// **Truncation**
 /// Paths will be limited in length to `3` path components by default.
+///
+/// **Project name**
+/// With `use_project_name`, the name of the project is shown instead of the path
+/// when there is one: the name in the nearest `Cargo.toml` or `package.json` up to
+/// the repo root, or else the name of the repo root.
+///
+/// **Symlink target**
+/// With `show_symlink_target`, the target of the current directory is appended
+/// when the directory is itself a symlink (`dir -> /real/path`).
 pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     const HOME_SYMBOL: &str = "~";
 
@@ -50,6 +62,21 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
 
     let repo = &context.get_repo().ok()?;
 
+    if config.use_project_name {
+        if let Some(project_name) = get_project_name(context, current_dir, repo.root.as_deref()) {
+            module.create_segment(
+                "project_name",
+                &SegmentConfig {
+                    value: &project_name,
+                    style: None,
+                },
+            );
+            module.get_prefix().set_value(config.prefix);
+
+            return Some(module);
+        }
+    }
+
     let dir_string = match &repo.root {
         Some(repo_root) if config.truncate_to_repo && (repo_root != &home_dir) => {
             let repo_folder_name = repo_root.file_name().unwrap().to_str().unwrap();
@@ -90,11 +117,73 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
         },
     );
 
+    if config.show_symlink_target {
+        if let Some(target) = get_symlink_target(current_dir) {
+            let target = contract_path(&target, &home_dir, HOME_SYMBOL);
+            module.create_segment(
+                "symlink_target",
+                &SegmentConfig {
+                    value: &format!(" -> {}", target),
+                    style: None,
+                },
+            );
+        }
+    }
+
     module.get_prefix().set_value(config.prefix);
 
     Some(module)
 }
 
+/// The path `dir` points to if it is a symlink itself, ignoring symlinks among its parents
+fn get_symlink_target(dir: &Path) -> Option<PathBuf> {
+    fs::read_link(dir).ok()
+}
+
+/// Find the name of the project `current_dir` belongs to, looking for a manifest
+/// in each directory up to the repo root, and falling back to the repo root name
+fn get_project_name(
+    context: &Context,
+    current_dir: &Path,
+    repo_root: Option<&Path>,
+) -> Option<String> {
+    let repo_root = match repo_root {
+        Some(repo_root) if current_dir.starts_with(repo_root) => repo_root,
+        // Outside of a repo, only the current directory is checked
+        _ => return get_manifest_name(context, current_dir),
+    };
+
+    current_dir
+        .ancestors()
+        .take_while(|dir| dir.starts_with(repo_root))
+        .find_map(|dir| get_manifest_name(context, dir))
+        .or_else(|| Some(repo_root.file_name()?.to_str()?.to_string()))
+}
+
+/// Read the project name from a `Cargo.toml` or `package.json` in `dir`
+fn get_manifest_name(context: &Context, dir: &Path) -> Option<String> {
+    get_cargo_name(context, dir).or_else(|| get_package_json_name(dir))
+}
+
+fn get_cargo_name(context: &Context, dir: &Path) -> Option<String> {
+    // The current directory's manifest is shared with the `package` module
+    let cargo_toml = if dir == context.current_dir {
+        context.get_cargo_manifest()?
+    } else {
+        let file_contents = utils::read_file(dir.join("Cargo.toml")).ok()?;
+        toml::from_str(&file_contents).ok()?
+    };
+    let name = cargo_toml.get("package")?.get("name")?.as_str()?;
+    Some(name.to_string())
+}
+
+fn get_package_json_name(dir: &Path) -> Option<String> {
+    let file_contents = utils::read_file(dir.join("package.json")).ok()?;
+    let package_json: json::Value = json::from_str(&file_contents).ok()?;
+    let name = package_json.get("name")?.as_str()?;
+    Some(name.to_string())
+}
+
 /// Contract the root component of a path
 ///
 /// Replaces the `top_level_path` in a given `full_path` with the provided
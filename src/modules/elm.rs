@@ -1,7 +1,7 @@
 use super::{Context, Module, RootModuleConfig, SegmentConfig};
 
 use crate::configs::elm::ElmConfig;
-use crate::utils;
+use crate::formatter::version::format_version;
 
 /// Creates a module with the current Elm version
 ///
@@ -23,8 +23,8 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
         return None;
     }
 
-    let elm_version = utils::exec_cmd("elm", &["--version"])?.stdout;
-    let formatted_version = Some(format!("v{}", elm_version.trim()))?;
+    let elm_version = context.exec_cmd("elm", "elm", &["--version"])?.stdout;
+    let formatted_version = format_version(&elm_version);
 
     let mut module = context.new_module("elm");
     let config: ElmConfig = ElmConfig::try_load(module.config);
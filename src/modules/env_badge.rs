@@ -0,0 +1,27 @@
+use super::{Context, Module, RootModuleConfig, SegmentConfig};
+
+use crate::configs::env_badge::EnvBadgeConfig;
+
+/// Creates a module with a badge for the value of the chosen environment variable
+///
+/// Will display the label of the first rule whose `value` equals the value of the
+/// `variable`, e.g. a red `PROD` while `APP_ENV=production`. Values without a rule
+/// are not displayed.
+pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
+    let mut module = context.new_module("env_badge");
+    let config: EnvBadgeConfig = EnvBadgeConfig::try_load(module.config);
+
+    let env_value = context.get_env(config.variable?)?;
+    let rule = config
+        .rules
+        .iter()
+        .find(|rule| rule.value == env_value.trim())?;
+
+    module.set_style(rule.style.unwrap_or(config.style));
+    module.get_prefix().set_value("in ");
+
+    module.create_segment("symbol", &config.symbol);
+    module.create_segment("label", &SegmentConfig::new(rule.label));
+
+    Some(module)
+}
@@ -0,0 +1,161 @@
+use std::fs;
+use std::time::{Duration, SystemTime};
+
+use super::{Context, Module, SegmentConfig};
+
+use crate::config::RootModuleConfig;
+use crate::configs::file_age::FileAgeConfig;
+use crate::formatter::duration::format_duration;
+
+/// Outputs how long ago the configured file was modified
+///
+/// Will display the age of the file if all of the following criteria are met:
+///     - file_age.path is defined
+///     - the file exists, relative to the current directory
+///     - the file was modified at least `threshold` seconds ago
+pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
+    let mut module = context.new_module("file_age");
+    let config: FileAgeConfig = FileAgeConfig::try_load(module.config);
+
+    let path = context.current_dir.join(config.path?);
+    let modified = fs::metadata(&path).ok()?.modified().ok()?;
+    let age = get_file_age(modified, SystemTime::now());
+
+    if age.as_secs() < config.threshold.max(0) as u64 {
+        return None;
+    }
+
+    module.set_style(config.style);
+    module.create_segment(
+        "age",
+        &SegmentConfig::new(&format_duration(age.as_millis())),
+    );
+    module.get_prefix().set_value(config.prefix);
+
+    Some(module)
+}
+
+/// Files modified in the future (clock skew) are treated as brand new
+fn get_file_age(modified: SystemTime, now: SystemTime) -> Duration {
+    now.duration_since(modified).unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::modules::utils::test::render_module_with_config;
+    use ansi_term::Color;
+    use std::fs::File;
+    use std::io;
+
+    const DAY: Duration = Duration::from_secs(60 * 60 * 24);
+
+    #[test]
+    fn test_file_age() {
+        let modified = SystemTime::now();
+        assert_eq!(
+            get_file_age(modified, modified + 3 * DAY),
+            Duration::from_secs(3 * 60 * 60 * 24)
+        );
+        assert_eq!(get_file_age(modified + DAY, modified), Duration::default());
+    }
+
+    #[test]
+    fn test_render_age() {
+        let modified = SystemTime::now();
+        let age = get_file_age(modified, modified + 3 * DAY + Duration::from_secs(90));
+        assert_eq!(format_duration(age.as_millis()), "3d");
+    }
+
+    #[test]
+    fn no_path_configured() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        File::create(dir.path().join(".env"))?.sync_all()?;
+
+        let actual = render_module_with_config(
+            "file_age",
+            dir.path(),
+            toml::Value::Table(Default::default()),
+        );
+
+        assert_eq!(None, actual);
+        dir.close()
+    }
+
+    #[test]
+    fn missing_file() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+
+        let actual = render_module_with_config(
+            "file_age",
+            dir.path(),
+            toml::toml! {
+                [file_age]
+                path = ".env"
+            },
+        );
+
+        assert_eq!(None, actual);
+        dir.close()
+    }
+
+    #[test]
+    fn recent_file_below_threshold() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        File::create(dir.path().join(".env"))?.sync_all()?;
+
+        let actual = render_module_with_config(
+            "file_age",
+            dir.path(),
+            toml::toml! {
+                [file_age]
+                path = ".env"
+                threshold = 3600
+            },
+        );
+
+        assert_eq!(None, actual);
+        dir.close()
+    }
+
+    #[test]
+    fn recent_file_without_threshold() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        File::create(dir.path().join(".env"))?.sync_all()?;
+
+        let actual = render_module_with_config(
+            "file_age",
+            dir.path(),
+            toml::toml! {
+                [file_age]
+                path = ".env"
+            },
+        )
+        .unwrap();
+
+        assert!(actual.starts_with(&format!("aged {}", Color::Yellow.bold().prefix())));
+        dir.close()
+    }
+
+    #[test]
+    fn old_file_above_threshold() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let file = File::create(dir.path().join(".env"))?;
+        file.set_modified(SystemTime::now() - 3 * DAY - Duration::from_secs(90))?;
+        file.sync_all()?;
+
+        let actual = render_module_with_config(
+            "file_age",
+            dir.path(),
+            toml::toml! {
+                [file_age]
+                path = ".env"
+                threshold = 3600
+            },
+        );
+
+        let expected = Some(format!("aged {} ", Color::Yellow.bold().paint("3d")));
+        assert_eq!(expected, actual);
+        dir.close()
+    }
+}
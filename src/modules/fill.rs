@@ -0,0 +1,19 @@
+use super::{Context, Module};
+use crate::config::SegmentConfig;
+use crate::formatter::string_formatter::FILL_SEGMENT;
+
+/// Creates a module padding the rest of its line, so that the modules following it
+/// are pushed to the right edge of the terminal
+///
+/// The module is left empty here, the padding being added once the whole line is
+/// known when the prompt is printed.
+pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
+    let mut module = context.new_module("fill");
+
+    module.get_prefix().set_value("");
+    module.get_suffix().set_value("");
+
+    module.create_segment(FILL_SEGMENT, &SegmentConfig::new(""));
+
+    Some(module)
+}
@@ -0,0 +1,334 @@
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use serde_json as json;
+
+use super::{Context, Module, RootModuleConfig};
+
+use crate::configs::flutter::FlutterConfig;
+use crate::formatter::version::format_version;
+use crate::utils;
+
+/// Creates a module with the current Flutter version, channel and engine
+///
+/// Will display the Flutter version if the current directory contains a
+/// `pubspec.yaml` file with a `flutter:` key, which plain Dart packages lack
+///
+/// The version is read from the checkout of the Flutter SDK, only falling back to
+/// the much slower `flutter --version` when it can't be found.
+pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
+    let has_pubspec = context
+        .try_begin_scan()?
+        .set_files(&["pubspec.yaml"])
+        .is_match();
+
+    if !has_pubspec || !is_flutter_pubspec(context) {
+        return None;
+    }
+
+    let flutter_version = match get_flutter_root(context).and_then(|root| read_sdk_version(&root)) {
+        Some(flutter_version) => flutter_version,
+        None => {
+            let flutter_stdout = context
+                .exec_cmd("flutter", "flutter", &["--version"])?
+                .stdout;
+            parse_flutter_version(&flutter_stdout)?
+        }
+    };
+
+    let mut module = context.new_module("flutter");
+    let config = FlutterConfig::try_load(module.config);
+    module.set_style(config.style);
+
+    module.create_segment("symbol", &config.symbol);
+    module.create_segment(
+        "version",
+        &config
+            .version
+            .with_value(&format_version(&flutter_version.version)),
+    );
+
+    if config.show_channel {
+        if let Some(channel) = flutter_version.channel {
+            module.create_segment(
+                "channel",
+                &config.channel.with_value(&format!(" ({})", channel)),
+            );
+        }
+    }
+
+    if config.show_engine {
+        if let Some(engine) = flutter_version.engine {
+            module.create_segment(
+                "engine",
+                &config.engine.with_value(&format!(" [{}]", engine)),
+            );
+        }
+    }
+
+    Some(module)
+}
+
+/// Whether the `pubspec.yaml` of the current directory has a `flutter:` key, either
+/// at the top level or as a dependency
+fn is_flutter_pubspec(context: &Context) -> bool {
+    utils::read_file(context.current_dir.join("pubspec.yaml")).map_or(false, |pubspec| {
+        pubspec
+            .lines()
+            .any(|line| line.trim_start().starts_with("flutter:"))
+    })
+}
+
+/// Returns the root of the Flutter SDK, from `FLUTTER_ROOT` or else from the location
+/// of the `flutter` executable in `PATH`
+fn get_flutter_root(context: &Context) -> Option<PathBuf> {
+    if let Some(flutter_root) = context.get_env("FLUTTER_ROOT") {
+        return Some(PathBuf::from(flutter_root));
+    }
+
+    let executable = if cfg!(windows) {
+        "flutter.bat"
+    } else {
+        "flutter"
+    };
+    let flutter = env::split_paths(&context.get_env("PATH")?)
+        .map(|dir| dir.join(executable))
+        .find(|path| path.is_file())?;
+    // `<root>/bin/flutter`, which is usually reached through a symlink
+    let flutter = fs::canonicalize(flutter).ok()?;
+    Some(flutter.parent()?.parent()?.to_path_buf())
+}
+
+/// Reads the version of the Flutter SDK at `root` from its files: the version from
+/// `bin/cache/flutter.version.json` or `version`, the channel from the branch checked
+/// out, and the engine revision from `bin/internal/engine.version`
+fn read_sdk_version(root: &Path) -> Option<FlutterVersion> {
+    let version_json: Option<json::Value> =
+        utils::read_file(root.join("bin/cache/flutter.version.json"))
+            .ok()
+            .and_then(|contents| json::from_str(&contents).ok());
+    let json_field = |field: &str| {
+        let value = version_json.as_ref()?.get(field)?.as_str()?;
+        Some(value.to_string())
+    };
+
+    let version = json_field("frameworkVersion")
+        .or_else(|| utils::read_file(root.join("version")).ok())
+        .map(|version| version.trim().to_string())
+        .filter(|version| !version.is_empty())?;
+    let channel = get_checked_out_branch(root).or_else(|| json_field("channel"));
+    // Shortened like `flutter --version` does
+    let engine = utils::read_file(root.join("bin/internal/engine.version"))
+        .ok()
+        .or_else(|| json_field("engineRevision"))
+        .map(|revision| revision.trim().chars().take(10).collect::<String>())
+        .filter(|revision| !revision.is_empty());
+
+    Some(FlutterVersion {
+        version,
+        channel,
+        engine,
+    })
+}
+
+/// The branch checked out in the git repo at `root`, which is the Flutter channel
+fn get_checked_out_branch(root: &Path) -> Option<String> {
+    let head = utils::read_file(root.join(".git").join("HEAD")).ok()?;
+    let branch = head.trim().trim_start_matches("ref: refs/heads/");
+    if branch == head.trim() || branch.is_empty() {
+        // A detached HEAD isn't on any channel
+        return None;
+    }
+    Some(branch.to_string())
+}
+
+#[derive(Debug, PartialEq)]
+struct FlutterVersion {
+    version: String,
+    channel: Option<String>,
+    engine: Option<String>,
+}
+
+fn parse_flutter_version(flutter_stdout: &str) -> Option<FlutterVersion> {
+    // flutter --version output looks like this:
+    // Flutter 3.7.0 • channel stable • https://github.com/flutter/flutter.git
+    // Framework • revision b06b8b2710 (3 weeks ago) • 2023-01-23 16:55:55 -0800
+    // Engine • revision b24591ed32
+    // Tools • Dart 2.19.0 • DevTools 2.20.1
+    let mut lines = flutter_stdout.lines().map(str::trim);
+
+    let flutter_line = lines.find(|line| line.starts_with("Flutter "))?;
+    let mut flutter_parts = flutter_line.split('•').map(str::trim);
+    let version = flutter_parts.next()?.trim_start_matches("Flutter ").trim();
+    if version.is_empty() {
+        return None;
+    }
+    let channel = flutter_parts
+        .find(|part| part.starts_with("channel "))
+        .map(|part| part.trim_start_matches("channel ").trim().to_string());
+
+    let engine = lines
+        .find(|line| line.starts_with("Engine "))
+        .and_then(|line| line.split("revision ").nth(1))
+        .and_then(|revision| revision.split_whitespace().next())
+        .map(String::from);
+
+    Some(FlutterVersion {
+        version: version.to_string(),
+        channel,
+        engine,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::modules::utils::test::{render_module, render_module_with_config};
+    use ansi_term::Color;
+    use std::fs;
+    use std::io;
+
+    #[test]
+    fn folder_without_pubspec() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let actual = render_module("flutter", dir.path());
+        let expected = None;
+        assert_eq!(expected, actual);
+        dir.close()
+    }
+
+    #[test]
+    fn folder_with_dart_pubspec() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        fs::write(
+            dir.path().join("pubspec.yaml"),
+            "name: hello\ndependencies:\n  path: ^1.8.0\n",
+        )?;
+        let actual = render_module("flutter", dir.path());
+        let expected = None;
+        assert_eq!(expected, actual);
+        dir.close()
+    }
+
+    #[test]
+    fn folder_with_flutter_pubspec() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        fs::write(
+            dir.path().join("pubspec.yaml"),
+            "name: hello\ndependencies:\n  flutter:\n    sdk: flutter\n",
+        )?;
+        let actual = render_module("flutter", dir.path());
+        let expected = Some(format!(
+            "via {} ",
+            Color::Blue.bold().paint("🐦 v3.7.0 (stable)")
+        ));
+        assert_eq!(expected, actual);
+        dir.close()
+    }
+
+    #[test]
+    fn folder_with_flutter_pubspec_and_engine() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        fs::write(dir.path().join("pubspec.yaml"), "flutter:\n")?;
+        let config = toml::toml! {
+            [flutter]
+            show_channel = false
+            show_engine = true
+        };
+        let actual = render_module_with_config("flutter", dir.path(), config);
+        let expected = Some(format!(
+            "via {} ",
+            Color::Blue.bold().paint("🐦 v3.7.0 [b24591ed32]")
+        ));
+        assert_eq!(expected, actual);
+        dir.close()
+    }
+
+    #[test]
+    fn test_parse_flutter_version() {
+        let input = "\
+Flutter 3.7.0 • channel stable • https://github.com/flutter/flutter.git
+Framework • revision b06b8b2710 (3 weeks ago) • 2023-01-23 16:55:55 -0800
+Engine • revision b24591ed32
+Tools • Dart 2.19.0 • DevTools 2.20.1";
+        assert_eq!(
+            parse_flutter_version(input),
+            Some(FlutterVersion {
+                version: "3.7.0".to_string(),
+                channel: Some("stable".to_string()),
+                engine: Some("b24591ed32".to_string()),
+            })
+        );
+
+        let input = "\
+Flutter 3.8.0-10.1.pre • channel beta • https://github.com/flutter/flutter.git
+Framework • revision 1a0a03a41d (2 weeks ago) • 2023-02-16 22:03:58 -0600";
+        assert_eq!(
+            parse_flutter_version(input),
+            Some(FlutterVersion {
+                version: "3.8.0-10.1.pre".to_string(),
+                channel: Some("beta".to_string()),
+                engine: None,
+            })
+        );
+
+        assert_eq!(parse_flutter_version("Unexpected output"), None);
+    }
+
+    #[test]
+    fn test_read_sdk_version() -> io::Result<()> {
+        let root = tempfile::tempdir()?;
+        fs::create_dir_all(root.path().join(".git"))?;
+        fs::create_dir_all(root.path().join("bin/internal"))?;
+        fs::write(root.path().join(".git/HEAD"), "ref: refs/heads/beta\n")?;
+        fs::write(root.path().join("version"), "3.8.0-10.1.pre\n")?;
+        fs::write(
+            root.path().join("bin/internal/engine.version"),
+            "b24591ed32b7a1b5a4e3e8f7d4a8b6f1c2e3d4f5\n",
+        )?;
+
+        assert_eq!(
+            read_sdk_version(root.path()),
+            Some(FlutterVersion {
+                version: "3.8.0-10.1.pre".to_string(),
+                channel: Some("beta".to_string()),
+                engine: Some("b24591ed32".to_string()),
+            })
+        );
+        root.close()
+    }
+
+    #[test]
+    fn test_read_sdk_version_from_json() -> io::Result<()> {
+        let root = tempfile::tempdir()?;
+        fs::create_dir_all(root.path().join(".git"))?;
+        fs::create_dir_all(root.path().join("bin/cache"))?;
+        // Detached, e.g. after checking out a release tag
+        fs::write(
+            root.path().join(".git/HEAD"),
+            "b06b8b2710955028a6b562f5aa6fe62941d6febf\n",
+        )?;
+        fs::write(
+            root.path().join("bin/cache/flutter.version.json"),
+            r#"{"frameworkVersion": "3.19.0", "channel": "stable", "engineRevision": "04817c99c9"}"#,
+        )?;
+
+        assert_eq!(
+            read_sdk_version(root.path()),
+            Some(FlutterVersion {
+                version: "3.19.0".to_string(),
+                channel: Some("stable".to_string()),
+                engine: Some("04817c99c9".to_string()),
+            })
+        );
+        root.close()
+    }
+
+    #[test]
+    fn test_read_sdk_version_without_sdk() -> io::Result<()> {
+        let root = tempfile::tempdir()?;
+        assert_eq!(read_sdk_version(root.path()), None);
+        root.close()
+    }
+}
@@ -1,3 +1,5 @@
+use git2::Repository;
+use regex::Regex;
 use unicode_segmentation::UnicodeSegmentation;
 
 use super::{Context, Module, RootModuleConfig};
@@ -6,7 +8,9 @@ use crate::configs::git_branch::GitBranchConfig;
 
 /// Creates a module with the Git branch in the current directory
 ///
-/// Will display the branch name if the current directory is a git repo
+/// Will display the branch name if the current directory is a git repo, followed by
+/// the branch description (`branch.<name>.description`) when one is set and
+/// `show_description` is enabled
 pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     let mut module = context.new_module("git_branch");
     let config = GitBranchConfig::try_load(module.config);
@@ -44,9 +48,108 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
         &config.branch_name.with_value(&truncated_and_symbol),
     );
 
+    if config.show_pr {
+        let ref_names = match context.lock_git_repo() {
+            Some(git_repo) => get_head_ref_names(&git_repo, branch_name),
+            None => vec![branch_name.to_string()],
+        };
+        if let Some(pr_number) = find_pr_number(&ref_names, &config.pr_patterns) {
+            module.create_segment("pr", &config.pr.with_value(&format!(" #{}", pr_number)));
+        }
+    }
+
+    if config.show_description {
+        let description = context
+            .lock_git_repo()
+            .and_then(|git_repo| get_branch_description(&git_repo, branch_name));
+        if let Some(description) = description.and_then(|description| {
+            format_description(&description, config.description_length, &truncation_symbol)
+        }) {
+            module.create_segment(
+                "description",
+                &config
+                    .description
+                    .with_value(&format!(" ({})", description)),
+            );
+        }
+    }
+
     Some(module)
 }
 
+/// Returns the description set with `git branch --edit-description` for `branch_name`
+fn get_branch_description(git_repo: &Repository, branch_name: &str) -> Option<String> {
+    git_repo
+        .config()
+        .ok()?
+        .get_string(&format!("branch.{}.description", branch_name))
+        .ok()
+}
+
+/// Formats the first line of a branch description, truncated to `length` graphemes
+///
+/// Returns `None` for an empty description or a non-positive `length`.
+fn format_description(description: &str, length: i64, truncation_symbol: &str) -> Option<String> {
+    let description = description.lines().next()?.trim();
+    if description.is_empty() || length <= 0 {
+        return None;
+    }
+
+    let length = length as usize;
+    let truncated = get_graphemes(description, length);
+    if length < graphemes_len(description) {
+        Some(truncated + truncation_symbol)
+    } else {
+        Some(truncated)
+    }
+}
+
+/// Returns the names HEAD is known by: the branch name, or when HEAD is
+/// detached, the full names of all references pointing at the same commit
+fn get_head_ref_names(git_repo: &Repository, branch_name: &str) -> Vec<String> {
+    if !git_repo.head_detached().unwrap_or(false) {
+        return vec![branch_name.to_string()];
+    }
+
+    let head_oid = match git_repo.head().ok().and_then(|head| head.target()) {
+        Some(oid) => oid,
+        None => return Vec::new(),
+    };
+    let references = match git_repo.references() {
+        Ok(references) => references,
+        Err(_) => return Vec::new(),
+    };
+
+    references
+        .filter_map(Result::ok)
+        .filter(|reference| reference.resolve().ok().and_then(|r| r.target()) == Some(head_oid))
+        .filter_map(|reference| reference.name().map(ToString::to_string))
+        .collect()
+}
+
+/// Extracts a pull request number from the first ref name matching one of `patterns`
+///
+/// The number is taken from the first capture group of the pattern.
+fn find_pr_number(ref_names: &[String], patterns: &[&str]) -> Option<String> {
+    let regexes: Vec<Regex> = patterns
+        .iter()
+        .filter_map(|pattern| match Regex::new(pattern) {
+            Ok(regex) => Some(regex),
+            Err(error) => {
+                log::warn!("Invalid pull request pattern \"{}\": {}", pattern, error);
+                None
+            }
+        })
+        .collect();
+
+    ref_names.iter().find_map(|name| {
+        regexes.iter().find_map(|regex| {
+            let captures = regex.captures(name)?;
+            Some(captures.get(1)?.as_str().to_string())
+        })
+    })
+}
+
 fn get_graphemes(text: &str, length: usize) -> String {
     UnicodeSegmentation::graphemes(text, true)
         .take(length)
@@ -57,3 +160,68 @@ fn get_graphemes(text: &str, length: usize) -> String {
 fn graphemes_len(text: &str) -> usize {
     UnicodeSegmentation::graphemes(&text[..], true).count()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DEFAULT_PATTERNS: &[&str] = &[r"^pr-(\d+)$", r"^refs/pull/(\d+)/head$"];
+
+    #[test]
+    fn pr_number_from_branch_name() {
+        let names = vec!["pr-123".to_string()];
+        assert_eq!(
+            find_pr_number(&names, DEFAULT_PATTERNS),
+            Some("123".to_string())
+        );
+    }
+
+    #[test]
+    fn pr_number_from_pull_ref() {
+        let names = vec![
+            "refs/heads/master".to_string(),
+            "refs/pull/42/head".to_string(),
+        ];
+        assert_eq!(
+            find_pr_number(&names, DEFAULT_PATTERNS),
+            Some("42".to_string())
+        );
+    }
+
+    #[test]
+    fn no_pr_number_for_normal_branch() {
+        let names = vec!["feature/pr-123-followup".to_string()];
+        assert_eq!(find_pr_number(&names, DEFAULT_PATTERNS), None);
+    }
+
+    #[test]
+    fn description_first_line_is_used() {
+        assert_eq!(
+            format_description("Rocket launch\n\nLonger explanation\n", 24, "…"),
+            Some("Rocket launch".to_string())
+        );
+    }
+
+    #[test]
+    fn description_is_truncated() {
+        assert_eq!(
+            format_description("Prepare the rocket for launch", 6, "…"),
+            Some("Prepar…".to_string())
+        );
+    }
+
+    #[test]
+    fn empty_description_is_skipped() {
+        assert_eq!(format_description("  \n", 24, "…"), None);
+        assert_eq!(format_description("Rocket launch", 0, "…"), None);
+    }
+
+    #[test]
+    fn invalid_pattern_is_skipped() {
+        let names = vec!["review/7".to_string()];
+        assert_eq!(
+            find_pr_number(&names, &["(", r"^review/(\d+)$"]),
+            Some("7".to_string())
+        );
+    }
+}
@@ -1,7 +1,7 @@
 use super::{Context, Module, RootModuleConfig};
-use git2::Repository;
 
 use crate::configs::git_commit::GitCommitConfig;
+use crate::formatter::duration::format_duration;
 
 /// Creates a module with the Git commit in the current directory
 ///
@@ -20,9 +20,7 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
         .set_style(config.style);
     module.set_style(config.style);
 
-    let repo = context.get_repo().ok()?;
-    let repo_root = repo.root.as_ref()?;
-    let git_repo = Repository::open(repo_root).ok()?;
+    let git_repo = context.lock_git_repo()?;
 
     let is_detached = git_repo.head_detached().ok()?;
     if config.only_detached && !is_detached {
@@ -40,9 +38,27 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
         )),
     );
 
+    if config.show_time_ago {
+        let commit_time = head_commit.author().when().seconds();
+        let now = chrono::Local::now().timestamp();
+        module.create_segment(
+            "time_ago",
+            &config
+                .time_ago
+                .with_value(&format!(" {}", render_time_ago(now - commit_time))),
+        );
+    }
+
     Some(module)
 }
 
+/// Renders the age of a commit by its two largest units, e.g. `3d4h ago`
+fn render_time_ago(seconds: i64) -> String {
+    // Commits dated in the future (clock skew) are treated as brand new
+    let millis = seconds.max(0) as u128 * 1000;
+    format!("{} ago", format_duration(millis))
+}
+
 /// len specifies length of hex encoded string
 pub fn id_to_hex_abbrev(bytes: &[u8], len: usize) -> String {
     bytes
@@ -54,3 +70,29 @@ pub fn id_to_hex_abbrev(bytes: &[u8], len: usize) -> String {
         .take(len)
         .collect()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_time_ago_seconds() {
+        assert_eq!(render_time_ago(42), "42s ago")
+    }
+    #[test]
+    fn test_time_ago_hours() {
+        assert_eq!(render_time_ago(3 * 60 * 60 + 59 * 60), "3h59m ago")
+    }
+    #[test]
+    fn test_time_ago_days() {
+        assert_eq!(render_time_ago(3 * 24 * 60 * 60 + 1), "3d ago")
+    }
+    #[test]
+    fn test_time_ago_years() {
+        assert_eq!(render_time_ago(2 * 365 * 24 * 60 * 60), "730d ago")
+    }
+    #[test]
+    fn test_time_ago_in_future() {
+        assert_eq!(render_time_ago(-100), "0ms ago")
+    }
+}
@@ -6,6 +6,8 @@ use crate::config::SegmentConfig;
 use crate::configs::git_status::{CountConfig, GitStatusConfig};
 use std::borrow::BorrowMut;
 use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::Read;
 
 /// Creates a module with the Git branch in the current directory
 ///
@@ -15,12 +17,23 @@ use std::collections::HashMap;
 ///   - `⇡` – This branch is ahead of the branch being tracked
 ///   - `⇣` – This branch is behind of the branch being tracked
 ///   - `⇕` – This branch has diverged from the branch being tracked
+///   - `⚑` – This branch is ahead by at least `ahead_threshold` commits (off by default)
+///   - `…` – The file status was skipped as the repo has more than `large_repo_threshold` files
+///   - `∅` – Nothing was committed yet, e.g. right after `git init`
 ///   - `?` — There are untracked files in the working directory
 ///   - `$` — A stash exists for the local repository
 ///   - `!` — There are file modifications in the working directory
 ///   - `+` — A new file has been added to the staging area
 ///   - `»` — A renamed file has been added to the staging area
 ///   - `✘` — A file's deletion has been added to the staging area
+///
+/// With `show_conflict_progress` enabled, a merge, rebase or cherry-pick with conflicts
+/// also shows how many conflicted files are resolved and how many remain, e.g. `1/2`
+///
+/// With `summarize_untracked` enabled, untracked files are counted by top-level
+/// directory, and a single directory holding all of them is named, e.g. `?build/`
+///
+/// With `show_describe` enabled, the nearest tag is shown too, like `git describe --tags`
 pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     let repo = context.get_repo().ok()?;
     let branch_name = repo.branch.as_ref()?;
@@ -40,7 +53,16 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
         .set_style(config.style);
     module.set_style(config.style);
 
-    let repo_status = get_repo_status(repository.borrow_mut());
+    // Getting the status of gigantic working trees can take seconds, so skip it
+    let is_large_repo = config.large_repo_threshold > 0
+        && get_index_entry_count(&repository).map_or(false, |count| {
+            i64::from(count) > config.large_repo_threshold
+        });
+    let repo_status = if is_large_repo {
+        Err(git2::Error::from_str("Repo too large to get its status"))
+    } else {
+        get_repo_status(repository.borrow_mut())
+    };
     log::debug!("Repo status: {:?}", repo_status);
 
     let ahead_behind = get_ahead_behind(&repository, branch_name);
@@ -50,8 +72,18 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
         log::debug!("Repo ahead/behind: {:?}", ahead_behind);
     }
 
+    // Add the large repo segment
+    if is_large_repo {
+        module.create_segment("large_repo", &config.large_repo);
+    }
+
+    // Add the no commits segment
+    if is_head_unborn(&repository) {
+        module.create_segment("no_commits", &config.no_commits);
+    }
+
     // Add the conflicted segment
-    if let Ok(repo_status) = repo_status {
+    if let Ok(repo_status) = &repo_status {
         create_segment_with_count(
             &mut module,
             "conflicted",
@@ -61,6 +93,29 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
         );
     }
 
+    // Add the conflict progress segments, e.g. `1/2` once one of three conflicts is resolved
+    if config.show_conflict_progress {
+        if let Ok(repo_status) = &repo_status {
+            if repo_status.conflicted > 0 {
+                let resolved = count_resolved_paths(&repository, &repo_status.conflicted_paths);
+                module.create_segment(
+                    "conflict_resolved",
+                    &config.conflict_resolved.with_value(&resolved.to_string()),
+                );
+                module.create_segment(
+                    "conflict_separator",
+                    &SegmentConfig::new(config.conflict_separator),
+                );
+                module.create_segment(
+                    "conflict_remaining",
+                    &config
+                        .conflict_remaining
+                        .with_value(&repo_status.conflicted.to_string()),
+                );
+            }
+        }
+    }
+
     // Add the ahead/behind segment
     if let Ok((ahead, behind)) = ahead_behind {
         let add_ahead = |m: &mut Module<'a>| {
@@ -105,10 +160,15 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
         if behind > 0 && ahead == 0 {
             add_behind(&mut module);
         }
+
+        // Nudge to push once enough commits are waiting
+        if config.ahead_threshold > 0 && ahead as i64 >= config.ahead_threshold {
+            module.create_segment("nudge", &config.nudge_symbol);
+        }
     }
 
     // Add the stashed segment
-    if let Ok(repo_status) = repo_status {
+    if let Ok(repo_status) = &repo_status {
         create_segment_with_count(
             &mut module,
             "stashed",
@@ -119,7 +179,7 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     }
 
     // Add all remaining status segments
-    if let Ok(repo_status) = repo_status {
+    if let Ok(repo_status) = &repo_status {
         create_segment_with_count(
             &mut module,
             "deleted",
@@ -152,13 +212,45 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
             config.staged_count,
         );
 
-        create_segment_with_count(
-            &mut module,
-            "untracked",
-            repo_status.untracked,
-            &config.untracked,
-            config.untracked_count,
-        );
+        let untracked_entries = if config.summarize_untracked && repo_status.untracked > 0 {
+            Some(group_untracked_paths(&repo_status.untracked_paths))
+        } else {
+            None
+        };
+
+        match untracked_entries.as_deref() {
+            Some([directory]) if directory.ends_with('/') => {
+                module.create_segment("untracked", &config.untracked);
+                module.create_segment(
+                    "untracked_summary",
+                    &config.untracked_summary.with_value(directory),
+                );
+            }
+            Some(entries) => create_segment_with_count(
+                &mut module,
+                "untracked",
+                entries.len(),
+                &config.untracked,
+                config.untracked_count,
+            ),
+            None => create_segment_with_count(
+                &mut module,
+                "untracked",
+                repo_status.untracked,
+                &config.untracked,
+                config.untracked_count,
+            ),
+        }
+    }
+
+    // Add the describe segment, e.g. `v1.2.0-3-gabcdef0`
+    if config.show_describe {
+        if let Some(describe) = get_describe(context) {
+            if !module.is_empty() {
+                module.create_segment("describe_separator", &SegmentConfig::new(" "));
+            }
+            module.create_segment("describe", &config.describe.with_value(&describe));
+        }
     }
 
     if module.is_empty() {
@@ -187,6 +279,23 @@ fn create_segment_with_count<'a>(
     }
 }
 
+/// Whether HEAD points to a branch without any commits yet
+fn is_head_unborn(repository: &Repository) -> bool {
+    match repository.head() {
+        Err(error) => error.code() == git2::ErrorCode::UnbornBranch,
+        Ok(_) => false,
+    }
+}
+
+/// Describes HEAD by the nearest tag and the commits since, as `git describe --tags` does
+fn get_describe(context: &Context) -> Option<String> {
+    let repository = context.lock_git_repo()?;
+    let describe = repository
+        .describe(git2::DescribeOptions::new().describe_tags())
+        .ok()?;
+    describe.format(None).ok()
+}
+
 /// Gets the number of files in various git states (staged, modified, deleted, etc...)
 fn get_repo_status(repository: &mut Repository) -> Result<RepoStatus, git2::Error> {
     let mut status_options = git2::StatusOptions::new();
@@ -201,11 +310,23 @@ fn get_repo_status(repository: &mut Repository) -> Result<RepoStatus, git2::Erro
         .renames_index_to_workdir(true)
         .include_unmodified(true);
 
-    let statuses: Vec<Status> = repository
-        .statuses(Some(&mut status_options))?
-        .iter()
-        .map(|s| s.status())
-        .collect();
+    // Untracked directories are listed once, e.g. `build/`
+    let (statuses, conflicted_paths, untracked_paths) = {
+        let entries = repository.statuses(Some(&mut status_options))?;
+        let statuses: Vec<Status> = entries.iter().map(|s| s.status()).collect();
+        let paths_where = |predicate: fn(Status) -> bool| -> Vec<String> {
+            entries
+                .iter()
+                .filter(|entry| predicate(entry.status()))
+                .filter_map(|entry| entry.path().map(String::from))
+                .collect()
+        };
+        (
+            statuses,
+            paths_where(is_conflicted),
+            paths_where(is_untracked),
+        )
+    };
 
     if statuses.is_empty() {
         return Err(git2::Error::from_str("Repo has no status"));
@@ -215,17 +336,34 @@ fn get_repo_status(repository: &mut Repository) -> Result<RepoStatus, git2::Erro
 
     let repo_status: RepoStatus = RepoStatus {
         conflicted: *statuses_count.get("conflicted").unwrap_or(&0),
+        conflicted_paths,
         deleted: *statuses_count.get("deleted").unwrap_or(&0),
         renamed: *statuses_count.get("renamed").unwrap_or(&0),
         modified: *statuses_count.get("modified").unwrap_or(&0),
         staged: *statuses_count.get("staged").unwrap_or(&0),
         untracked: *statuses_count.get("untracked").unwrap_or(&0),
+        untracked_paths,
         stashed: stashed_count(repository)?,
     };
 
     Ok(repo_status)
 }
 
+/// Groups untracked paths by their top-level directory, e.g. `build/a.o` and `build/b.o`
+/// into `build/`, while files at the root of the repo stay entries of their own
+fn group_untracked_paths(paths: &[String]) -> Vec<String> {
+    let mut entries: Vec<String> = paths
+        .iter()
+        .map(|path| match path.find('/') {
+            Some(index) => path[..=index].to_string(),
+            None => path.clone(),
+        })
+        .collect();
+    entries.sort();
+    entries.dedup();
+    entries
+}
+
 fn count_statuses(statuses: Vec<Status>) -> HashMap<&'static str, usize> {
     let mut predicates: HashMap<&'static str, fn(git2::Status) -> bool> = HashMap::new();
     predicates.insert("conflicted", is_conflicted);
@@ -279,6 +417,117 @@ fn stashed_count(repository: &mut Repository) -> Result<usize, git2::Error> {
     Result::Ok(count)
 }
 
+/// Gets the number of files tracked by the repo, read from the header of its index
+/// rather than by loading the whole index
+fn get_index_entry_count(repository: &Repository) -> Option<u32> {
+    let mut header = [0; 12];
+    File::open(repository.path().join("index"))
+        .ok()?
+        .read_exact(&mut header)
+        .ok()?;
+    parse_index_entry_count(&header)
+}
+
+/// The index starts with the "DIRC" signature, a version and the entry count,
+/// each as a 4-byte big-endian number
+fn parse_index_entry_count(header: &[u8; 12]) -> Option<u32> {
+    if !header.starts_with(b"DIRC") {
+        return None;
+    }
+
+    let mut count = [0; 4];
+    count.copy_from_slice(&header[8..12]);
+    Some(u32::from_be_bytes(count))
+}
+
+/// Counts the paths whose conflicts were resolved, i.e. which were conflicted but no
+/// longer are, unlike `conflicted_paths`. Files git merged cleanly never had conflicts.
+fn count_resolved_paths(repository: &Repository, conflicted_paths: &[String]) -> usize {
+    fs::read(repository.path().join("index"))
+        .ok()
+        .and_then(|index| parse_resolve_undo_paths(&index))
+        .unwrap_or_default()
+        .iter()
+        .filter(|path| !conflicted_paths.contains(path))
+        .count()
+}
+
+/// Reads the paths recorded by the resolve-undo ("REUC") extension of the index, which
+/// git adds for each conflicted file once its resolution is staged. git2 doesn't expose
+/// the extension, so the index is read past its entries to find it.
+fn parse_resolve_undo_paths(index: &[u8]) -> Option<Vec<String>> {
+    let mut header = [0; 12];
+    header.copy_from_slice(index.get(..12)?);
+    let entry_count = parse_index_entry_count(&header)?;
+    let version = index[7];
+
+    let mut offset = 12;
+    for _ in 0..entry_count {
+        // Entries are 62 bytes of stat data, hash and flags, followed by the path
+        let flags = index.get(offset + 60..offset + 62)?;
+        let is_extended = flags[0] & 0x40 != 0;
+        let path_start = offset + if is_extended { 64 } else { 62 };
+
+        if version >= 4 {
+            // The path is compressed against the previous one, after a varint
+            let varint_len = index
+                .get(path_start..)?
+                .iter()
+                .position(|b| b & 0x80 == 0)?;
+            let (_, rest) = split_at_nul(index.get(path_start + varint_len + 1..)?)?;
+            offset = index.len() - rest.len();
+        } else {
+            // The path is padded with NULs to a multiple of 8 bytes
+            let (path, _) = split_at_nul(index.get(path_start..)?)?;
+            offset += (path_start - offset + path.len() + 8) & !7;
+        }
+    }
+
+    // Extensions follow the entries, up to the 20 byte hash ending the index
+    let extensions_end = index.len().checked_sub(20)?;
+    while offset + 8 <= extensions_end {
+        let signature = &index[offset..offset + 4];
+        let mut size = [0; 4];
+        size.copy_from_slice(&index[offset + 4..offset + 8]);
+        let data_end = offset + 8 + u32::from_be_bytes(size) as usize;
+        let data = index.get(offset + 8..data_end)?;
+        if signature == b"REUC" {
+            return parse_resolve_undo_extension(data);
+        }
+        offset = data_end;
+    }
+
+    Some(Vec::new())
+}
+
+/// Each resolve-undo entry is a path and the octal modes of its three stages, each
+/// NUL-terminated, followed by the hash of each stage with a non-zero mode
+fn parse_resolve_undo_extension(mut data: &[u8]) -> Option<Vec<String>> {
+    let mut paths = Vec::new();
+    while !data.is_empty() {
+        let (path, rest) = split_at_nul(data)?;
+        data = rest;
+
+        let mut stage_count = 0;
+        for _ in 0..3 {
+            let (mode, rest) = split_at_nul(data)?;
+            data = rest;
+            if mode.iter().any(|digit| *digit != b'0') {
+                stage_count += 1;
+            }
+        }
+        data = data.get(stage_count * 20..)?;
+
+        paths.push(String::from_utf8_lossy(path).into_owned());
+    }
+    Some(paths)
+}
+
+fn split_at_nul(bytes: &[u8]) -> Option<(&[u8], &[u8])> {
+    let nul = bytes.iter().position(|byte| *byte == 0)?;
+    Some((&bytes[..nul], &bytes[nul + 1..]))
+}
+
 /// Compares the current branch with the branch it is tracking to determine how
 /// far ahead or behind it is in relation
 fn get_ahead_behind(
@@ -295,13 +544,72 @@ fn get_ahead_behind(
     repository.graph_ahead_behind(branch_oid, tracking_oid)
 }
 
-#[derive(Default, Debug, Copy, Clone)]
+#[derive(Default, Debug, Clone)]
 struct RepoStatus {
     conflicted: usize,
+    conflicted_paths: Vec<String>,
     deleted: usize,
     renamed: usize,
     modified: usize,
     staged: usize,
     untracked: usize,
+    untracked_paths: Vec<String>,
     stashed: usize,
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_index_entry_count() {
+        let header = *b"DIRC\0\0\0\x02\0\x01\x86\xa0";
+        assert_eq!(parse_index_entry_count(&header), Some(100_000));
+
+        let header = *b"NOPE\0\0\0\x02\0\x01\x86\xa0";
+        assert_eq!(parse_index_entry_count(&header), None);
+    }
+
+    #[test]
+    fn test_parse_resolve_undo_paths() {
+        // A version 2 index with a single entry, followed by a resolve-undo
+        // extension for it and one more path
+        let mut index = b"DIRC\0\0\0\x02\0\0\0\x01".to_vec();
+        index.extend_from_slice(&[0; 60]);
+        index.extend_from_slice(b"\0\x08notes.md\0\0");
+        let mut resolve_undo = b"notes.md\0100644\0100644\0100644\0".to_vec();
+        resolve_undo.extend_from_slice(&[0xaa; 60]);
+        resolve_undo.extend_from_slice(b"readme.md\0100644\00\0100644\0");
+        resolve_undo.extend_from_slice(&[0xbb; 40]);
+        index.extend_from_slice(b"TREE\0\0\0\x02\0\0REUC");
+        index.extend_from_slice(&(resolve_undo.len() as u32).to_be_bytes());
+        index.extend_from_slice(&resolve_undo);
+        index.extend_from_slice(&[0; 20]);
+
+        assert_eq!(
+            parse_resolve_undo_paths(&index),
+            Some(vec!["notes.md".to_string(), "readme.md".to_string()])
+        );
+
+        let without_extensions = [&index[..84], &[0; 20]].concat();
+        assert_eq!(parse_resolve_undo_paths(&without_extensions), Some(vec![]));
+    }
+
+    #[test]
+    fn test_group_untracked_paths() {
+        let paths: Vec<String> = ["build/a.o", "build/b.o", "build/obj/c.o"]
+            .iter()
+            .map(|path| path.to_string())
+            .collect();
+        assert_eq!(group_untracked_paths(&paths), vec!["build/"]);
+
+        let paths: Vec<String> = ["notes.txt", "build/a.o", "docs/", "build/b.o"]
+            .iter()
+            .map(|path| path.to_string())
+            .collect();
+        assert_eq!(
+            group_untracked_paths(&paths),
+            vec!["build/", "docs/", "notes.txt"]
+        );
+    }
+}
@@ -0,0 +1,90 @@
+use super::{Context, Module, RootModuleConfig};
+
+use crate::configs::gleam::GleamConfig;
+use crate::formatter::version::format_version;
+
+/// Creates a module with the current Gleam version
+///
+/// Will display the Gleam version if any of the following criteria are met:
+///     - The current directory contains a `gleam.toml` file
+///     - The current directory contains a `*.gleam` file
+pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
+    let is_gleam_project = context
+        .try_begin_scan()?
+        .set_files(&["gleam.toml"])
+        .set_extensions(&["gleam"])
+        .is_match();
+
+    if !is_gleam_project {
+        return None;
+    }
+
+    let gleam_version = context.exec_cmd("gleam", "gleam", &["--version"])?.stdout;
+    let formatted_version = format_gleam_version(&gleam_version)?;
+
+    let mut module = context.new_module("gleam");
+    let config = GleamConfig::try_load(module.config);
+    module.set_style(config.style);
+
+    module.create_segment("symbol", &config.symbol);
+    module.create_segment("version", &config.version.with_value(&formatted_version));
+
+    Some(module)
+}
+
+fn format_gleam_version(gleam_version: &str) -> Option<String> {
+    // gleam 0.9.0
+    let version = gleam_version.trim().trim_start_matches("gleam ");
+    if version.is_empty() {
+        return None;
+    }
+
+    Some(format_version(version))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::modules::utils::test::render_module;
+    use ansi_term::Color;
+    use std::fs::File;
+    use std::io;
+
+    #[test]
+    fn folder_without_gleam() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let actual = render_module("gleam", dir.path());
+        let expected = None;
+        assert_eq!(expected, actual);
+        dir.close()
+    }
+
+    #[test]
+    fn folder_with_gleam_toml() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        File::create(dir.path().join("gleam.toml"))?.sync_all()?;
+        let actual = render_module("gleam", dir.path());
+        let expected = Some(format!("via {} ", Color::Purple.bold().paint("⭐ v0.9.0")));
+        assert_eq!(expected, actual);
+        dir.close()
+    }
+
+    #[test]
+    fn folder_with_gleam_file() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        File::create(dir.path().join("main.gleam"))?.sync_all()?;
+        let actual = render_module("gleam", dir.path());
+        let expected = Some(format!("via {} ", Color::Purple.bold().paint("⭐ v0.9.0")));
+        assert_eq!(expected, actual);
+        dir.close()
+    }
+
+    #[test]
+    fn test_format_gleam_version() {
+        assert_eq!(
+            format_gleam_version("gleam 0.9.0\n"),
+            Some("v0.9.0".to_string())
+        );
+        assert_eq!(format_gleam_version(""), None);
+    }
+}
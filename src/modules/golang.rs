@@ -1,3 +1,6 @@
+use std::path::Path;
+
+use super::utils::toolchain_source::{self, ToolchainSource};
 use super::{Context, Module, RootModuleConfig};
 
 use crate::configs::go::GoConfig;
@@ -14,6 +17,11 @@ use crate::utils;
 ///     - Current directory contains a `.go-version` file
 ///     - Current directory contains a `Godeps` directory
 ///     - Current directory contains a file with the `.go` extension
+///
+/// Disabling cgo with `CGO_ENABLED=0` and passing build tags in `GOFLAGS` are displayed too.
+///
+/// With `show_module_path`, the path of the module declared by the nearest `go.mod` is
+/// displayed as well, or only its last element with `module_name_only`.
 pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     let is_go_project = context
         .try_begin_scan()?
@@ -39,13 +47,133 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     module.set_style(config.style);
     module.create_segment("symbol", &config.symbol);
 
-    let formatted_version =
-        format_go_version(&utils::exec_cmd("go", &["version"])?.stdout.as_str())?;
+    let formatted_version = format_go_version(
+        &context
+            .exec_cmd("golang", "go", &["version"])?
+            .stdout
+            .as_str(),
+    )?;
     module.create_segment("version", &config.version.with_value(&formatted_version));
 
+    if config.show_source {
+        let source = get_go_source(context);
+        module.create_segment(
+            "source",
+            &config.source.with_value(&format!(" ({})", source.as_str())),
+        );
+    }
+
+    if is_cgo_disabled(context.get_env("CGO_ENABLED").as_deref()) {
+        module.create_segment("cgo", &config.cgo.with_value(" cgo:off"));
+    }
+
+    if let Some(build_tags) = context
+        .get_env("GOFLAGS")
+        .and_then(|goflags| get_build_tags(&goflags))
+    {
+        module.create_segment(
+            "build_tags",
+            &config.build_tags.with_value(&format!(" [{}]", build_tags)),
+        );
+    }
+
+    if config.show_module_path {
+        if let Some(module_path) = get_go_module_path(&context.current_dir) {
+            let module_path = if config.module_name_only {
+                get_go_module_name(&module_path)
+            } else {
+                &module_path
+            };
+            module.create_segment(
+                "module_path",
+                &config.module_path.with_value(&format!(" {}", module_path)),
+            );
+        }
+    }
+
     Some(module)
 }
 
+/// Go is managed by asdf with a `.tool-versions` file, or by goenv with a `.go-version`
+/// file in the current directory
+fn get_go_source(context: &Context) -> ToolchainSource {
+    if toolchain_source::is_asdf_managed(&context.current_dir, "golang") {
+        ToolchainSource::Asdf
+    } else if context.current_dir.join(".go-version").is_file() {
+        ToolchainSource::File
+    } else {
+        ToolchainSource::Path
+    }
+}
+
+/// cgo is enabled by default, so only an explicit `0` disables it
+fn is_cgo_disabled(cgo_enabled: Option<&str>) -> bool {
+    cgo_enabled.map(str::trim) == Some("0")
+}
+
+/// Returns the value of the last `-tags` flag in `GOFLAGS`, whose flags always take
+/// the `-flag=value` form
+fn get_build_tags(goflags: &str) -> Option<String> {
+    let tags = goflags
+        .split_whitespace()
+        .filter_map(|flag| {
+            let mut parts = flag.trim_start_matches('-').splitn(2, '=');
+            match (parts.next(), parts.next()) {
+                (Some("tags"), Some(tags)) => Some(tags),
+                _ => None,
+            }
+        })
+        .last()?;
+
+    if tags.is_empty() {
+        return None;
+    }
+
+    Some(tags.to_string())
+}
+
+/// Returns the module path declared by the `go.mod` of `current_dir` or of its nearest
+/// parent having one
+fn get_go_module_path(current_dir: &Path) -> Option<String> {
+    let go_mod = current_dir
+        .ancestors()
+        .map(|dir| dir.join("go.mod"))
+        .find(|path| path.is_file())?;
+    parse_go_module_path(&utils::read_file(go_mod).ok()?)
+}
+
+/// Parses the path of the first `module` directive, e.g. `module example.com/rocket // v2`
+fn parse_go_module_path(go_mod: &str) -> Option<String> {
+    let module_path = go_mod
+        .lines()
+        .map(|line| line.splitn(2, "//").next().unwrap_or_default().trim())
+        .find_map(|line| {
+            let mut parts = line.splitn(2, char::is_whitespace);
+            match (parts.next(), parts.next()) {
+                (Some("module"), Some(path)) => Some(path.trim().trim_matches('"')),
+                _ => None,
+            }
+        })?;
+
+    if module_path.is_empty() {
+        return None;
+    }
+    Some(module_path.to_string())
+}
+
+/// The last element of a module path, ignoring a major version suffix, e.g. `rocket`
+/// for `example.com/rocket/v2`
+fn get_go_module_name(module_path: &str) -> &str {
+    let mut elements = module_path.rsplit('/');
+    let last = elements.next().unwrap_or(module_path);
+    let is_major_version =
+        last.len() > 1 && last.starts_with('v') && last[1..].bytes().all(|b| b.is_ascii_digit());
+    match elements.next() {
+        Some(previous) if is_major_version => previous,
+        _ => last,
+    }
+}
+
 fn format_go_version(go_stdout: &str) -> Option<String> {
     // go version output looks like this:
     // go version go1.13.3 linux/amd64
@@ -66,7 +194,7 @@ fn format_go_version(go_stdout: &str) -> Option<String> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::modules::utils::test::render_module;
+    use crate::modules::utils::test::{render_module, render_module_with_config};
     use ansi_term::Color;
     use std::fs::{self, File};
     use std::io;
@@ -175,6 +303,115 @@ mod tests {
         dir.close()
     }
 
+    #[test]
+    fn folder_with_go_version_and_source() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        File::create(dir.path().join(".go-version"))?.sync_all()?;
+
+        let actual = render_module_with_config(
+            "golang",
+            dir.path(),
+            toml::toml! {
+                [golang]
+                show_source = true
+            },
+        );
+        let expected = Some(format!(
+            "via {} ",
+            Color::Cyan.bold().paint("🐹 v1.12.1 (file)")
+        ));
+        assert_eq!(expected, actual);
+        dir.close()
+    }
+
+    #[test]
+    fn folder_with_go_mod_module_path() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        fs::write(
+            dir.path().join("go.mod"),
+            "module github.com/starship/rocket/v2\n\ngo 1.14\n",
+        )?;
+        let sub_dir = dir.path().join("engine");
+        fs::create_dir(&sub_dir)?;
+        File::create(sub_dir.join("thrust.go"))?.sync_all()?;
+
+        let actual = render_module_with_config(
+            "golang",
+            &sub_dir,
+            toml::toml! {
+                [golang]
+                show_module_path = true
+            },
+        );
+        let expected = Some(format!(
+            "via {} ",
+            Color::Cyan
+                .bold()
+                .paint("🐹 v1.12.1 github.com/starship/rocket/v2")
+        ));
+        assert_eq!(expected, actual);
+
+        let actual = render_module_with_config(
+            "golang",
+            &sub_dir,
+            toml::toml! {
+                [golang]
+                show_module_path = true
+                module_name_only = true
+            },
+        );
+        let expected = Some(format!(
+            "via {} ",
+            Color::Cyan.bold().paint("🐹 v1.12.1 rocket")
+        ));
+        assert_eq!(expected, actual);
+        dir.close()
+    }
+
+    #[test]
+    fn test_parse_go_module_path() {
+        assert_eq!(
+            parse_go_module_path("module example.com/rocket\n\ngo 1.14\n"),
+            Some("example.com/rocket".to_string())
+        );
+        assert_eq!(
+            parse_go_module_path("// Rocket\nmodule \"example.com/rocket\" // v1\n"),
+            Some("example.com/rocket".to_string())
+        );
+        assert_eq!(parse_go_module_path("go 1.14\nmodulex foo\n"), None);
+    }
+
+    #[test]
+    fn test_get_go_module_name() {
+        assert_eq!(get_go_module_name("example.com/rocket"), "rocket");
+        assert_eq!(get_go_module_name("example.com/rocket/v2"), "rocket");
+        assert_eq!(get_go_module_name("example.com/v8"), "example.com");
+        assert_eq!(get_go_module_name("rocket"), "rocket");
+        assert_eq!(get_go_module_name("example.com/rocket/vendor"), "vendor");
+    }
+
+    #[test]
+    fn test_is_cgo_disabled() {
+        assert!(is_cgo_disabled(Some("0")));
+        assert!(!is_cgo_disabled(Some("1")));
+        assert!(!is_cgo_disabled(None));
+    }
+
+    #[test]
+    fn test_get_build_tags() {
+        assert_eq!(
+            get_build_tags("-mod=vendor -tags=netgo,osusergo"),
+            Some("netgo,osusergo".to_string())
+        );
+        assert_eq!(
+            get_build_tags("--tags=integration -tags=e2e"),
+            Some("e2e".to_string())
+        );
+        assert_eq!(get_build_tags("-mod=vendor -tags="), None);
+        assert_eq!(get_build_tags("-mod=vendor"), None);
+        assert_eq!(get_build_tags(""), None);
+    }
+
     #[test]
     fn test_format_go_version() {
         let input = "go version go1.12 darwin/amd64";
@@ -1,7 +1,7 @@
 use super::{Context, Module, RootModuleConfig, SegmentConfig};
 
 use crate::configs::haskell::HaskellConfig;
-use crate::utils;
+use crate::formatter::version::format_version;
 
 /// Creates a module with the current Haskell Stack version
 ///
@@ -20,12 +20,14 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
         return None;
     }
 
-    let haskell_version = utils::exec_cmd(
-        "stack",
-        &["ghc", "--", "--numeric-version", "--no-install-ghc"],
-    )?
-    .stdout;
-    let formatted_version = Some(format!("v{}", haskell_version.trim()))?;
+    let haskell_version = context
+        .exec_cmd(
+            "haskell",
+            "stack",
+            &["ghc", "--", "--numeric-version", "--no-install-ghc"],
+        )?
+        .stdout;
+    let formatted_version = format_version(&haskell_version);
 
     let mut module = context.new_module("haskell");
     let config: HaskellConfig = HaskellConfig::try_load(module.config);
@@ -20,32 +20,74 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
         return None;
     }
 
+    let hostnames = Hostnames::new(get_full_hostname()?, config.trim_at);
+
+    module.set_style(config.style);
+    let hostname_stacked = format!("{}{}{}", config.prefix, hostnames.short, config.suffix);
+    module.create_segment("hostname", &SegmentConfig::new(&hostname_stacked));
+    module.get_prefix().set_value("on ");
+
+    Some(module)
+}
+
+/// Both variants of the hostname, so either can be displayed without another lookup
+#[derive(Debug, PartialEq)]
+pub struct Hostnames {
+    /// The hostname cut off at the first match of `trim_at`
+    pub short: String,
+
+    /// The hostname as configured on the system, usually the FQDN
+    pub full: String,
+}
+
+impl Hostnames {
+    pub fn new(full: String, trim_at: &str) -> Self {
+        let short = match full.find(trim_at) {
+            Some(index) if !trim_at.is_empty() => full[..index].to_string(),
+            _ => full.clone(),
+        };
+        Hostnames { short, full }
+    }
+}
+
+/// Returns the hostname as configured on the system
+pub fn get_full_hostname() -> Option<String> {
     let os_hostname: OsString = gethostname::gethostname();
 
-    let host = match os_hostname.into_string() {
-        Ok(host) => host,
+    match os_hostname.into_string() {
+        Ok(host) => Some(host),
         Err(bad) => {
             log::debug!("hostname is not valid UTF!\n{:?}", bad);
-            return None;
-        }
-    };
-
-    //rustc doesn't let you do an "if" and an "if let" in the same if statement
-    // if this changes in the future this can become a lot cleaner
-    let host = if config.trim_at != "" {
-        if let Some(index) = host.find(config.trim_at) {
-            host.split_at(index).0
-        } else {
-            host.as_ref()
+            None
         }
-    } else {
-        host.as_ref()
-    };
+    }
+}
 
-    module.set_style(config.style);
-    let hostname_stacked = format!("{}{}{}", config.prefix, host, config.suffix);
-    module.create_segment("hostname", &SegmentConfig::new(&hostname_stacked));
-    module.get_prefix().set_value("on ");
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    Some(module)
+    #[test]
+    fn test_short_and_full_hostnames() {
+        let hostnames = Hostnames::new("rocket.starship.example.com".to_string(), ".");
+        assert_eq!(hostnames.short, "rocket");
+        assert_eq!(hostnames.full, "rocket.starship.example.com");
+    }
+
+    #[test]
+    fn test_hostnames_trimmed_at_domain() {
+        let hostnames = Hostnames::new("rocket.starship.example.com".to_string(), ".example.com");
+        assert_eq!(hostnames.short, "rocket.starship");
+        assert_eq!(hostnames.full, "rocket.starship.example.com");
+    }
+
+    #[test]
+    fn test_hostnames_without_trimming() {
+        let expected = Hostnames {
+            short: "rocket.local".to_string(),
+            full: "rocket.local".to_string(),
+        };
+        assert_eq!(Hostnames::new("rocket.local".to_string(), ""), expected);
+        assert_eq!(Hostnames::new("rocket.local".to_string(), ","), expected);
+    }
 }
@@ -3,7 +3,6 @@ use crate::configs::java::JavaConfig;
 use super::{Context, Module, RootModuleConfig, SegmentConfig};
 
 use crate::modules::utils::java_version_parser;
-use crate::utils;
 
 /// Creates a module with the current Java version
 ///
@@ -21,7 +20,7 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
         return None;
     }
 
-    match get_java_version() {
+    match get_java_version(context) {
         Some(java_version) => {
             let mut module = context.new_module("java");
             let config: JavaConfig = JavaConfig::try_load(module.config);
@@ -37,13 +36,13 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     }
 }
 
-fn get_java_version() -> Option<String> {
+fn get_java_version(context: &Context) -> Option<String> {
     let java_command = match std::env::var("JAVA_HOME") {
         Ok(java_home) => format!("{}/bin/java", java_home),
         Err(_) => String::from("java"),
     };
 
-    let output = utils::exec_cmd(&java_command.as_str(), &["-Xinternalversion"])?;
+    let output = context.exec_cmd("java", &java_command.as_str(), &["-Xinternalversion"])?;
     Some(format!("{}{}", output.stdout, output.stderr))
 }
 
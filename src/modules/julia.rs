@@ -1,7 +1,7 @@
 use super::{Context, Module, RootModuleConfig};
 
 use crate::configs::julia::JuliaConfig;
-use crate::utils;
+use crate::formatter::version::format_version;
 
 /// Creates a module with the current Julia version
 ///
@@ -26,8 +26,12 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     module.set_style(config.style);
     module.create_segment("symbol", &config.symbol);
 
-    let formatted_version =
-        format_julia_version(&utils::exec_cmd("julia", &["--version"])?.stdout.as_str())?;
+    let formatted_version = format_julia_version(
+        &context
+            .exec_cmd("julia", "julia", &["--version"])?
+            .stdout
+            .as_str(),
+    )?;
     module.create_segment("version", &config.version.with_value(&formatted_version));
 
     Some(module)
@@ -45,7 +49,7 @@ fn format_julia_version(julia_stdout: &str) -> Option<String> {
         .split_whitespace()
         .next()?;
 
-    Some(format!("v{}", version))
+    Some(format_version(version))
 }
 
 #[cfg(test)]
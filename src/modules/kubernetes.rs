@@ -1,3 +1,4 @@
+use regex::Regex;
 use yaml_rust::YamlLoader;
 
 use std::env;
@@ -42,6 +43,25 @@ fn parse_kubectl_file(filename: &path::PathBuf) -> Option<(String, String)> {
     get_kube_context(&contents)
 }
 
+/// Whether the context name, before aliasing, entirely matches one of the
+/// `dangerous_contexts` patterns, e.g. `.*prod.*`
+fn is_dangerous_context(kube_ctx: &str, patterns: &[&str]) -> bool {
+    patterns
+        .iter()
+        .filter_map(|pattern| match Regex::new(&format!("^(?:{})$", pattern)) {
+            Ok(regex) => Some(regex),
+            Err(error) => {
+                log::warn!(
+                    "Invalid dangerous context pattern \"{}\": {}",
+                    pattern,
+                    error
+                );
+                None
+            }
+        })
+        .any(|regex| regex.is_match(kube_ctx))
+}
+
 pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     let kube_cfg = match env::var("KUBECONFIG") {
         Ok(paths) => env::split_paths(&paths)
@@ -63,7 +83,12 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
                 return None;
             };
 
-            module.set_style(config.style);
+            // Contexts like production ones stand out, to avoid running commands there by accident
+            if is_dangerous_context(&kube_ctx, &config.dangerous_contexts) {
+                module.set_style(config.dangerous_style);
+            } else {
+                module.set_style(config.style);
+            }
             module.get_prefix().set_value(KUBERNETES_PREFIX);
 
             module.create_segment("symbol", &config.symbol);
@@ -90,6 +115,24 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
 mod tests {
     use super::*;
 
+    #[test]
+    fn dangerous_context_matched() {
+        let patterns = [".*prod.*", "admin@.*"];
+        assert!(is_dangerous_context("gke_acme_prod-cluster", &patterns));
+        assert!(is_dangerous_context("admin@staging", &patterns));
+    }
+
+    #[test]
+    fn dangerous_context_not_matched() {
+        let patterns = [".*prod.*", "admin@.*"];
+        assert!(!is_dangerous_context("dev-cluster", &patterns));
+        // Patterns match the entire context name
+        assert!(!is_dangerous_context("dev-admin@staging", &patterns));
+        assert!(!is_dangerous_context("prod", &[]));
+        // Invalid patterns are ignored
+        assert!(!is_dangerous_context("prod", &["(prod"]));
+    }
+
     #[test]
     fn parse_empty_config() {
         let input = "";
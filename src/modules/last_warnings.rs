@@ -0,0 +1,87 @@
+use std::io;
+
+use super::{Context, Module, RootModuleConfig, SegmentConfig};
+
+use crate::configs::last_warnings::LastWarningsConfig;
+use crate::session::SessionStore;
+
+/// The session store key holding the warning count of the last prompt
+const LAST_WARNINGS_KEY: &str = "last_warnings";
+
+/// Creates a module with the number of warnings logged while rendering the previous
+/// prompt of the shell session, e.g. about an invalid config
+///
+/// Will display the count if all of the following criteria are met:
+///     - The shell session has a session store, as set up by `starship init`
+///     - The previous prompt logged at least one warning
+pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
+    let mut module = context.new_module("last_warnings");
+    let config = LastWarningsConfig::try_load(module.config);
+    if config.disabled {
+        return None;
+    }
+
+    let store = SessionStore::new()?;
+    let last_warnings = get_last_warnings(&store)?;
+
+    module.set_style(config.style);
+    module.get_prefix().set_value("");
+
+    module.create_segment("symbol", &config.symbol);
+    module.create_segment("count", &SegmentConfig::new(&last_warnings.to_string()));
+
+    Some(module)
+}
+
+/// Returns the number of warnings of the previous prompt, if there were any
+fn get_last_warnings(store: &SessionStore) -> Option<usize> {
+    let count: usize = store.get(LAST_WARNINGS_KEY)?.trim().parse().ok()?;
+    Some(count).filter(|count| *count > 0)
+}
+
+/// Stores the number of warnings logged while rendering the current prompt, for the
+/// next one to display. Nothing is stored unless the module is enabled.
+pub fn record_prompt_warnings(context: &Context, count: usize) {
+    let config = LastWarningsConfig::try_load(context.config.get_module_config("last_warnings"));
+    if config.disabled {
+        return;
+    }
+
+    if let Some(store) = SessionStore::new() {
+        if let Err(error) = record_warnings(&store, count) {
+            log::debug!("Unable to store the number of warnings: {}", error);
+        }
+    }
+}
+
+/// Stores the number of warnings of the current prompt, for the next one to display
+fn record_warnings(store: &SessionStore, count: usize) -> io::Result<()> {
+    let count = count.to_string();
+    // Most prompts log no warnings, so avoid rewriting the same count every time
+    if store.get(LAST_WARNINGS_KEY).as_deref() == Some(&count) {
+        return Ok(());
+    }
+    store.set(LAST_WARNINGS_KEY, &count)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn last_warnings_shown_on_next_prompt() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+
+        let first_prompt = SessionStore::in_dir(dir.path());
+        assert_eq!(get_last_warnings(&first_prompt), None);
+        record_warnings(&first_prompt, 2)?;
+
+        let second_prompt = SessionStore::in_dir(dir.path());
+        assert_eq!(get_last_warnings(&second_prompt), Some(2));
+        record_warnings(&second_prompt, 0)?;
+
+        let third_prompt = SessionStore::in_dir(dir.path());
+        assert_eq!(get_last_warnings(&third_prompt), None);
+        dir.close()
+    }
+}
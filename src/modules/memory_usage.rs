@@ -1,15 +1,90 @@
-use byte_unit::{Byte, ByteUnit};
-use sysinfo::{RefreshKind, SystemExt};
+#[cfg(target_os = "linux")]
+use std::fs;
+#[cfg(not(target_os = "linux"))]
+use sysinfo::ProcessExt;
+use sysinfo::{Pid, RefreshKind, SystemExt};
 
+use super::utils::gauge::{render_gauge, usage_color};
 use super::{Context, Module, RootModuleConfig, Shell};
 
+use crate::config::SegmentConfig;
 use crate::configs::memory_usage::MemoryConfig;
+use crate::formatter::number::NumberLocale;
+use crate::utils::format_bytes_with_precision;
 
-fn format_kib(n_kib: u64) -> String {
-    let byte = Byte::from_unit(n_kib as f64, ByteUnit::KiB).unwrap_or_else(|_| Byte::from_bytes(0));
-    let mut display_bytes = byte.get_appropriate_unit(true).format(0);
-    display_bytes.retain(|c| c != ' ');
-    display_bytes
+/// Renders a size in KiB in its largest binary (`GiB`) or decimal (`GB`) unit with at
+/// most `precision` decimals, e.g. `8GiB`, writing the number as `locale` does if set
+fn format_kib(n_kib: u64, binary: bool, precision: usize, locale: Option<NumberLocale>) -> String {
+    let display_bytes = format_bytes_with_precision(n_kib as f64 * 1024., binary, precision);
+    let mut parts = display_bytes.splitn(2, ' ');
+    let number = parts.next().unwrap_or_default();
+    let unit = parts.next().unwrap_or_default();
+    let number = locale
+        .and_then(|locale| locale.format(number))
+        .unwrap_or_else(|| number.to_string());
+    number + unit
+}
+
+/// Returns the resident memory of the shell, as given by `STARSHIP_SHELL_PID` or else
+/// assumed to be the parent of starship
+fn get_shell_rss_kib(context: &Context) -> Option<u64> {
+    let shell_pid = context
+        .get_env("STARSHIP_SHELL_PID")
+        .and_then(|pid| pid.parse().ok());
+    get_process_rss_kib(shell_pid)
+}
+
+#[cfg(target_os = "linux")]
+fn get_process_rss_kib(pid: Option<Pid>) -> Option<u64> {
+    let pid = match pid {
+        Some(pid) => pid,
+        None => {
+            let self_status = fs::read_to_string("/proc/self/status").ok()?;
+            get_status_field(&self_status, "PPid")?.parse().ok()?
+        }
+    };
+
+    let status = fs::read_to_string(format!("/proc/{}/status", pid)).ok()?;
+    parse_vm_rss_kib(&status)
+}
+
+#[cfg(not(target_os = "linux"))]
+fn get_process_rss_kib(pid: Option<Pid>) -> Option<u64> {
+    let mut system = sysinfo::System::new();
+    let pid = match pid {
+        Some(pid) => pid,
+        None => {
+            let own_pid = sysinfo::get_current_pid().ok()?;
+            system.refresh_process(own_pid);
+            system.get_process(own_pid)?.parent()?
+        }
+    };
+
+    system.refresh_process(pid);
+    Some(system.get_process(pid)?.memory())
+}
+
+/// Returns the value of a `Name:\tvalue` line of a `/proc/<pid>/status` file
+#[cfg(target_os = "linux")]
+fn get_status_field<'a>(status: &'a str, name: &str) -> Option<&'a str> {
+    status.lines().find_map(|line| {
+        let mut parts = line.splitn(2, ':');
+        if parts.next()? == name {
+            Some(parts.next()?.trim())
+        } else {
+            None
+        }
+    })
+}
+
+/// Parses the resident set size, e.g. `VmRSS:\t    5432 kB`, which is always in kB
+#[cfg(target_os = "linux")]
+fn parse_vm_rss_kib(status: &str) -> Option<u64> {
+    get_status_field(status, "VmRSS")?
+        .split_whitespace()
+        .next()?
+        .parse()
+        .ok()
 }
 
 /// Creates a module with system memory usage information
@@ -31,6 +106,23 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     module.set_style(config.style);
     module.create_segment("symbol", &config.symbol);
 
+    let locale = match config.locale {
+        "" => None,
+        name => NumberLocale::from_name(name).or_else(|| {
+            log::warn!("Unknown memory_usage locale `{}`, using `en`", name);
+            Some(NumberLocale::ENGLISH)
+        }),
+    };
+    let precision = config.precision.max(0) as usize;
+    let binary = match config.units {
+        "binary" => true,
+        "decimal" => false,
+        units => {
+            log::warn!("Unknown memory_usage units `{}`, using `binary`", units);
+            true
+        }
+    };
+
     let system = sysinfo::System::new_with_specifics(RefreshKind::new().with_memory());
 
     let used_memory_kib = system.get_used_memory();
@@ -51,12 +143,24 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     } else {
         format!(
             "{}/{}",
-            format_kib(used_memory_kib),
-            format_kib(total_memory_kib)
+            format_kib(used_memory_kib, binary, precision, locale),
+            format_kib(total_memory_kib, binary, precision, locale)
         )
     };
     module.create_segment("ram", &config.ram.with_value(&ram));
 
+    let bar_config = &config.ram_bar;
+    if !bar_config.disabled {
+        let gauge = render_gauge(percent_mem_used, bar_config, percent_sign);
+        let style = bar_config
+            .style
+            .unwrap_or_else(|| usage_color(percent_mem_used).bold());
+        module.create_segment(
+            "ram_bar",
+            &SegmentConfig::new(&format!(" {}", gauge)).with_style(Some(style)),
+        );
+    }
+
     // swap only shown if enabled and there is swap on the system
     let total_swap_kib = system.get_total_swap();
     if config.show_swap && total_swap_kib > 0 {
@@ -68,8 +172,8 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
         } else {
             format!(
                 "{}/{}",
-                format_kib(used_swap_kib),
-                format_kib(total_swap_kib)
+                format_kib(used_swap_kib, binary, precision, locale),
+                format_kib(total_swap_kib, binary, precision, locale)
             )
         };
 
@@ -77,5 +181,84 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
         module.create_segment("swap", &config.swap.with_value(&swap));
     }
 
+    if config.show_shell_rss {
+        if let Some(shell_rss_kib) = get_shell_rss_kib(context) {
+            module.create_segment("separator", &config.separator);
+            module.create_segment(
+                "shell_rss",
+                &config
+                    .shell_rss
+                    .with_value(&format_kib(shell_rss_kib, binary, precision, locale)),
+            );
+        }
+    }
+
     Some(module)
 }
+
+#[cfg(test)]
+#[cfg(target_os = "linux")]
+mod tests {
+    use super::*;
+
+    const STATUS: &str = "\
+Name:\tbash
+Umask:\t0022
+State:\tS (sleeping)
+Pid:\t4242
+PPid:\t4200
+VmPeak:\t   12480 kB
+VmSize:\t   12412 kB
+VmHWM:\t    6100 kB
+VmRSS:\t    5432 kB
+Threads:\t1
+";
+
+    #[test]
+    fn test_parse_vm_rss() {
+        assert_eq!(parse_vm_rss_kib(STATUS), Some(5432));
+        assert_eq!(get_status_field(STATUS, "PPid"), Some("4200"));
+    }
+
+    #[test]
+    fn test_parse_vm_rss_missing() {
+        // Kernel threads have no memory of their own
+        let status = "Name:\tkthreadd\nPid:\t2\nPPid:\t0\n";
+        assert_eq!(parse_vm_rss_kib(status), None);
+    }
+
+    #[test]
+    fn test_format_kib_in_locale() {
+        let german = NumberLocale::from_name("de");
+        let english = Some(NumberLocale::ENGLISH);
+        assert_eq!(format_kib(8 * 1024 * 1024, true, 0, english), "8GiB");
+        assert_eq!(format_kib(1000 * 1024 * 1024, true, 0, english), "1,000GiB");
+        assert_eq!(format_kib(1000 * 1024 * 1024, true, 0, german), "1.000GiB");
+        assert_eq!(format_kib(1536, true, 1, german), "1,5MiB");
+    }
+
+    #[test]
+    fn test_format_kib_units() {
+        assert_eq!(format_kib(1023, true, 0, None), "1023KiB");
+        assert_eq!(format_kib(1024, true, 0, None), "1MiB");
+        assert_eq!(format_kib(1536, true, 1, None), "1.5MiB");
+        assert_eq!(format_kib(1536, false, 1, None), "1.6MB");
+        assert_eq!(format_kib(8 * 1024 * 1024, false, 1, None), "8.6GB");
+    }
+
+    #[test]
+    fn test_format_kib_default() {
+        // Rounded to whole units without grouping, as before sizes could be configured
+        let config = MemoryConfig::new();
+        let precision = config.precision as usize;
+        assert_eq!(config.locale, "");
+        assert_eq!(format_kib(7864320, true, precision, None), "8GiB");
+        assert_eq!(format_kib(1023, true, precision, None), "1023KiB");
+    }
+
+    #[test]
+    fn test_own_rss() {
+        let own_pid = std::process::id() as Pid;
+        assert!(get_process_rss_kib(Some(own_pid)).unwrap_or(0) > 0);
+    }
+}
@@ -1,6 +1,8 @@
 // While adding out new module add out module to src/module.rs ALL_MODULES const array also.
+mod ansible;
 mod aws;
-mod character;
+pub(crate) mod character;
+mod cloudflare;
 mod cmd_duration;
 mod conda;
 mod crystal;
@@ -10,32 +12,43 @@ mod docker_context;
 mod dotnet;
 mod elixir;
 mod elm;
+mod env_badge;
 mod env_var;
+mod file_age;
+mod fill;
+mod flutter;
 mod git_branch;
 mod git_commit;
 mod git_state;
 mod git_status;
+mod gleam;
 mod golang;
 mod haskell;
 mod hg_branch;
-mod hostname;
+pub(crate) mod hostname;
 mod java;
 mod jobs;
 mod julia;
 mod kubernetes;
+pub(crate) mod last_warnings;
 mod line_break;
 mod memory_usage;
 mod nix_shell;
 mod nodejs;
+mod ocaml;
 mod package;
 mod php;
+mod pulumi;
 mod python;
+mod raku;
+pub(crate) mod raw_escape;
 mod ruby;
 mod rust;
 mod singularity;
+pub(crate) mod terminal_title;
 mod terraform;
 mod time;
-mod username;
+pub(crate) mod username;
 mod utils;
 
 #[cfg(feature = "battery")]
@@ -46,13 +59,15 @@ use crate::context::{Context, Shell};
 use crate::module::Module;
 
 pub fn handle<'a>(module: &str, context: &'a Context) -> Option<Module<'a>> {
-    match module {
+    context.compute_module(module, || match module {
         // Keep these ordered alphabetically.
         // Default ordering is handled in configs/mod.rs
+        "ansible" => ansible::module(context),
         "aws" => aws::module(context),
         #[cfg(feature = "battery")]
         "battery" => battery::module(context),
         "character" => character::module(context),
+        "cloudflare" => cloudflare::module(context),
         "cmd_duration" => cmd_duration::module(context),
         "conda" => conda::module(context),
         "directory" => directory::module(context),
@@ -60,11 +75,16 @@ pub fn handle<'a>(module: &str, context: &'a Context) -> Option<Module<'a>> {
         "dotnet" => dotnet::module(context),
         "elixir" => elixir::module(context),
         "elm" => elm::module(context),
+        "env_badge" => env_badge::module(context),
         "env_var" => env_var::module(context),
+        "file_age" => file_age::module(context),
+        "fill" => fill::module(context),
+        "flutter" => flutter::module(context),
         "git_branch" => git_branch::module(context),
         "git_commit" => git_commit::module(context),
         "git_state" => git_state::module(context),
         "git_status" => git_status::module(context),
+        "gleam" => gleam::module(context),
         "golang" => golang::module(context),
         "haskell" => haskell::module(context),
         "hg_branch" => hg_branch::module(context),
@@ -73,16 +93,23 @@ pub fn handle<'a>(module: &str, context: &'a Context) -> Option<Module<'a>> {
         "jobs" => jobs::module(context),
         "julia" => julia::module(context),
         "kubernetes" => kubernetes::module(context),
+        "last_warnings" => last_warnings::module(context),
         "line_break" => line_break::module(context),
         "memory_usage" => memory_usage::module(context),
         "nix_shell" => nix_shell::module(context),
         "nodejs" => nodejs::module(context),
+        "ocaml" => ocaml::module(context),
         "package" => package::module(context),
         "php" => php::module(context),
+        "pulumi" => pulumi::module(context),
         "python" => python::module(context),
+        "raku" => raku::module(context),
         "ruby" => ruby::module(context),
         "rust" => rust::module(context),
         "singularity" => singularity::module(context),
+        "terminal_title" => terminal_title::module(context, |name| {
+            terminal_title::compute_module_text(name, context)
+        }),
         "terraform" => terraform::module(context),
         "time" => time::module(context),
         "crystal" => crystal::module(context),
@@ -91,26 +118,33 @@ pub fn handle<'a>(module: &str, context: &'a Context) -> Option<Module<'a>> {
             eprintln!("Error: Unknown module {}. Use starship module --list to list out all supported modules.", module);
             None
         }
-    }
+    })
 }
 
 pub fn description(module: &str) -> &'static str {
     match module {
+        "ansible" => "The currently installed version of Ansible core",
         "aws" => "The current AWS region and profile",
         "battery" => "The current charge of the device's battery and its current charging status",
         "character" => {
             "A character (usually an arrow) beside where the text is entered in your terminal"
         }
+        "cloudflare" => "The Cloudflare Workers project and its wrangler environment",
         "cmd_duration" => "How long the last command took to execute",
         "conda" => "The current conda environment, if $CONDA_DEFAULT_ENV is set",
         "directory" => "The current working directory",
         "docker_context" => "The current docker context",
         "dotnet" => "The relevant version of the .NET Core SDK for the current directory",
+        "env_badge" => "A badge for the value of a selected environment variable",
         "env_var" => "Displays the current value of a selected environment variable",
+        "file_age" => "How long ago a selected file was modified",
+        "fill" => "Pushes the modules after it to the right edge of the terminal",
+        "flutter" => "The currently installed version and channel of Flutter",
         "git_branch" => "The active branch of the repo in your current directory",
         "git_commit" => "The active commit of the repo in your current directory",
         "git_state" => "The current git operation, and it's progress",
         "git_status" => "Symbol representing the state of the repo",
+        "gleam" => "The currently installed version of Gleam",
         "golang" => "The currently installed version of Golang",
         "hg_branch" => "The active branch of the repo in your current directory",
         "hostname" => "The system hostname",
@@ -118,15 +152,20 @@ pub fn description(module: &str) -> &'static str {
         "jobs" => "The current number of jobs running",
         "julia" => "The currently installed version of Julia",
         "kubernetes" => "The current Kubernetes context name and, if set, the namespace",
+        "last_warnings" => "The number of warnings logged while rendering the previous prompt",
         "line_break" => "Separates the prompt into two lines",
         "memory_usage" => "Current system memory and swap usage",
         "nix_shell" => "The nix-shell environment",
         "nodejs" => "The currently installed version of NodeJS",
+        "ocaml" => "The currently installed version of OCaml",
         "package" => "The package version of the current directory's project",
         "php" => "The currently installed version of PHP",
+        "pulumi" => "The current Pulumi project and stack",
         "python" => "The currently installed version of Python",
+        "raku" => "The currently installed version of Raku",
         "ruby" => "The currently installed version of Ruby",
         "rust" => "The currently installed version of Rust",
+        "terminal_title" => "Sets the title of the terminal window",
         "terraform" => "The currently selected terraform workspace and version",
         "time" => "The current local time",
         "username" => "The active user's username",
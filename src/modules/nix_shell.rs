@@ -16,6 +16,7 @@ use crate::configs::nix_shell::NixShellConfig;
 ///     - use_name   (bool)   // print the name of the nix-shell
 ///     - impure_msg (string) // change the impure msg
 ///     - pure_msg (string)   // change the pure msg
+///     - flake_symbol (string) // change the symbol shown next to a `flake.nix`
 ///
 /// Will display the following:
 ///     - name (pure)    // use_name == true in a pure nix-shell
@@ -27,10 +28,10 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     let config: NixShellConfig = NixShellConfig::try_load(module.config);
 
     module.set_style(config.style);
-    module.create_segment("symbol", &config.symbol);
 
+    // An empty `IN_NIX_SHELL` is left behind by some tools after leaving the shell
     let shell_type = env::var("IN_NIX_SHELL").ok()?;
-    let shell_type_segment: SegmentConfig = match shell_type.as_ref() {
+    let shell_type_segment: SegmentConfig = match shell_type.trim() {
         "1" | "impure" => config.impure_msg,
         "pure" => config.pure_msg,
         _ => {
@@ -38,6 +39,16 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
         }
     };
 
+    // Shells of flakes, e.g. entered with `nix develop`, stand out from classic ones
+    let is_flake = context
+        .try_begin_scan()
+        .map_or(false, |scan| scan.set_files(&["flake.nix"]).is_match());
+    if is_flake {
+        module.create_segment("symbol", &config.flake_symbol);
+    } else {
+        module.create_segment("symbol", &config.symbol);
+    }
+
     if config.use_name {
         if let Ok(name) = env::var("name") {
             module.create_segment(
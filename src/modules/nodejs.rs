@@ -1,7 +1,7 @@
+use super::utils::toolchain_source::{self, ToolchainSource};
 use super::{Context, Module, RootModuleConfig, SegmentConfig};
 
 use crate::configs::nodejs::NodejsConfig;
-use crate::utils;
 
 /// Creates a module with the current Node.js version
 ///
@@ -21,7 +21,7 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
         return None;
     }
 
-    let node_version = utils::exec_cmd("node", &["--version"])?.stdout;
+    let node_version = context.exec_cmd("nodejs", "node", &["--version"])?.stdout;
 
     let mut module = context.new_module("nodejs");
     let config: NodejsConfig = NodejsConfig::try_load(module.config);
@@ -32,12 +32,35 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     module.create_segment("symbol", &config.symbol);
     module.create_segment("version", &SegmentConfig::new(formatted_version));
 
+    if config.show_source {
+        let source = get_node_source(context);
+        module.create_segment(
+            "source",
+            &config.source.with_value(&format!(" ({})", source.as_str())),
+        );
+    }
+
     Some(module)
 }
 
+/// Node.js is managed by asdf with a `.tool-versions` file, or by nvm once it put its
+/// `NVM_BIN` directory in `PATH`
+fn get_node_source(context: &Context) -> ToolchainSource {
+    if toolchain_source::is_asdf_managed(&context.current_dir, "nodejs") {
+        ToolchainSource::Asdf
+    } else if context.get_env("NVM_BIN").is_some() {
+        ToolchainSource::Nvm
+    } else {
+        ToolchainSource::Path
+    }
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::modules::utils::test::render_module;
+    use crate::context::Env;
+    use crate::modules::utils::test::{
+        render_module, render_module_with_config, render_module_with_env,
+    };
     use ansi_term::Color;
     use std::fs::{self, File};
     use std::io;
@@ -95,4 +118,50 @@ mod tests {
         assert_eq!(expected, actual);
         dir.close()
     }
+
+    #[test]
+    fn folder_with_tool_versions_and_source() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        File::create(dir.path().join("package.json"))?.sync_all()?;
+        fs::write(dir.path().join(".tool-versions"), "nodejs 12.0.0\n")?;
+
+        let actual = render_module_with_config(
+            "nodejs",
+            dir.path(),
+            toml::toml! {
+                [nodejs]
+                show_source = true
+            },
+        );
+        let expected = Some(format!(
+            "via {} ",
+            Color::Green.bold().paint("⬢ v12.0.0 (asdf)")
+        ));
+        assert_eq!(expected, actual);
+        dir.close()
+    }
+
+    #[test]
+    fn folder_with_nvm_source() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        File::create(dir.path().join("package.json"))?.sync_all()?;
+        let mut env = Env::default();
+        env.insert("NVM_BIN", "/home/user/.nvm/versions/node/v12.0.0/bin");
+
+        let actual = render_module_with_env(
+            "nodejs",
+            dir.path(),
+            toml::toml! {
+                [nodejs]
+                show_source = true
+            },
+            env,
+        );
+        let expected = Some(format!(
+            "via {} ",
+            Color::Green.bold().paint("⬢ v12.0.0 (nvm)")
+        ));
+        assert_eq!(expected, actual);
+        dir.close()
+    }
 }
@@ -0,0 +1,301 @@
+use std::iter::Peekable;
+use std::path::Path;
+use std::str::Chars;
+
+use super::{Context, Module, RootModuleConfig};
+
+use crate::configs::ocaml::OCamlConfig;
+use crate::formatter::version::format_version;
+use crate::utils;
+
+/// Creates a module with the current OCaml version
+///
+/// Will display the OCaml version if any of the following criteria are met:
+///     - The current directory contains a `dune-project` file
+///     - The current directory contains a `*.opam` file
+///     - The current directory contains a `*.ml` or `*.mli` file
+///     - The current directory contains a `_opam` folder
+pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
+    let is_ocaml_project = context
+        .try_begin_scan()?
+        .set_files(&["dune-project"])
+        .set_extensions(&["opam", "ml", "mli"])
+        .set_folders(&["_opam"])
+        .is_match();
+
+    if !is_ocaml_project {
+        return None;
+    }
+
+    // `-vnum` prints nothing but the version number, e.g. 4.10.0
+    let ocaml_version = context.exec_cmd("ocaml", "ocaml", &["-vnum"])?.stdout;
+    let ocaml_version = ocaml_version.trim();
+    if ocaml_version.is_empty() {
+        return None;
+    }
+
+    let mut module = context.new_module("ocaml");
+    let config = OCamlConfig::try_load(module.config);
+    module.set_style(config.style);
+
+    module.create_segment("symbol", &config.symbol);
+    module.create_segment(
+        "version",
+        &config.version.with_value(&format_version(ocaml_version)),
+    );
+
+    if config.show_switch {
+        if let Some(switch_name) = get_switch_name(context) {
+            module.create_segment(
+                "switch",
+                &config.switch.with_value(&format!(" ({})", switch_name)),
+            );
+        }
+    }
+
+    if config.show_project_name {
+        if let Some(project_name) = get_dune_project_name(&context.current_dir) {
+            module.create_segment(
+                "project_name",
+                &config
+                    .project_name
+                    .with_value(&format!(" [{}]", project_name)),
+            );
+        }
+    }
+
+    Some(module)
+}
+
+/// Returns the name of the active opam switch, taken from the prefix opam sets in the
+/// env or, failing that, from `opam switch show`
+fn get_switch_name(context: &Context) -> Option<String> {
+    let switch = context.get_env("OPAM_SWITCH_PREFIX").or_else(|| {
+        context
+            .exec_cmd("ocaml", "opam", &["switch", "show"])
+            .map(|output| output.stdout)
+    })?;
+
+    format_switch_name(switch.trim())
+}
+
+/// Global switches are named after their folder under `~/.opam`, while local switches
+/// live in the `_opam` folder of a project and are named after the project
+fn format_switch_name(switch: &str) -> Option<String> {
+    let path = Path::new(switch);
+    let path = if path.ends_with("_opam") {
+        path.parent()?
+    } else {
+        path
+    };
+
+    path.file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+}
+
+/// Returns the name declared in the nearest `dune-project` file, which in a dune
+/// monorepo is the one of the project the current directory belongs to
+fn get_dune_project_name(current_dir: &Path) -> Option<String> {
+    let dune_project = current_dir
+        .ancestors()
+        .map(|dir| dir.join("dune-project"))
+        .find(|path| path.is_file())?;
+    let contents = utils::read_file(dune_project).ok()?;
+
+    parse_dune_project_name(&contents)
+}
+
+#[derive(Debug, PartialEq)]
+enum SexpToken {
+    Open,
+    Close,
+    Atom(String),
+}
+
+/// Finds the top level `(name <name>)` stanza of a `dune-project` file
+fn parse_dune_project_name(contents: &str) -> Option<String> {
+    let tokens = tokenize_sexp(contents);
+    let mut depth = 0;
+
+    for (i, token) in tokens.iter().enumerate() {
+        match token {
+            SexpToken::Open => {
+                depth += 1;
+                if depth == 1 {
+                    if let (Some(SexpToken::Atom(key)), Some(SexpToken::Atom(name))) =
+                        (tokens.get(i + 1), tokens.get(i + 2))
+                    {
+                        if key == "name" {
+                            return Some(name.to_string());
+                        }
+                    }
+                }
+            }
+            SexpToken::Close => depth -= 1,
+            SexpToken::Atom(_) => (),
+        }
+    }
+
+    None
+}
+
+/// Splits an s-expression into parentheses and atoms, skipping `;` comments
+fn tokenize_sexp(contents: &str) -> Vec<SexpToken> {
+    let mut tokens = Vec::new();
+    let mut chars = contents.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '(' => tokens.push(SexpToken::Open),
+            ')' => tokens.push(SexpToken::Close),
+            ';' => {
+                chars.by_ref().find(|c| *c == '\n');
+            }
+            '"' => tokens.push(SexpToken::Atom(read_quoted_atom(&mut chars))),
+            c if c.is_whitespace() => (),
+            c => {
+                let mut atom = c.to_string();
+                while let Some(&next) = chars.peek() {
+                    if next.is_whitespace() || "();\"".contains(next) {
+                        break;
+                    }
+                    atom.push(next);
+                    chars.next();
+                }
+                tokens.push(SexpToken::Atom(atom));
+            }
+        }
+    }
+
+    tokens
+}
+
+fn read_quoted_atom(chars: &mut Peekable<Chars>) -> String {
+    let mut atom = String::new();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' => break,
+            '\\' => atom.extend(chars.next()),
+            c => atom.push(c),
+        }
+    }
+    atom
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::modules::utils::test::{render_module, render_module_with_config};
+    use ansi_term::Color;
+    use std::fs::{self, File};
+    use std::io;
+
+    #[test]
+    fn folder_without_ocaml_files() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let actual = render_module("ocaml", dir.path());
+        let expected = None;
+        assert_eq!(expected, actual);
+        dir.close()
+    }
+
+    #[test]
+    fn folder_with_dune_project() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        File::create(dir.path().join("dune-project"))?.sync_all()?;
+        let actual = render_module("ocaml", dir.path());
+        let expected = Some(format!(
+            "via {} ",
+            Color::Yellow.bold().paint("🐫 v4.10.0 (default)")
+        ));
+        assert_eq!(expected, actual);
+        dir.close()
+    }
+
+    #[test]
+    fn folder_with_ml_file() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        File::create(dir.path().join("main.ml"))?.sync_all()?;
+        let actual = render_module("ocaml", dir.path());
+        let expected = Some(format!(
+            "via {} ",
+            Color::Yellow.bold().paint("🐫 v4.10.0 (default)")
+        ));
+        assert_eq!(expected, actual);
+        dir.close()
+    }
+
+    #[test]
+    fn folder_with_local_switch() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        fs::create_dir_all(dir.path().join("_opam"))?;
+        let actual = render_module("ocaml", dir.path());
+        let expected = Some(format!(
+            "via {} ",
+            Color::Yellow.bold().paint("🐫 v4.10.0 (default)")
+        ));
+        assert_eq!(expected, actual);
+        dir.close()
+    }
+
+    #[test]
+    fn folder_with_named_dune_project() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        fs::write(
+            dir.path().join("dune-project"),
+            "(lang dune 2.5)\n(name camel)\n",
+        )?;
+        let sub_dir = dir.path().join("src");
+        fs::create_dir_all(&sub_dir)?;
+        File::create(sub_dir.join("main.ml"))?.sync_all()?;
+
+        let actual = render_module_with_config(
+            "ocaml",
+            &sub_dir,
+            toml::toml! {
+                [ocaml]
+                show_project_name = true
+            },
+        );
+        let expected = Some(format!(
+            "via {} ",
+            Color::Yellow.bold().paint("🐫 v4.10.0 (default) [camel]")
+        ));
+        assert_eq!(expected, actual);
+        dir.close()
+    }
+
+    #[test]
+    fn test_parse_dune_project_name() {
+        assert_eq!(
+            parse_dune_project_name("(lang dune 2.5)\n(name camel)\n"),
+            Some("camel".to_string())
+        );
+        assert_eq!(
+            parse_dune_project_name(
+                "; a (name comment)\n(lang dune 2.5)\n(name \"quoted name\")\n(package (name other))"
+            ),
+            Some("quoted name".to_string())
+        );
+        // Only the top level stanza names the project
+        assert_eq!(
+            parse_dune_project_name("(lang dune 2.5)\n(package\n (name camel_lib))"),
+            None
+        );
+        assert_eq!(parse_dune_project_name(""), None);
+    }
+
+    #[test]
+    fn test_format_switch_name() {
+        assert_eq!(
+            format_switch_name("/home/user/.opam/4.10.0"),
+            Some("4.10.0".to_string())
+        );
+        assert_eq!(
+            format_switch_name("/home/user/projects/camel/_opam"),
+            Some("camel".to_string())
+        );
+        assert_eq!(format_switch_name("default"), Some("default".to_string()));
+        assert_eq!(format_switch_name(""), None);
+    }
+}
@@ -1,5 +1,3 @@
-use std::path::PathBuf;
-
 use super::{Context, Module};
 use crate::utils;
 
@@ -13,7 +11,7 @@ use crate::configs::package::PackageConfig;
 ///
 /// Will display if a version is defined for your Node.js or Rust project (if one exists)
 pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
-    match get_package_version(&context.current_dir) {
+    match get_package_version(context) {
         Some(package_version) => {
             let mut module = context.new_module("package");
             let config: PackageConfig = PackageConfig::try_load(module.config);
@@ -30,8 +28,7 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     }
 }
 
-fn extract_cargo_version(file_contents: &str) -> Option<String> {
-    let cargo_toml: toml::Value = toml::from_str(file_contents).ok()?;
+fn extract_cargo_version(cargo_toml: &toml::Value) -> Option<String> {
     let raw_version = cargo_toml.get("package")?.get("version")?.as_str()?;
 
     let formatted_version = format_version(raw_version);
@@ -101,8 +98,9 @@ fn extract_mix_version(file_contents: &str) -> Option<String> {
     Some(formatted_version)
 }
 
-fn get_package_version(base_dir: &PathBuf) -> Option<String> {
-    if let Ok(cargo_toml) = utils::read_file(base_dir.join("Cargo.toml")) {
+fn get_package_version(context: &Context) -> Option<String> {
+    let base_dir = &context.current_dir;
+    if let Some(cargo_toml) = context.get_cargo_manifest() {
         extract_cargo_version(&cargo_toml)
     } else if let Ok(package_json) = utils::read_file(base_dir.join("package.json")) {
         extract_package_version(&package_json)
@@ -155,8 +153,7 @@ mod tests {
             [package]
             name = "starship"
             version = "0.1.0"
-        }
-        .to_string();
+        };
 
         let expected_version = Some("v0.1.0".to_string());
         assert_eq!(extract_cargo_version(&cargo_with_version), expected_version);
@@ -164,8 +161,7 @@ mod tests {
         let cargo_without_version = toml::toml! {
             [package]
             name = "starship"
-        }
-        .to_string();
+        };
 
         let expected_version = None;
         assert_eq!(
@@ -1,3 +1,5 @@
+use std::path::Path;
+
 use super::{Context, Module, RootModuleConfig, SegmentConfig};
 
 use crate::configs::php::PhpConfig;
@@ -8,6 +10,9 @@ use crate::utils;
 /// Will display the PHP version if any of the following criteria are met:
 ///     - Current directory contains a `.php` file
 ///     - Current directory contains a `composer.json` or `.php-version` file
+///
+/// With `show_platform_php`, the PHP version pinned by `config.platform.php` in
+/// `composer.json` is displayed as well, so that it can be compared to the installed one.
 pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     let is_php_project = context
         .try_begin_scan()?
@@ -19,7 +24,8 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
         return None;
     }
 
-    match utils::exec_cmd(
+    match context.exec_cmd(
+        "php",
         "php",
         &[
             "-r",
@@ -38,6 +44,17 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
             module.create_segment("symbol", &config.symbol);
             module.create_segment("version", &SegmentConfig::new(&formatted_version));
 
+            if config.show_platform_php {
+                if let Some(platform_php) = get_platform_php(&context.current_dir) {
+                    module.create_segment(
+                        "platform_php",
+                        &config
+                            .platform_php
+                            .with_value(&format!(" (platform v{})", platform_php)),
+                    );
+                }
+            }
+
             Some(module)
         }
         None => None,
@@ -51,12 +68,28 @@ fn format_php_version(php_version: &str) -> Option<String> {
     Some(formatted_version)
 }
 
+/// Returns the PHP version Composer resolves dependencies for, as configured by
+/// `config.platform.php` in the `composer.json` of `current_dir`
+fn get_platform_php(current_dir: &Path) -> Option<String> {
+    let composer_json = utils::read_file(current_dir.join("composer.json")).ok()?;
+    parse_platform_php(&composer_json)
+}
+
+fn parse_platform_php(composer_json: &str) -> Option<String> {
+    let composer: serde_json::Value = serde_json::from_str(composer_json).ok()?;
+    let platform_php = composer.pointer("/config/platform/php")?.as_str()?.trim();
+    if platform_php.is_empty() {
+        return None;
+    }
+    Some(platform_php.to_string())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::modules::utils::test::render_module;
+    use crate::modules::utils::test::{render_module, render_module_with_config};
     use ansi_term::Color;
-    use std::fs::File;
+    use std::fs::{self, File};
     use std::io;
 
     #[test]
@@ -65,6 +98,52 @@ mod tests {
         assert_eq!(format_php_version(input), Some("v7.3.8".to_string()));
     }
 
+    #[test]
+    fn test_parse_platform_php() {
+        let composer_json = r#"{
+            "require": { "php": "^7.2" },
+            "config": { "platform": { "php": "7.2.5", "ext-intl": "1.0.0" } }
+        }"#;
+        assert_eq!(parse_platform_php(composer_json), Some("7.2.5".to_string()));
+    }
+
+    #[test]
+    fn test_parse_platform_php_unpinned() {
+        assert_eq!(
+            parse_platform_php(r#"{ "require": { "php": "^7.2" } }"#),
+            None
+        );
+        assert_eq!(
+            parse_platform_php(r#"{ "config": { "platform": {} } }"#),
+            None
+        );
+        assert_eq!(parse_platform_php("not json"), None);
+    }
+
+    #[test]
+    fn folder_with_composer_platform_php() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        fs::write(
+            dir.path().join("composer.json"),
+            r#"{ "config": { "platform": { "php": "7.2.5" } } }"#,
+        )?;
+
+        let config = toml::toml! {
+            [php]
+            show_platform_php = true
+        };
+        let actual = render_module_with_config("php", dir.path(), config);
+
+        let expected = Some(format!(
+            "via {} ",
+            Color::Fixed(147)
+                .bold()
+                .paint("🐘 v7.3.8 (platform v7.2.5)")
+        ));
+        assert_eq!(expected, actual);
+        dir.close()
+    }
+
     #[test]
     fn folder_without_php_files() -> io::Result<()> {
         let dir = tempfile::tempdir()?;
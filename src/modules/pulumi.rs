@@ -0,0 +1,249 @@
+use std::path::{Path, PathBuf};
+
+use serde_json as json;
+use sha1::{Digest, Sha1};
+use yaml_rust::YamlLoader;
+
+use super::{Context, Module, RootModuleConfig};
+
+use crate::configs::pulumi::PulumiConfig;
+use crate::formatter::version::format_version;
+use crate::utils;
+
+const PULUMI_PROJECT_FILES: &[&str] = &["Pulumi.yaml", "Pulumi.yml"];
+
+/// Creates a module with the current Pulumi project and stack
+///
+/// Will display the stack if the current directory or one of its parents contains a
+/// `Pulumi.yaml` project file or a `Pulumi.<stack>.yaml` stack file. The stack is read
+/// from `PULUMI_STACK`, or else from the workspace Pulumi keeps for the project.
+///
+/// The Pulumi version is only queried with `show_version` enabled.
+pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
+    let is_pulumi_project =
+        context.has_any_file(&["Pulumi.yaml", "Pulumi.yml", "Pulumi.*.yaml", "Pulumi.*.yml"]);
+    if !is_pulumi_project {
+        return None;
+    }
+
+    let mut module = context.new_module("pulumi");
+    let config = PulumiConfig::try_load(module.config);
+    module.set_style(config.style);
+
+    let project_file = find_pulumi_project_file(&context.current_dir);
+    let project_name = project_file
+        .as_ref()
+        .and_then(|project_file| get_pulumi_project_name(project_file));
+
+    let stack = get_pulumi_stack(context, project_file.as_deref(), project_name.as_deref());
+    let project = if config.show_project {
+        project_name
+    } else {
+        None
+    };
+    let stack = match (project, stack) {
+        (Some(project), Some(stack)) => format!("{}/{}", project, stack),
+        (Some(project), None) => project,
+        (None, Some(stack)) => stack,
+        (None, None) => return None,
+    };
+
+    module.create_segment("symbol", &config.symbol);
+
+    if config.show_version {
+        if let Some(version) = get_pulumi_version(context) {
+            module.create_segment(
+                "version",
+                &config.version.with_value(&format!("{} ", version)),
+            );
+        }
+    }
+
+    module.create_segment("stack", &config.stack.with_value(&stack));
+
+    Some(module)
+}
+
+/// Returns the selected stack, from `PULUMI_STACK` if set, as Pulumi itself does, or
+/// else from the workspace of the project
+fn get_pulumi_stack(
+    context: &Context,
+    project_file: Option<&Path>,
+    project_name: Option<&str>,
+) -> Option<String> {
+    let stack = match context.get_env("PULUMI_STACK") {
+        Some(stack) => stack,
+        None => {
+            let pulumi_home = context
+                .get_env("PULUMI_HOME")
+                .map(PathBuf::from)
+                .or_else(|| Some(context.get_home()?.join(".pulumi")))?;
+            get_workspace_stack(&pulumi_home, project_file?, project_name?)?
+        }
+    };
+
+    let stack = stack.trim();
+    if stack.is_empty() {
+        return None;
+    }
+    Some(stack.to_string())
+}
+
+/// Reads the stack selected with `pulumi stack select` from the workspace of the
+/// project, which Pulumi keeps in `workspaces/<name>-<sha1 of project file>-workspace.json`
+fn get_workspace_stack(pulumi_home: &Path, project_file: &Path, name: &str) -> Option<String> {
+    let project_hash = Sha1::digest(project_file.to_str()?.as_bytes());
+    let workspace_file = pulumi_home
+        .join("workspaces")
+        .join(format!("{}-{:x}-workspace.json", name, project_hash));
+
+    let workspace: json::Value = json::from_str(&utils::read_file(workspace_file).ok()?).ok()?;
+    Some(workspace.get("stack")?.as_str()?.to_string())
+}
+
+fn get_pulumi_version(context: &Context) -> Option<String> {
+    // `pulumi version` output looks like this: v3.5.1
+    let version = context.exec_cmd("pulumi", "pulumi", &["version"])?.stdout;
+    if version.trim().is_empty() {
+        return None;
+    }
+    Some(format_version(&version))
+}
+
+/// Returns the nearest `Pulumi.yaml`, looking in `current_dir` and then in its parents
+fn find_pulumi_project_file(current_dir: &Path) -> Option<PathBuf> {
+    current_dir.ancestors().find_map(|dir| {
+        PULUMI_PROJECT_FILES
+            .iter()
+            .map(|name| dir.join(name))
+            .find(|path| path.is_file())
+    })
+}
+
+/// Returns the name of the project declared by a `Pulumi.yaml`
+fn get_pulumi_project_name(project_file: &Path) -> Option<String> {
+    parse_pulumi_project_name(&utils::read_file(project_file).ok()?)
+}
+
+fn parse_pulumi_project_name(project_yaml: &str) -> Option<String> {
+    let yaml_docs = YamlLoader::load_from_str(project_yaml).ok()?;
+    let name = yaml_docs.get(0)?["name"].as_str()?.trim();
+    if name.is_empty() {
+        return None;
+    }
+    Some(name.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::modules::utils::test::{render_module, render_module_with_config};
+    use ansi_term::Color;
+    use std::fs::{self, File};
+    use std::io;
+
+    #[test]
+    fn test_parse_pulumi_project_name() {
+        let project_yaml = "name: rocket\nruntime: nodejs\ndescription: Launches rockets\n";
+        assert_eq!(
+            parse_pulumi_project_name(project_yaml),
+            Some("rocket".to_string())
+        );
+
+        assert_eq!(parse_pulumi_project_name("runtime: go\n"), None);
+        assert_eq!(parse_pulumi_project_name("name: [rocket\n"), None);
+    }
+
+    #[test]
+    fn folder_without_pulumi_files() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        File::create(dir.path().join("index.ts"))?.sync_all()?;
+        let actual = render_module("pulumi", dir.path());
+        let expected = None;
+        assert_eq!(expected, actual);
+        dir.close()
+    }
+
+    #[test]
+    fn folder_with_pulumi_project() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        fs::write(
+            dir.path().join("Pulumi.yaml"),
+            "name: rocket\nruntime: nodejs\n",
+        )?;
+        let sub_dir = dir.path().join("infra");
+        fs::create_dir(&sub_dir)?;
+
+        let config = toml::toml! {
+            [pulumi]
+            show_version = true
+        };
+        let actual = render_module_with_config("pulumi", &sub_dir, config);
+        let expected = Some(format!(
+            "via {} ",
+            Color::Purple.bold().paint("🧊 v3.5.1 rocket")
+        ));
+        assert_eq!(expected, actual);
+        dir.close()
+    }
+
+    #[test]
+    fn folder_with_stack_file_only() -> io::Result<()> {
+        // Without a project, there is no workspace to read the stack from
+        let dir = tempfile::tempdir()?;
+        File::create(dir.path().join("Pulumi.dev.yaml"))?.sync_all()?;
+        let actual = render_module("pulumi", dir.path());
+        let expected = None;
+        assert_eq!(expected, actual);
+        dir.close()
+    }
+
+    #[test]
+    fn stack_from_workspace() -> io::Result<()> {
+        let pulumi_home = tempfile::tempdir()?;
+        let workspaces = pulumi_home.path().join("workspaces");
+        fs::create_dir(&workspaces)?;
+        fs::write(
+            workspaces.join("rocket-aed1ca66ac2278b7626f8139969f89a7bf16c3ec-workspace.json"),
+            "{\n    \"stack\": \"dev\"\n}\n",
+        )?;
+
+        let project_file = Path::new("/home/astronaut/rocket/Pulumi.yaml");
+        assert_eq!(
+            get_workspace_stack(pulumi_home.path(), project_file, "rocket"),
+            Some("dev".to_string())
+        );
+        assert_eq!(
+            get_workspace_stack(pulumi_home.path(), project_file, "other"),
+            None
+        );
+        pulumi_home.close()
+    }
+
+    #[test]
+    fn stack_from_workspace_in_home_dir() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let project_file = dir.path().join("Pulumi.yaml");
+        fs::write(&project_file, "name: rocket\nruntime: nodejs\n")?;
+
+        let home = tempfile::tempdir()?;
+        let workspaces = home.path().join(".pulumi").join("workspaces");
+        fs::create_dir_all(&workspaces)?;
+        let project_hash = Sha1::digest(project_file.to_str().unwrap().as_bytes());
+        fs::write(
+            workspaces.join(format!("rocket-{:x}-workspace.json", project_hash)),
+            "{\"stack\": \"staging\"}",
+        )?;
+
+        let mut context = Context::new_with_dir(clap::ArgMatches::default(), dir.path());
+        context.home_dir = Some(home.path().to_path_buf());
+        assert_eq!(
+            get_pulumi_stack(&context, Some(&project_file), Some("rocket")),
+            Some("staging".to_string())
+        );
+        assert_eq!(get_pulumi_stack(&context, None, Some("rocket")), None);
+
+        home.close()?;
+        dir.close()
+    }
+}
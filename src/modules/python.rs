@@ -1,9 +1,7 @@
-use std::env;
 use std::path::Path;
 
 use super::{Context, Module, RootModuleConfig, SegmentConfig};
 use crate::configs::python::PythonConfig;
-use crate::utils;
 
 /// Creates a module with the current Python version
 ///
@@ -35,7 +33,7 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
         }
     };
 
-    let is_venv = env::var("VIRTUAL_ENV").ok().is_some();
+    let is_venv = context.get_env("VIRTUAL_ENV").is_some();
 
     if !is_py_project && !is_venv {
         return None;
@@ -44,17 +42,22 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     module.set_style(config.style);
     module.create_segment("symbol", &config.symbol);
 
-    if config.pyenv_version_name {
-        let python_version = utils::exec_cmd("pyenv", &["version-name"])?.stdout;
+    if is_virtual_env_broken(context) {
+        // The version of whichever python is next in PATH would be misleading
+        module.create_segment("broken", &config.broken);
+    } else if config.pyenv_version_name {
+        let python_version = context
+            .exec_cmd("python", "pyenv", &["version-name"])?
+            .stdout;
         module.create_segment("pyenv_prefix", &config.pyenv_prefix);
         module.create_segment("version", &SegmentConfig::new(&python_version.trim()));
     } else {
-        let python_version = get_python_version()?;
+        let python_version = get_python_version(context)?;
         let formatted_version = format_python_version(&python_version);
         module.create_segment("version", &SegmentConfig::new(&formatted_version));
     };
 
-    if let Some(virtual_env) = get_python_virtual_env() {
+    if let Some(virtual_env) = get_python_virtual_env(context) {
         module.create_segment(
             "virtualenv",
             &SegmentConfig::new(&format!(" ({})", virtual_env)),
@@ -64,8 +67,8 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     Some(module)
 }
 
-fn get_python_version() -> Option<String> {
-    match utils::exec_cmd("python", &["--version"]) {
+fn get_python_version(context: &Context) -> Option<String> {
+    match context.exec_cmd("python", "python", &["--version"]) {
         Some(output) => {
             if output.stdout.is_empty() {
                 Some(output.stderr)
@@ -87,8 +90,18 @@ fn format_python_version(python_stdout: &str) -> String {
     )
 }
 
-fn get_python_virtual_env() -> Option<String> {
-    env::var("VIRTUAL_ENV").ok().and_then(|venv| {
+/// Whether the active virtual environment lacks its interpreter, e.g. after the
+/// venv was deleted without being deactivated
+fn is_virtual_env_broken(context: &Context) -> bool {
+    context.get_env("VIRTUAL_ENV").map_or(false, |venv| {
+        let venv = Path::new(&venv);
+        !venv.join("bin").join("python").exists()
+            && !venv.join("Scripts").join("python.exe").exists()
+    })
+}
+
+fn get_python_virtual_env(context: &Context) -> Option<String> {
+    context.get_env("VIRTUAL_ENV").and_then(|venv| {
         Path::new(&venv)
             .file_name()
             .map(|filename| String::from(filename.to_str().unwrap_or("")))
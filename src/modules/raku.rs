@@ -0,0 +1,128 @@
+use super::{Context, Module, RootModuleConfig};
+
+use crate::configs::raku::RakuConfig;
+use crate::formatter::version::format_version;
+
+/// Creates a module with the current Raku version
+///
+/// Will display the Raku version if any of the following criteria are met:
+///     - Current directory contains a `META6.json` file
+///     - Current directory contains a file with the `.raku`, `.rakumod` or `.rakudoc` extension
+pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
+    let is_raku_project = context
+        .try_begin_scan()?
+        .set_files(&["META6.json"])
+        .set_extensions(&["raku", "rakumod", "rakudoc"])
+        .is_match();
+
+    if !is_raku_project {
+        return None;
+    }
+
+    let mut module = context.new_module("raku");
+    let config: RakuConfig = RakuConfig::try_load(module.config);
+
+    module.set_style(config.style);
+    module.create_segment("symbol", &config.symbol);
+
+    // Older installs only provide the interpreter under its Perl 6 name
+    let raku_version = context
+        .exec_cmd("raku", "raku", &["--version"])
+        .or_else(|| context.exec_cmd("raku", "perl6", &["--version"]))?
+        .stdout;
+    let formatted_version = format_raku_version(&raku_version)?;
+    module.create_segment("version", &config.version.with_value(&formatted_version));
+
+    Some(module)
+}
+
+fn format_raku_version(raku_stdout: &str) -> Option<String> {
+    // raku version output looks like this:
+    // Welcome to Rakudo™ v2022.04.
+    // Implementing the Raku® Programming Language v6.d.
+    // Built on MoarVM version 2022.04.
+    //
+    // or, for older releases:
+    // This is Rakudo version 2020.05.1 built on MoarVM version 2020.05
+    // implementing Raku 6.d.
+
+    let version = raku_stdout
+        .lines()
+        .find(|line| line.contains("Rakudo"))?
+        // split into ["This is", "version 2020.05.1 built on ..."]
+        .splitn(2, "Rakudo")
+        .nth(1)?
+        .split_whitespace()
+        // return "v2022.04." or "2020.05.1"
+        .find(|word| {
+            word.trim_start_matches('v')
+                .starts_with(|c: char| c.is_ascii_digit())
+        })?
+        .trim_end_matches('.');
+
+    Some(format_version(version))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::modules::utils::test::render_module;
+    use ansi_term::Color;
+    use std::fs::File;
+    use std::io;
+
+    #[test]
+    fn folder_without_raku_files() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+
+        let actual = render_module("raku", dir.path());
+
+        let expected = None;
+        assert_eq!(expected, actual);
+        dir.close()
+    }
+
+    #[test]
+    fn folder_with_meta6_json() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        File::create(dir.path().join("META6.json"))?.sync_all()?;
+
+        let actual = render_module("raku", dir.path());
+
+        let expected = Some(format!(
+            "via {} ",
+            Color::Fixed(149).bold().paint("🦋 v2022.04")
+        ));
+        assert_eq!(expected, actual);
+        dir.close()
+    }
+
+    #[test]
+    fn folder_with_raku_file() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        File::create(dir.path().join("hello.raku"))?.sync_all()?;
+
+        let actual = render_module("raku", dir.path());
+
+        let expected = Some(format!(
+            "via {} ",
+            Color::Fixed(149).bold().paint("🦋 v2022.04")
+        ));
+        assert_eq!(expected, actual);
+        dir.close()
+    }
+
+    #[test]
+    fn test_format_raku_version() {
+        let input = "Welcome to Rakudo™ v2022.04.
+Implementing the Raku® Programming Language v6.d.
+Built on MoarVM version 2022.04.";
+        assert_eq!(format_raku_version(input), Some("v2022.04".to_string()));
+
+        let input = "This is Rakudo version 2020.05.1 built on MoarVM version 2020.05
+implementing Raku 6.d.";
+        assert_eq!(format_raku_version(input), Some("v2020.05.1".to_string()));
+
+        assert_eq!(format_raku_version("Unexpected output"), None);
+    }
+}
@@ -0,0 +1,85 @@
+use regex::Regex;
+
+use super::{Context, Module};
+
+use crate::config::SegmentConfig;
+
+/// Only SGR sequences (`ESC [ ... m`) and OSC 0-2 title sequences terminated by BEL
+/// are allowed, so that a configured snippet cannot move the cursor, clear the
+/// screen or otherwise corrupt the prompt.
+const ALLOWED_ESCAPE_PATTERN: &str = r"^(?:\x1b\[[0-9;]*m|\x1b\][012];[^\x00-\x1f\x7f]*\x07)+$";
+
+/// Creates a module emitting one of the raw escape snippets configured under
+/// `raw_escapes` in the root config
+///
+/// The snippet is referenced from `prompt_order` as `raw_escape.<name>`. Unknown
+/// names and snippets containing anything but allowed escape sequences are ignored.
+pub fn module<'a>(name: &'a str, context: &'a Context) -> Option<Module<'a>> {
+    let raw_escapes = context.config.get_root_config().raw_escapes;
+    let escape = match raw_escapes.get(name) {
+        Some(escape) => *escape,
+        None => {
+            log::debug!(
+                "prompt_order contains raw escape \"{}\", but it isn't listed in raw_escapes",
+                name
+            );
+            return None;
+        }
+    };
+
+    if !is_allowed_escape(escape) {
+        log::warn!(
+            "Raw escape \"{}\" contains disallowed characters and will not be printed",
+            name
+        );
+        return None;
+    }
+
+    let mut module = Module::new(
+        &format!("raw_escape.{}", name),
+        "A raw escape sequence",
+        None,
+    );
+    module.get_prefix().set_value("");
+    module.get_suffix().set_value("");
+    module.create_segment("escape", &SegmentConfig::new(escape));
+
+    Some(module)
+}
+
+/// Checks that `escape` is only made of allowed escape sequences
+fn is_allowed_escape(escape: &str) -> bool {
+    Regex::new(ALLOWED_ESCAPE_PATTERN)
+        .map(|re| re.is_match(escape))
+        .unwrap_or(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn allows_title_sequences() {
+        assert!(is_allowed_escape("\x1b]0;starship\x07"));
+        assert!(is_allowed_escape("\x1b]2;user@host: ~/rocket\x07"));
+    }
+
+    #[test]
+    fn allows_sgr_sequences() {
+        assert!(is_allowed_escape("\x1b[1;31m"));
+        assert!(is_allowed_escape("\x1b[0m\x1b]1;icon\x07"));
+    }
+
+    #[test]
+    fn rejects_other_sequences() {
+        // Plain text
+        assert!(!is_allowed_escape("hello"));
+        // Clear screen
+        assert!(!is_allowed_escape("\x1b[2J"));
+        // Unterminated title
+        assert!(!is_allowed_escape("\x1b]0;starship"));
+        // Control characters smuggled into the title
+        assert!(!is_allowed_escape("\x1b]0;star\x1b[2Jship\x07"));
+        assert!(!is_allowed_escape(""));
+    }
+}
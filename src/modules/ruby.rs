@@ -1,7 +1,6 @@
 use super::{Context, Module, RootModuleConfig, SegmentConfig};
 
 use crate::configs::ruby::RubyConfig;
-use crate::utils;
 
 /// Creates a module with the current Ruby version
 ///
@@ -19,7 +18,7 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
         return None;
     }
 
-    let ruby_version = utils::exec_cmd("ruby", &["-v"])?.stdout;
+    let ruby_version = context.exec_cmd("ruby", "ruby", &["-v"])?.stdout;
     let formatted_version = format_ruby_version(&ruby_version)?;
 
     let mut module = context.new_module("ruby");
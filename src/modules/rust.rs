@@ -1,16 +1,22 @@
+use std::cmp::Ordering;
 use std::path::Path;
 use std::process::{Command, Output};
 use std::{env, fs};
 
+use super::utils::toolchain_source::ToolchainSource;
 use super::{Context, Module, RootModuleConfig};
 
 use crate::configs::rust::RustConfig;
+use crate::utils;
 
 /// Creates a module with the current Rust version
 ///
 /// Will display the Rust version if any of the following criteria are met:
 ///     - Current directory contains a file with a `.rs` extension
 ///     - Current directory contains a `Cargo.toml` file
+///
+/// With `check_msrv`, the MSRV is displayed too when the toolchain is older than the
+/// `rust-version` of the nearest `Cargo.toml`.
 pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     let is_rs_project = context
         .try_begin_scan()?
@@ -38,23 +44,29 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     // - `rustup show`
     // - `rustup show active-toolchain`
     // - `rustup which`
-    let module_version = if let Some(toolchain) = env_rustup_toolchain()
-        .or_else(|| execute_rustup_override_list(&context.current_dir))
-        .or_else(|| find_rust_toolchain_file(&context))
-    {
-        match execute_rustup_run_rustc_version(&toolchain) {
-            RustupRunRustcVersionOutcome::RustcVersion(stdout) => format_rustc_version(stdout),
-            RustupRunRustcVersionOutcome::ToolchainName(toolchain) => toolchain,
-            RustupRunRustcVersionOutcome::RustupNotWorking => {
-                // If `rustup` is not in `$PATH` or cannot be executed for other reasons, we can
-                // safely execute `rustc --version`.
-                format_rustc_version(execute_rustc_version()?)
+    let (module_version, source) =
+        if let Some((toolchain, source)) = find_rustup_toolchain(context, env_rustup_toolchain()) {
+            match execute_rustup_run_rustc_version(context, &toolchain) {
+                RustupRunRustcVersionOutcome::RustcVersion(stdout) => {
+                    (format_rustc_version(stdout), source)
+                }
+                RustupRunRustcVersionOutcome::ToolchainName(toolchain) => (toolchain, source),
+                RustupRunRustcVersionOutcome::RustupNotWorking => {
+                    // If `rustup` is not in `$PATH` or cannot be executed for other reasons, we can
+                    // safely execute `rustc --version`.
+                    (
+                        format_rustc_version(execute_rustc_version(context)?),
+                        ToolchainSource::Path,
+                    )
+                }
+                RustupRunRustcVersionOutcome::Err => return None,
             }
-            RustupRunRustcVersionOutcome::Err => return None,
-        }
-    } else {
-        format_rustc_version(execute_rustc_version()?)
-    };
+        } else {
+            (
+                format_rustc_version(execute_rustc_version(context)?),
+                ToolchainSource::Path,
+            )
+        };
 
     let mut module = context.new_module("rust");
     let config = RustConfig::try_load(module.config);
@@ -63,9 +75,68 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     module.create_segment("symbol", &config.symbol);
     module.create_segment("version", &config.version.with_value(&module_version));
 
+    if config.show_source {
+        module.create_segment(
+            "source",
+            &config.source.with_value(&format!(" ({})", source.as_str())),
+        );
+    }
+
+    if config.check_msrv {
+        if let Some(msrv) = find_msrv(&context.current_dir) {
+            if is_older_than_msrv(&module_version, &msrv) == Some(true) {
+                module.create_segment(
+                    "msrv",
+                    &config.msrv.with_value(&format!(" (MSRV v{})", msrv)),
+                );
+            }
+        }
+    }
+
     Some(module)
 }
 
+/// Returns the `package.rust-version` of the nearest `Cargo.toml`
+fn find_msrv(current_dir: &Path) -> Option<String> {
+    let manifest = current_dir
+        .ancestors()
+        .map(|dir| dir.join("Cargo.toml"))
+        .find(|path| path.is_file())?;
+    let manifest: toml::Value = toml::from_str(&utils::read_file(manifest).ok()?).ok()?;
+
+    let msrv = manifest.get("package")?.get("rust-version")?.as_str()?;
+    Some(msrv.to_string())
+}
+
+/// Compares the rustc version, e.g. `v1.46.0-nightly`, with the MSRV, e.g. `1.45`.
+/// Returns `None` when either isn't a version, such as a toolchain name.
+fn is_older_than_msrv(rustc_version: &str, msrv: &str) -> Option<bool> {
+    let is_version = |version: &str| {
+        version
+            .trim()
+            .trim_start_matches('v')
+            .starts_with(|c: char| c.is_ascii_digit())
+    };
+    if !is_version(rustc_version) || !is_version(msrv) {
+        return None;
+    }
+    Some(utils::compare_versions(rustc_version, msrv) == Ordering::Less)
+}
+
+/// Returns the toolchain overridden for the current directory, and whether the override
+/// came from rustup itself or from a `rust-toolchain` file
+fn find_rustup_toolchain(
+    context: &Context,
+    env_toolchain: Option<String>,
+) -> Option<(String, ToolchainSource)> {
+    env_toolchain
+        .or_else(|| execute_rustup_override_list(&context.current_dir))
+        .map(|toolchain| (toolchain, ToolchainSource::Rustup))
+        .or_else(|| {
+            find_rust_toolchain_file(context).map(|toolchain| (toolchain, ToolchainSource::File))
+        })
+}
+
 fn env_rustup_toolchain() -> Option<String> {
     let val = env::var("RUSTUP_TOOLCHAIN").ok()?;
     Some(val.trim().to_owned())
@@ -124,10 +195,13 @@ fn find_rust_toolchain_file(context: &Context) -> Option<String> {
     }
 }
 
-fn execute_rustup_run_rustc_version(toolchain: &str) -> RustupRunRustcVersionOutcome {
-    Command::new("rustup")
-        .args(&["run", toolchain, "rustc", "--version"])
-        .output()
+fn execute_rustup_run_rustc_version(
+    context: &Context,
+    toolchain: &str,
+) -> RustupRunRustcVersionOutcome {
+    // A toolchain which is not installed is reported on stderr, with an error status
+    context
+        .exec_cmd_full("rust", "rustup", &["run", toolchain, "rustc", "--version"])
         .map(extract_toolchain_from_rustup_run_rustc_version)
         .unwrap_or(RustupRunRustcVersionOutcome::RustupNotWorking)
 }
@@ -148,11 +222,10 @@ fn extract_toolchain_from_rustup_run_rustc_version(output: Output) -> RustupRunR
     RustupRunRustcVersionOutcome::Err
 }
 
-fn execute_rustc_version() -> Option<String> {
-    match Command::new("rustc").arg("--version").output() {
-        Ok(output) => Some(String::from_utf8(output.stdout).unwrap()),
-        Err(_) => None,
-    }
+fn execute_rustc_version(context: &Context) -> Option<String> {
+    context
+        .exec_cmd("rust", "rustc", &["--version"])
+        .map(|output| output.stdout)
 }
 
 fn format_rustc_version(mut rustc_stdout: String) -> String {
@@ -268,6 +341,53 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_is_older_than_msrv() {
+        assert_eq!(is_older_than_msrv("v1.44.1", "1.45"), Some(true));
+        assert_eq!(is_older_than_msrv("v1.46.0-nightly", "1.46.1"), Some(true));
+        assert_eq!(is_older_than_msrv("v1.45.0", "1.45"), Some(false));
+        assert_eq!(is_older_than_msrv("v1.47.0", "1.45.2"), Some(false));
+        assert_eq!(is_older_than_msrv("nightly-2020-06-01", "1.45"), None);
+        assert_eq!(is_older_than_msrv("v1.47.0", "latest"), None);
+    }
+
+    #[test]
+    fn test_find_msrv() -> std::io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        fs::write(
+            dir.path().join("Cargo.toml"),
+            "[package]\nname = \"crab\"\nrust-version = \"1.45\"\n",
+        )?;
+        let sub_dir = dir.path().join("src");
+        fs::create_dir_all(&sub_dir)?;
+
+        assert_eq!(find_msrv(&sub_dir), Some("1.45".to_string()));
+
+        fs::write(
+            dir.path().join("Cargo.toml"),
+            "[package]\nname = \"crab\"\n",
+        )?;
+        assert_eq!(find_msrv(&sub_dir), None);
+        dir.close()
+    }
+
+    #[test]
+    fn test_find_rustup_toolchain() -> std::io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        fs::write(dir.path().join("rust-toolchain"), "nightly-2020-06-01\n")?;
+        let context = Context::new_with_dir(clap::ArgMatches::default(), dir.path());
+
+        assert_eq!(
+            find_rustup_toolchain(&context, Some("beta".to_string())),
+            Some(("beta".to_string(), ToolchainSource::Rustup))
+        );
+        assert_eq!(
+            find_rustup_toolchain(&context, None),
+            Some(("nightly-2020-06-01".to_string(), ToolchainSource::File))
+        );
+        dir.close()
+    }
+
     #[test]
     fn test_format_rustc_version() {
         let nightly_input = String::from("rustc 1.34.0-nightly (b139669f3 2019-04-10)");
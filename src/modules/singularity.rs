@@ -1,5 +1,3 @@
-use std::env;
-
 use super::{Context, Module, SegmentConfig};
 
 use crate::config::RootModuleConfig;
@@ -9,7 +7,7 @@ use crate::configs::singularity::SingularityConfig;
 ///
 /// Will display the Singularity image if `$SINGULARITY_NAME` is set.
 pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
-    let singularity_env = env::var("SINGULARITY_NAME").unwrap_or_else(|_| "".into());
+    let singularity_env = context.get_env_or("SINGULARITY_NAME", "");
     if singularity_env.trim().is_empty() {
         return None;
     }
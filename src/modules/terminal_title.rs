@@ -0,0 +1,147 @@
+use super::{Context, Module, RootModuleConfig};
+
+use crate::config::SegmentConfig;
+use crate::configs::terminal_title::TerminalTitleConfig;
+use crate::formatter::StringFormatter;
+use crate::module::ALL_MODULES;
+
+/// Creates a module emitting the OSC sequence that sets the terminal title, if
+/// enabled in `[terminal_title]`
+///
+/// Variables in the title format are replaced by the plain text of the module with
+/// the same name, as returned by `module_text`. This lets the prompt reuse the
+/// modules it already computed, rather than computing them again for the title.
+pub fn module<'a>(
+    context: &'a Context,
+    module_text: impl Fn(&str) -> Option<String> + Sync,
+) -> Option<Module<'a>> {
+    let mut module = context.new_module("terminal_title");
+    let config = TerminalTitleConfig::try_load(module.config);
+    if config.disabled {
+        return None;
+    }
+
+    let formatter = match StringFormatter::new(config.format) {
+        Ok(formatter) => formatter,
+        Err(error) => {
+            log::warn!("Error parsing terminal_title format:\n{}", error);
+            return None;
+        }
+    };
+
+    let title: String = formatter
+        .map(|variable| {
+            // Unlike `$hostname`, this isn't cut off at the `trim_at` of the module
+            if variable == "hostname_full" {
+                return super::hostname::get_full_hostname();
+            }
+            module_text(variable)
+        })
+        .parse(None)
+        .iter()
+        .flat_map(|segment| segment.value.chars())
+        // Control characters would end the sequence early
+        .filter(|c| !c.is_control())
+        .collect();
+
+    module.get_prefix().set_value("");
+    module.get_suffix().set_value("");
+    module.create_segment(
+        "title",
+        &SegmentConfig::new(&format!("\x1b]0;{}\x07", title.trim())),
+    );
+
+    Some(module)
+}
+
+/// Computes the module named `name` on its own and returns its plain text, for
+/// modules which aren't part of the prompt
+pub fn compute_module_text(name: &str, context: &Context) -> Option<String> {
+    if name == "terminal_title"
+        || !ALL_MODULES.contains(&name)
+        || context.is_module_disabled_in_config(name)
+    {
+        return None;
+    }
+    super::handle(name, context).map(|module| module.get_segments().join(""))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use clap::ArgMatches;
+    use std::collections::HashMap;
+
+    fn context_with_config(config: toml::Value) -> Context<'static> {
+        let mut context = Context::new_with_dir(ArgMatches::default(), "/");
+        context.config = crate::config::StarshipConfig {
+            config: Some(config),
+        };
+        context
+    }
+
+    fn render_title(context: &Context, texts: &HashMap<&str, &str>) -> Option<String> {
+        module(context, |name| texts.get(name).map(|text| text.to_string()))
+            .map(|module| module.get_segments().join(""))
+    }
+
+    #[test]
+    fn disabled_by_default() {
+        let context = context_with_config(toml::Value::Table(Default::default()));
+        assert_eq!(render_title(&context, &HashMap::new()), None);
+    }
+
+    #[test]
+    fn title_sequence() {
+        let context = context_with_config(toml::toml! {
+            [terminal_title]
+            disabled = false
+            format = "starship"
+        });
+        assert_eq!(
+            render_title(&context, &HashMap::new()),
+            Some("\x1b]0;starship\x07".to_string())
+        );
+    }
+
+    #[test]
+    fn title_with_full_hostname() {
+        let context = context_with_config(toml::toml! {
+            [terminal_title]
+            disabled = false
+            format = "$hostname_full"
+        });
+        let full_hostname = crate::modules::hostname::get_full_hostname().unwrap();
+        assert_eq!(
+            render_title(&context, &HashMap::new()),
+            Some(format!("\x1b]0;{}\x07", full_hostname))
+        );
+    }
+
+    #[test]
+    fn title_with_module_text() {
+        let context = context_with_config(toml::toml! {
+            [terminal_title]
+            disabled = false
+            format = "rocket: $jobs $unknown"
+        });
+        let mut texts = HashMap::new();
+        texts.insert("jobs", "✦3\x1b");
+        assert_eq!(
+            render_title(&context, &texts),
+            Some("\x1b]0;rocket: ✦3\x07".to_string())
+        );
+    }
+
+    #[test]
+    fn computes_modules_outside_the_prompt() {
+        let mut context = context_with_config(toml::Value::Table(Default::default()));
+        context.properties.insert("jobs", "3".to_string());
+        assert_eq!(
+            compute_module_text("jobs", &context),
+            Some("✦3".to_string())
+        );
+        assert_eq!(compute_module_text("unknown", &context), None);
+        assert_eq!(compute_module_text("terminal_title", &context), None);
+    }
+}
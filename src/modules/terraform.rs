@@ -3,19 +3,39 @@ use super::{Context, Module, RootModuleConfig};
 use crate::configs::terraform::TerraformConfig;
 use crate::utils;
 use std::env;
+use std::ffi::OsString;
 use std::io;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+
+/// The tool managing the infrastructure, Terraform or its fork OpenTofu
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Tool {
+    Terraform,
+    OpenTofu,
+}
+
+impl Tool {
+    fn binary(self) -> &'static str {
+        match self {
+            Tool::Terraform => "terraform",
+            Tool::OpenTofu => "tofu",
+        }
+    }
+}
 
 /// Creates a module with the current Terraform version and workspace
 ///
 /// Will display the Terraform version and workspace if any of the following criteria are met:
 ///     - Current directory contains a `.terraform` directory
-///     - Current directory contains a file with the `.tf` extension
+///     - Current directory contains a file with the `.tf` or `.tfvars` extension
+///
+/// OpenTofu is used instead of Terraform when pinned by a `.opentofu-version` file,
+/// or when only the `tofu` binary is installed.
 pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     let is_terraform_project = context
         .try_begin_scan()?
         .set_folders(&[".terraform"])
-        .set_extensions(&["tf"])
+        .set_extensions(&["tf", "tfvars"])
         .is_match();
 
     if !is_terraform_project {
@@ -25,16 +45,30 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     let mut module = context.new_module("terraform");
     let config: TerraformConfig = TerraformConfig::try_load(module.config);
 
+    let tool = detect_tool(
+        &context.current_dir,
+        context.get_env("PATH").map(OsString::from),
+    );
+
     module.set_style(config.style);
-    module.create_segment("symbol", &config.symbol);
+    match tool {
+        Tool::Terraform => module.create_segment("symbol", &config.symbol),
+        Tool::OpenTofu => module.create_segment("symbol", &config.opentofu_symbol),
+    };
+
+    if config.show_tool {
+        module.create_segment(
+            "tool",
+            &config.tool.with_value(&format!("{} ", tool.binary())),
+        );
+    }
 
     if config.show_version {
-        let terraform_version =
-            format_terraform_version(&utils::exec_cmd("terraform", &["version"])?.stdout.as_str())?;
+        let terraform_version = get_terraform_version(context, tool)?;
         module.create_segment("version", &config.version.with_value(&terraform_version));
     }
 
-    let terraform_workspace = &get_terraform_workspace(&context.current_dir)?;
+    let terraform_workspace = &get_terraform_workspace(context)?;
     module.create_segment(
         "workspace",
         &config.workspace.with_value(&terraform_workspace),
@@ -44,17 +78,17 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
 }
 
 // Determines the currently selected workspace (see https://github.com/hashicorp/terraform/blob/master/command/meta.go for the original implementation)
-fn get_terraform_workspace(cwd: &PathBuf) -> Option<String> {
+fn get_terraform_workspace(context: &Context) -> Option<String> {
     // Workspace can be explicitly overwritten by an env var
-    let workspace_override = env::var("TF_WORKSPACE");
-    if workspace_override.is_ok() {
-        return workspace_override.ok();
+    let workspace_override = context.get_env("TF_WORKSPACE");
+    if workspace_override.is_some() {
+        return workspace_override;
     }
 
     // Data directory containing current workspace can be overwritten by an env var
-    let datadir = match env::var("TF_DATA_DIR") {
-        Ok(s) => PathBuf::from(s),
-        Err(_) => cwd.join(".terraform"),
+    let datadir = match context.get_env("TF_DATA_DIR") {
+        Some(s) => PathBuf::from(s),
+        None => context.current_dir.join(".terraform"),
     };
     match utils::read_file(datadir.join("environment")) {
         Err(ref e) if e.kind() == io::ErrorKind::NotFound => Some("default".to_string()),
@@ -63,15 +97,56 @@ fn get_terraform_workspace(cwd: &PathBuf) -> Option<String> {
     }
 }
 
+/// Determines whether OpenTofu or Terraform is in use, going by the version file pinning
+/// one of them (as used by tofuenv and tfenv), or else by which binary is in `path_var`
+fn detect_tool(cwd: &Path, path_var: Option<OsString>) -> Tool {
+    if cwd.join(".opentofu-version").is_file() {
+        return Tool::OpenTofu;
+    }
+    if cwd.join(".terraform-version").is_file() {
+        return Tool::Terraform;
+    }
+
+    let is_installed = |binary: &str| {
+        path_var.as_ref().map_or(false, |path_var| {
+            env::split_paths(path_var).any(|dir| dir.join(binary).is_file())
+        })
+    };
+    if is_installed(Tool::OpenTofu.binary()) && !is_installed(Tool::Terraform.binary()) {
+        Tool::OpenTofu
+    } else {
+        Tool::Terraform
+    }
+}
+
+fn get_terraform_version(context: &Context, tool: Tool) -> Option<String> {
+    // `-json` is only supported since Terraform 0.13, older versions may reject it
+    let output = context
+        .exec_cmd("terraform", tool.binary(), &["version", "-json"])
+        .or_else(|| context.exec_cmd("terraform", tool.binary(), &["version"]))?
+        .stdout;
+    parse_terraform_version_json(&output).or_else(|| format_terraform_version(&output))
+}
+
+fn parse_terraform_version_json(output: &str) -> Option<String> {
+    // `terraform version -json` output looks like this, and OpenTofu keeps the same keys
+    // {"terraform_version": "0.13.0", "terraform_revision": "", "provider_selections": {}, ...}
+    let version_json: serde_json::Value = serde_json::from_str(output).ok()?;
+    let version = version_json.get("terraform_version")?.as_str()?;
+    Some(format!("v{} ", version))
+}
+
 fn format_terraform_version(version: &str) -> Option<String> {
     // `terraform version` output looks like this
     // Terraform v0.12.14
     // With potential extra output if it detects you are not running the latest version
+    // OpenTofu prints `OpenTofu v1.6.0` instead
     Some(
         version
             .lines()
             .next()?
             .trim_start_matches("Terraform ")
+            .trim_start_matches("OpenTofu ")
             .trim()
             .to_owned()
             + " ",
@@ -81,6 +156,115 @@ fn format_terraform_version(version: &str) -> Option<String> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::context::Env;
+    use clap::ArgMatches;
+    use std::fs;
+
+    #[test]
+    fn test_parse_terraform_version_json() {
+        let input = r#"{
+  "terraform_version": "0.13.0",
+  "terraform_revision": "",
+  "provider_selections": {},
+  "terraform_outdated": true
+}"#;
+        assert_eq!(
+            parse_terraform_version_json(input),
+            Some("v0.13.0 ".to_string())
+        );
+    }
+
+    #[test]
+    fn test_parse_terraform_version_json_older_output() {
+        // Versions before 0.13 ignore `-json`
+        let input = "Terraform v0.12.14";
+        assert_eq!(parse_terraform_version_json(input), None);
+        assert_eq!(
+            parse_terraform_version_json(input).or_else(|| format_terraform_version(input)),
+            Some("v0.12.14 ".to_string())
+        );
+        assert_eq!(
+            parse_terraform_version_json(r#"{"platform": "linux"}"#),
+            None
+        );
+    }
+
+    #[test]
+    fn test_get_terraform_workspace_from_environment_file() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let cwd = dir.path().to_path_buf();
+        let mut env = Env::default();
+        env.insert("TF_DATA_DIR", cwd.join("data").to_str().unwrap());
+        let context = Context::new_with_env(ArgMatches::default(), &cwd, env);
+        assert_eq!(
+            get_terraform_workspace(&context),
+            Some("default".to_string())
+        );
+
+        fs::create_dir(cwd.join("data"))?;
+        fs::write(cwd.join("data/environment"), "staging")?;
+        assert_eq!(
+            get_terraform_workspace(&context),
+            Some("staging".to_string())
+        );
+        dir.close()
+    }
+
+    #[test]
+    fn test_get_terraform_workspace_from_env() {
+        let mut env = Env::default();
+        env.insert("TF_WORKSPACE", "production");
+        let context = Context::new_with_env(ArgMatches::default(), "/", env);
+        assert_eq!(
+            get_terraform_workspace(&context),
+            Some("production".to_string())
+        );
+    }
+
+    #[test]
+    fn test_detect_tool_from_version_file() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let cwd = dir.path();
+        assert_eq!(detect_tool(cwd, None), Tool::Terraform);
+
+        fs::write(cwd.join(".opentofu-version"), "1.6.0")?;
+        assert_eq!(detect_tool(cwd, None), Tool::OpenTofu);
+
+        fs::remove_file(cwd.join(".opentofu-version"))?;
+        fs::write(cwd.join(".terraform-version"), "1.5.7")?;
+        assert_eq!(detect_tool(cwd, None), Tool::Terraform);
+        dir.close()
+    }
+
+    #[test]
+    fn test_detect_tool_from_installed_binary() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let cwd = dir.path().join("infra");
+        let tofu_bin = dir.path().join("tofu_bin");
+        let terraform_bin = dir.path().join("terraform_bin");
+        for path in &[&cwd, &tofu_bin, &terraform_bin] {
+            fs::create_dir(path)?;
+        }
+        fs::write(tofu_bin.join("tofu"), "")?;
+        fs::write(terraform_bin.join("terraform"), "")?;
+
+        let only_tofu = env::join_paths(vec![&tofu_bin]).unwrap();
+        assert_eq!(detect_tool(&cwd, Some(only_tofu)), Tool::OpenTofu);
+
+        let both = env::join_paths(vec![&tofu_bin, &terraform_bin]).unwrap();
+        assert_eq!(detect_tool(&cwd, Some(both.clone())), Tool::Terraform);
+
+        // A version file takes precedence over the installed binaries
+        fs::write(cwd.join(".opentofu-version"), "1.6.0")?;
+        assert_eq!(detect_tool(&cwd, Some(both)), Tool::OpenTofu);
+        dir.close()
+    }
+
+    #[test]
+    fn test_format_opentofu_version() {
+        let input = "OpenTofu v1.6.0\non linux_amd64";
+        assert_eq!(format_terraform_version(input), Some("v1.6.0 ".to_string()));
+    }
 
     #[test]
     fn test_format_terraform_version_release() {
@@ -3,7 +3,6 @@ use std::env;
 use super::{Context, Module, RootModuleConfig, SegmentConfig};
 
 use crate::configs::username::UsernameConfig;
-use crate::utils;
 
 /// Creates a module with the current user's username
 ///
@@ -17,7 +16,7 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     let ssh_connection = env::var("SSH_CONNECTION").ok();
 
     const ROOT_UID: Option<u32> = Some(0);
-    let user_uid = get_uid();
+    let user_uid = get_uid(context);
 
     let mut module = context.new_module("username");
     let config: UsernameConfig = UsernameConfig::try_load(module.config);
@@ -37,8 +36,9 @@ pub fn module<'a>(context: &'a Context) -> Option<Module<'a>> {
     }
 }
 
-fn get_uid() -> Option<u32> {
-    utils::exec_cmd("id", &["-u"])?
+pub(crate) fn get_uid(context: &Context) -> Option<u32> {
+    context
+        .exec_cmd("username", "id", &["-u"])?
         .stdout
         .trim()
         .parse::<u32>()
@@ -0,0 +1,126 @@
+use ansi_term::Color;
+
+use crate::configs::gauge::{GaugeConfig, GaugeDisplay};
+
+/// Block characters of increasing height used by the `block` display
+const BLOCKS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+/// Render `percentage` according to the display mode of the gauge `config`
+pub fn render_gauge(percentage: f64, config: &GaugeConfig, percent_sign: &str) -> String {
+    let percentage = percentage.max(0.0).min(100.0);
+
+    match config.display {
+        GaugeDisplay::Bar => format!(
+            "[{}]",
+            render_bar(
+                percentage,
+                config.width,
+                config.fill_symbol,
+                config.empty_symbol
+            )
+        ),
+        GaugeDisplay::Percent => format!("{:.0}{}", percentage, percent_sign),
+        GaugeDisplay::Block => render_block(percentage).to_string(),
+    }
+}
+
+/// Render a bar of `width` cells, filled in proportion to `percentage`
+fn render_bar(percentage: f64, width: usize, fill_symbol: &str, empty_symbol: &str) -> String {
+    let percentage = percentage.max(0.0).min(100.0);
+    let filled = ((percentage / 100.0) * width as f64).round() as usize;
+
+    fill_symbol.repeat(filled) + &empty_symbol.repeat(width - filled)
+}
+
+/// Render `percentage` as a single block character whose height reflects it
+fn render_block(percentage: f64) -> char {
+    let index = ((percentage / 100.0) * (BLOCKS.len() - 1) as f64).round() as usize;
+    BLOCKS[index]
+}
+
+/// Color shifting from green to yellow to red as `percentage` of usage grows
+pub fn usage_color(percentage: f64) -> Color {
+    match percentage {
+        p if p < 50.0 => Color::Green,
+        p if p < 80.0 => Color::Yellow,
+        _ => Color::Red,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::RootModuleConfig;
+
+    fn gauge_config(display: GaugeDisplay) -> GaugeConfig<'static> {
+        GaugeConfig {
+            display,
+            width: 4,
+            fill_symbol: "#",
+            empty_symbol: "-",
+            ..GaugeConfig::new()
+        }
+    }
+
+    #[test]
+    fn bar_empty() {
+        assert_eq!(render_bar(0.0, 10, "#", "-"), "----------");
+    }
+
+    #[test]
+    fn bar_half() {
+        assert_eq!(render_bar(50.0, 10, "#", "-"), "#####-----");
+    }
+
+    #[test]
+    fn bar_full() {
+        assert_eq!(render_bar(100.0, 10, "#", "-"), "##########");
+    }
+
+    #[test]
+    fn bar_rounds_to_nearest_cell() {
+        assert_eq!(render_bar(66.0, 5, "█", "░"), "███░░");
+    }
+
+    #[test]
+    fn bar_out_of_range() {
+        assert_eq!(render_bar(-5.0, 4, "#", "-"), "----");
+        assert_eq!(render_bar(120.0, 4, "#", "-"), "####");
+    }
+
+    #[test]
+    fn gauge_as_bar() {
+        let config = gauge_config(GaugeDisplay::Bar);
+        assert_eq!(render_gauge(0.0, &config, "%"), "[----]");
+        assert_eq!(render_gauge(50.0, &config, "%"), "[##--]");
+        assert_eq!(render_gauge(100.0, &config, "%"), "[####]");
+    }
+
+    #[test]
+    fn gauge_as_percent() {
+        let config = gauge_config(GaugeDisplay::Percent);
+        assert_eq!(render_gauge(0.0, &config, "%"), "0%");
+        assert_eq!(render_gauge(49.6, &config, "%%"), "50%%");
+        assert_eq!(render_gauge(100.0, &config, "%"), "100%");
+        assert_eq!(render_gauge(150.0, &config, "%"), "100%");
+    }
+
+    #[test]
+    fn gauge_as_block() {
+        let config = gauge_config(GaugeDisplay::Block);
+        assert_eq!(render_gauge(0.0, &config, "%"), "▁");
+        assert_eq!(render_gauge(50.0, &config, "%"), "▅");
+        assert_eq!(render_gauge(100.0, &config, "%"), "█");
+        assert_eq!(render_gauge(-10.0, &config, "%"), "▁");
+    }
+
+    #[test]
+    fn color_by_usage() {
+        assert_eq!(usage_color(0.0), Color::Green);
+        assert_eq!(usage_color(49.9), Color::Green);
+        assert_eq!(usage_color(50.0), Color::Yellow);
+        assert_eq!(usage_color(79.9), Color::Yellow);
+        assert_eq!(usage_color(80.0), Color::Red);
+        assert_eq!(usage_color(100.0), Color::Red);
+    }
+}
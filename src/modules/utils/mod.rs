@@ -1,5 +1,7 @@
 pub mod directory;
+pub mod gauge;
 pub mod java_version_parser;
+pub mod toolchain_source;
 
 #[cfg(test)]
 pub mod test;
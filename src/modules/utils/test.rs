@@ -1,12 +1,42 @@
 use crate::config::StarshipConfig;
-use crate::context::{Context, Shell};
+use crate::context::{ColorMode, Context, Env, Shell};
 use std::path::Path;
 
 /// Render a specific starship module by name
 pub fn render_module(module_name: &str, path: &Path) -> Option<String> {
-    let mut context = Context::new_with_dir(clap::ArgMatches::default(), path);
-    context.config = StarshipConfig { config: None };
-    context.shell = Shell::Unknown;
+    render_module_with(module_name, path, None, Env::default())
+}
+
+/// Render a specific starship module by name, using the given config
+pub fn render_module_with_config(
+    module_name: &str,
+    path: &Path,
+    config: toml::Value,
+) -> Option<String> {
+    render_module_with(module_name, path, Some(config), Env::default())
+}
+
+/// Render a specific starship module by name, using the given config and reading
+/// env vars from `env` only
+pub fn render_module_with_env(
+    module_name: &str,
+    path: &Path,
+    config: toml::Value,
+    env: Env,
+) -> Option<String> {
+    render_module_with(module_name, path, Some(config), env)
+}
+
+fn render_module_with(
+    module_name: &str,
+    path: &Path,
+    config: Option<toml::Value>,
+    env: Env,
+) -> Option<String> {
+    let mut context = Context::new_with_env(clap::ArgMatches::default(), path, env);
+    context.config = StarshipConfig { config };
+    context.shell = Shell::Unknown(String::new());
+    context.color_mode = ColorMode::Enabled;
 
     crate::print::get_module(module_name, context)
 }
@@ -0,0 +1,68 @@
+use std::path::Path;
+
+use crate::utils;
+
+/// Where the version displayed by a toolchain module came from
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ToolchainSource {
+    /// The toolchain found in `PATH`, not managed by any version manager
+    Path,
+    Nvm,
+    Asdf,
+    Rustup,
+    /// A version file in the current or a parent directory, e.g. `rust-toolchain`
+    File,
+}
+
+impl ToolchainSource {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ToolchainSource::Path => "path",
+            ToolchainSource::Nvm => "nvm",
+            ToolchainSource::Asdf => "asdf",
+            ToolchainSource::Rustup => "rustup",
+            ToolchainSource::File => "file",
+        }
+    }
+}
+
+/// Whether `tool` has a version set in the nearest `.tool-versions` file, as asdf
+/// looks it up
+pub fn is_asdf_managed(current_dir: &Path, tool: &str) -> bool {
+    let tool_versions = match current_dir
+        .ancestors()
+        .map(|dir| dir.join(".tool-versions"))
+        .find(|path| path.is_file())
+        .and_then(|path| utils::read_file(path).ok())
+    {
+        Some(tool_versions) => tool_versions,
+        None => return false,
+    };
+
+    tool_versions
+        .lines()
+        .any(|line| line.split_whitespace().next() == Some(tool))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use std::io;
+
+    #[test]
+    fn test_is_asdf_managed() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        fs::write(
+            dir.path().join(".tool-versions"),
+            "nodejs 12.0.0\n# golang 1.14\n",
+        )?;
+        let sub_dir = dir.path().join("src");
+        fs::create_dir_all(&sub_dir)?;
+
+        assert!(is_asdf_managed(&sub_dir, "nodejs"));
+        assert!(!is_asdf_managed(&sub_dir, "golang"));
+        assert!(!is_asdf_managed(&sub_dir, "node"));
+        dir.close()
+    }
+}
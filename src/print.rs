@@ -1,14 +1,24 @@
 use ansi_term::ANSIStrings;
+use chrono::{DateTime, Local, Timelike};
 use clap::ArgMatches;
+use once_cell::sync::OnceCell;
 use rayon::prelude::*;
 use std::fmt::{self, Debug, Write as FmtWrite};
+use std::fs::OpenOptions;
 use std::io::{self, Write};
-use unicode_width::UnicodeWidthChar;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+use unicode_width::{UnicodeWidthChar, UnicodeWidthStr};
 
+use crate::config::parse_style_string;
+use crate::configs::StarshipRootConfig;
 use crate::context::{Context, Shell};
+use crate::formatter::string_formatter::FILL_SEGMENT;
+use crate::logger;
 use crate::module::Module;
 use crate::module::ALL_MODULES;
 use crate::modules;
+use crate::segment::Segment;
 
 pub fn prompt(args: ArgMatches) {
     let context = Context::new(args);
@@ -32,7 +42,10 @@ pub fn get_prompt(context: Context) -> String {
         buf.push_str("\x1b[J"); // An ASCII control code to clear screen
     }
 
-    let modules = compute_modules(&context);
+    let mut modules = compute_modules(&context);
+    if let Some(width) = context.width() {
+        fill_modules(&mut modules, width);
+    }
 
     let mut print_without_prefix = true;
     let printable = modules.iter();
@@ -40,19 +53,98 @@ pub fn get_prompt(context: Context) -> String {
     for module in printable {
         // Skip printing the prefix of a module after the line_break
         if print_without_prefix {
-            let module_without_prefix = module.to_string_without_prefix(context.shell.clone());
+            let module_without_prefix = module.to_string_without_prefix(context.shell());
             write!(buf, "{}", module_without_prefix).unwrap()
         } else {
-            let module = module.ansi_strings_for_shell(context.shell.clone());
+            let module = module.ansi_strings_for_shell(context.shell());
             write!(buf, "{}", ANSIStrings(&module)).unwrap();
         }
 
-        print_without_prefix = module.get_name() == "line_break"
+        print_without_prefix = skips_next_prefix(module, print_without_prefix);
+    }
+
+    modules::last_warnings::record_prompt_warnings(&context, logger::warning_count());
+    modules::character::record_prompt_streak(&context);
+
+    if let Some(profile_output) = context.get_env("STARSHIP_PROFILE_OUTPUT") {
+        if let Err(error) = write_profile(&context, Path::new(&profile_output)) {
+            log::warn!(
+                "Unable to write the profile to {}: {}",
+                profile_output,
+                error
+            );
+        }
     }
 
     buf
 }
 
+/// Whether the prefix of the module after `module` isn't printed, `skipped` being
+/// whether that of `module` wasn't
+fn skips_next_prefix(module: &Module, skipped: bool) -> bool {
+    // Raw escapes, fills and titles aren't content, so they shouldn't consume the skipped prefix
+    let is_content = module.get_name() != "fill"
+        && module.get_name() != "terminal_title"
+        && !module.get_name().starts_with("raw_escape.");
+    module.get_name() == "line_break" || (skipped && !is_content)
+}
+
+/// Pads the `fill` modules of the prompt, so that the modules following them are
+/// pushed to the right edge of a terminal `width` columns wide
+fn fill_modules(modules: &mut [Module], width: usize) {
+    let mut print_without_prefix = true;
+    let mut line: Vec<Segment> = Vec::with_capacity(modules.len());
+    for module in modules.iter_mut() {
+        if module.get_name() == "fill" {
+            line.push(Segment::new(FILL_SEGMENT));
+            continue;
+        }
+
+        let mut segment = Segment::new(module.get_name());
+        if !print_without_prefix {
+            segment.value.push_str(module.get_prefix().get_value());
+        }
+        segment.value.push_str(&module.get_segments().join(""));
+        segment.value.push_str(module.get_suffix().get_value());
+        line.push(segment);
+
+        print_without_prefix = skips_next_prefix(module, print_without_prefix);
+    }
+
+    expand_fill(&mut line, width);
+    for (module, segment) in modules.iter_mut().zip(line) {
+        if module.get_name() == "fill" {
+            module.set_segments(vec![segment]);
+        }
+    }
+}
+
+/// Appends how long each module of the prompt took to compute to `path`, as a line of
+/// JSON like `{"timestamp":1590000000000,"modules":[{"name":"git_status","duration_us":4242}]}`
+fn write_profile(context: &Context, path: &Path) -> io::Result<()> {
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|since_epoch| since_epoch.as_millis())
+        .unwrap_or_default();
+    let modules: Vec<serde_json::Value> = context
+        .get_module_timings()
+        .iter()
+        .map(|timing| {
+            serde_json::json!({
+                "name": timing.name,
+                "duration_us": timing.elapsed.as_micros() as u64,
+            })
+        })
+        .collect();
+    let profile = serde_json::json!({
+        "timestamp": timestamp as u64,
+        "modules": modules,
+    });
+
+    let mut file = OpenOptions::new().create(true).append(true).open(path)?;
+    writeln!(file, "{}", profile)
+}
+
 pub fn module(module_name: &str, args: ArgMatches) {
     let context = Context::new(args);
     let module = get_module(module_name, context).unwrap_or_default();
@@ -72,11 +164,12 @@ pub fn explain(args: ArgMatches) {
         desc: String,
     }
 
-    let dont_print = vec!["line_break", "character"];
+    let dont_print = vec!["line_break", "fill", "terminal_title", "character"];
 
     let modules = compute_modules(&context)
         .into_iter()
         .filter(|module| !dont_print.contains(&module.get_name().as_str()))
+        .filter(|module| !module.get_name().starts_with("raw_escape."))
         .map(|module| {
             let ansi_strings = module.ansi_strings();
             let value = module.get_segments().join("");
@@ -99,8 +192,8 @@ pub fn explain(args: ArgMatches) {
         max_module_width = std::cmp::max(max_module_width, info.value_len);
     }
 
-    let desc_width = term_size::dimensions()
-        .map(|(w, _)| w)
+    let desc_width = context
+        .width()
         .map(|width| width - std::cmp::min(width, max_ansi_module_width));
 
     println!("\n Here's a breakdown of your prompt:");
@@ -135,6 +228,8 @@ fn compute_modules<'a>(context: &'a Context) -> Vec<Module<'a>> {
     enum Mod<'a> {
         Builtin(&'a str),
         Custom(&'a str),
+        RawEscape(&'a str),
+        TerminalTitle,
     }
 
     struct DebugCustomModules<'tmp>(&'tmp toml::value::Table);
@@ -148,10 +243,17 @@ fn compute_modules<'a>(context: &'a Context) -> Vec<Module<'a>> {
     let mut prompt_order: Vec<Mod> = Vec::new();
 
     // Write out a custom prompt order
-    let config_prompt_order = context.config.get_root_config().prompt_order;
+    let root_config = context.config.get_root_config();
+    let config_prompt_order =
+        apply_order_hints(select_prompt_order(&root_config, Local::now()), context);
 
     for module in &config_prompt_order {
-        if ALL_MODULES.contains(module) {
+        if *module == "terminal_title" {
+            // The title is made of the other modules, so it is only computed after them
+            if !context.is_module_disabled_in_config(*module) {
+                prompt_order.push(Mod::TerminalTitle);
+            }
+        } else if ALL_MODULES.contains(module) {
             // Write out a module if it isn't disabled
             if !context.is_module_disabled_in_config(*module) {
                 prompt_order.push(Mod::Builtin(module));
@@ -186,6 +288,8 @@ fn compute_modules<'a>(context: &'a Context) -> Vec<Module<'a>> {
                     ),
                 },
             }
+        } else if module.starts_with("raw_escape.") {
+            prompt_order.push(Mod::RawEscape(&module[11..]));
         } else {
             log::debug!(
                 "Expected prompt_order to contain value from {:?}. Instead received {}",
@@ -195,14 +299,171 @@ fn compute_modules<'a>(context: &'a Context) -> Vec<Module<'a>> {
         }
     }
 
-    prompt_order
-        .par_iter()
-        .map(|module| match module {
-            Mod::Builtin(builtin) => modules::handle(builtin, context),
-            Mod::Custom(custom) => modules::custom::module(custom, context),
-        }) // Compute segments
-        .flatten() // Remove segments set to `None`
-        .collect::<Vec<Module<'a>>>()
+    let parallelism = root_config.parallelism;
+    let is_root = OnceCell::new();
+
+    let mut modules = with_parallelism(parallelism, || {
+        prompt_order
+            .par_iter()
+            .map(|module| match module {
+                Mod::Builtin(builtin) => modules::handle(builtin, context),
+                Mod::Custom(custom) => context
+                    .compute_module(&format!("custom.{}", custom), || {
+                        modules::custom::module(custom, context)
+                    }),
+                Mod::RawEscape(name) => modules::raw_escape::module(name, context),
+                Mod::TerminalTitle => None,
+            }) // Compute segments
+            .map(|module| {
+                module.map(|mut module| {
+                    highlight_root(&mut module, &root_config, || {
+                        *is_root.get_or_init(|| modules::username::get_uid(context) == Some(0))
+                    });
+                    module
+                })
+            })
+            .collect::<Vec<Option<Module<'a>>>>()
+    });
+
+    let title_index = prompt_order.iter().position(|module| match module {
+        Mod::TerminalTitle => true,
+        _ => false,
+    });
+    if let Some(index) = title_index {
+        // Modules of the prompt are reused as is, even those which returned `None`
+        let module_text = |name: &str| {
+            let index = prompt_order.iter().position(|module| match module {
+                Mod::Builtin(builtin) => *builtin == name,
+                _ => false,
+            });
+            match index {
+                Some(index) => modules[index]
+                    .as_ref()
+                    .map(|module| module.get_segments().join("")),
+                None => modules::terminal_title::compute_module_text(name, context),
+            }
+        };
+        let title = context.compute_module("terminal_title", || {
+            modules::terminal_title::module(context, module_text)
+        });
+        modules[index] = title;
+    }
+
+    modules.into_iter().flatten().collect()
+}
+
+/// Restyle the segments of `module` with `root_style` when running as root.
+///
+/// Both `highlight_root` and `root_style` can be overridden in the module's own config.
+fn highlight_root(
+    module: &mut Module,
+    root_config: &StarshipRootConfig,
+    is_root: impl FnOnce() -> bool,
+) {
+    let module_config = module.config.and_then(toml::Value::as_table);
+    let enabled = module_config
+        .and_then(|config| config.get("highlight_root")?.as_bool())
+        .unwrap_or(root_config.highlight_root);
+    if !enabled || !is_root() {
+        return;
+    }
+
+    let style = module_config
+        .and_then(|config| parse_style_string(config.get("root_style")?.as_str()?))
+        .unwrap_or(root_config.root_style);
+    module.override_segment_styles(style);
+}
+
+/// Pick `night_prompt_order` during the night hours, if it is set, or else `prompt_order`
+fn select_prompt_order<'b>(config: &StarshipRootConfig<'b>, now: DateTime<Local>) -> Vec<&'b str> {
+    let hour = u64::from(now.hour());
+    let (start, end) = (config.night_start_hour, config.night_end_hour);
+    // The night usually wraps around midnight, e.g. from 19 to 7
+    let is_night = if start <= end {
+        start <= hour && hour < end
+    } else {
+        start <= hour || hour < end
+    };
+
+    if is_night && !config.night_prompt_order.is_empty() {
+        config.night_prompt_order.clone()
+    } else {
+        config.prompt_order.clone()
+    }
+}
+
+/// Move each module with a `before` or `after` option right next to the module it
+/// names, keeping the rest of the prompt order as is
+fn apply_order_hints<'b>(prompt_order: Vec<&'b str>, context: &'b Context) -> Vec<&'b str> {
+    let mut order = prompt_order.clone();
+
+    for module in prompt_order {
+        let (before, target) = match get_order_hint(module, context) {
+            Some(hint) => hint,
+            None => continue,
+        };
+        if target == module || !order.contains(&target) {
+            log::debug!(
+                "Ignoring the order hint of {}: {} isn't in the prompt",
+                module,
+                target
+            );
+            continue;
+        }
+
+        if let Some(index) = order.iter().position(|m| *m == module) {
+            order.remove(index);
+        }
+        let target_index = order.iter().position(|m| *m == target).unwrap();
+        let index = if before {
+            target_index
+        } else {
+            target_index + 1
+        };
+        order.insert(index, module);
+    }
+
+    order
+}
+
+/// Returns whether the module should go before (or else after) another one, and which
+fn get_order_hint<'b>(module: &str, context: &'b Context) -> Option<(bool, &'b str)> {
+    let config = if module.starts_with("custom.") {
+        context.config.get_custom_module_config(&module[7..])
+    } else {
+        context.config.get_module_config(module)
+    }?
+    .as_table()?;
+
+    if let Some(target) = config.get("before").and_then(toml::Value::as_str) {
+        return Some((true, target));
+    }
+    let target = config.get("after").and_then(toml::Value::as_str)?;
+    Some((false, target))
+}
+
+/// Run `op` in a thread pool limited to `parallelism` threads, bounding the number
+/// of modules (and thus subprocesses) computed at the same time.
+/// A `parallelism` of 0 uses the global thread pool.
+fn with_parallelism<T, F>(parallelism: usize, op: F) -> T
+where
+    T: Send,
+    F: FnOnce() -> T + Send,
+{
+    if parallelism == 0 {
+        return op();
+    }
+
+    match rayon::ThreadPoolBuilder::new()
+        .num_threads(parallelism)
+        .build()
+    {
+        Ok(pool) => pool.install(op),
+        Err(error) => {
+            log::debug!("Unable to build a thread pool: {}", error);
+            op()
+        }
+    }
 }
 
 fn should_add_implicit_custom_module(
@@ -228,6 +489,269 @@ fn should_add_implicit_custom_module(
         .unwrap_or(false)
 }
 
+/// Pads the fill segments emitted by a `StringFormatter` for `$fill` with spaces, so
+/// that what follows them is pushed to the right edge of a terminal `width` columns
+/// wide. Each line of a multi-line format is padded on its own, with the padding split
+/// between its fill segments. Lines without room to spare leave them empty.
+fn expand_fill(segments: &mut [Segment], width: usize) {
+    fn pad_line(segments: &mut [Segment], fills: &[usize], line_width: usize, width: usize) {
+        if fills.is_empty() {
+            return;
+        }
+        let padding = width.saturating_sub(line_width);
+        for (i, fill) in fills.iter().enumerate() {
+            let extra = if i < padding % fills.len() { 1 } else { 0 };
+            segments[*fill].value = " ".repeat(padding / fills.len() + extra);
+        }
+    }
+
+    let mut fills = Vec::new();
+    let mut line_width = 0;
+    for index in 0..segments.len() {
+        if segments[index]._name == FILL_SEGMENT {
+            fills.push(index);
+            continue;
+        }
+
+        let value = segments[index].value.clone();
+        let mut lines = value.split('\n');
+        line_width += lines.next().unwrap_or_default().width();
+        for line in lines {
+            pad_line(segments, &fills, line_width, width);
+            fills.clear();
+            line_width = line.width();
+        }
+    }
+    pad_line(segments, &fills, line_width, width);
+}
+
 fn count_wide_chars(value: &str) -> usize {
     value.chars().filter(|c| c.width().unwrap_or(0) > 1).count()
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::StarshipConfig;
+    use crate::formatter::StringFormatter;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::thread;
+    use std::time::Duration;
+
+    fn render_filled(format: &str, width: usize) -> String {
+        let mut segments = StringFormatter::new(format)
+            .unwrap()
+            .map(|variable| match variable {
+                "dir" => Some("~/rocket".to_string()),
+                "time" => Some("12:00".to_string()),
+                _ => None,
+            })
+            .parse(None);
+        expand_fill(&mut segments, width);
+        segments
+            .iter()
+            .map(|segment| segment.value.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn expand_fill_to_width() {
+        assert_eq!(render_filled("$dir$fill$time", 20), "~/rocket       12:00");
+        assert_eq!(render_filled("$fill$time", 8), "   12:00");
+        // The padding is split between the fill segments of a line
+        assert_eq!(render_filled("$fill$dir$fill", 11), "  ~/rocket ");
+        // Without room to spare, the fill is left empty
+        assert_eq!(render_filled("$dir$fill$time", 10), "~/rocket12:00");
+    }
+
+    #[test]
+    fn expand_fill_per_line() {
+        assert_eq!(
+            render_filled("$dir$fill$time\n❯ $fill", 16),
+            "~/rocket   12:00\n❯               "
+        );
+        assert_eq!(render_filled("$dir\n$fill$time", 7), "~/rocket\n  12:00");
+    }
+
+    #[test]
+    fn with_parallelism_bounds_concurrency() {
+        let running = AtomicUsize::new(0);
+        let max_running = AtomicUsize::new(0);
+
+        with_parallelism(2, || {
+            (0..16).into_par_iter().for_each(|_| {
+                let now_running = running.fetch_add(1, Ordering::SeqCst) + 1;
+                max_running.fetch_max(now_running, Ordering::SeqCst);
+                thread::sleep(Duration::from_millis(5));
+                running.fetch_sub(1, Ordering::SeqCst);
+            })
+        });
+
+        let max_running = max_running.load(Ordering::SeqCst);
+        assert!(max_running >= 1);
+        assert!(max_running <= 2);
+    }
+
+    #[test]
+    fn selects_prompt_order_by_hour() {
+        use chrono::TimeZone;
+
+        let context = context_with_config(toml::toml! {
+            prompt_order = ["directory", "character"]
+            night_prompt_order = ["time", "character"]
+            night_start_hour = 20
+            night_end_hour = 6
+        });
+        let config = context.config.get_root_config();
+
+        let noon = Local.ymd(2020, 5, 1).and_hms(12, 0, 0);
+        assert_eq!(
+            select_prompt_order(&config, noon),
+            vec!["directory", "character"]
+        );
+        let night = Local.ymd(2020, 5, 1).and_hms(23, 30, 0);
+        assert_eq!(
+            select_prompt_order(&config, night),
+            vec!["time", "character"]
+        );
+        let early_morning = Local.ymd(2020, 5, 1).and_hms(5, 59, 0);
+        assert_eq!(
+            select_prompt_order(&config, early_morning),
+            vec!["time", "character"]
+        );
+    }
+
+    #[test]
+    fn moves_modules_by_order_hints() {
+        let context = context_with_config(toml::toml! {
+            [git_branch]
+            before = "directory"
+
+            [username]
+            after = "character"
+
+            [time]
+            before = "battery"
+        });
+        let prompt_order = vec!["username", "directory", "git_branch", "time", "character"];
+
+        assert_eq!(
+            apply_order_hints(prompt_order, &context),
+            vec!["git_branch", "directory", "time", "character", "username"]
+        );
+    }
+
+    #[test]
+    fn selects_day_prompt_order_without_night_order() {
+        use chrono::TimeZone;
+
+        let context = context_with_config(toml::toml! {
+            prompt_order = ["directory", "character"]
+        });
+        let config = context.config.get_root_config();
+
+        let night = Local.ymd(2020, 5, 1).and_hms(23, 30, 0);
+        assert_eq!(
+            select_prompt_order(&config, night),
+            vec!["directory", "character"]
+        );
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn highlights_modules_as_root() {
+        use crate::config::SegmentConfig;
+        use ansi_term::Color;
+
+        let context = context_with_config(toml::toml! {
+            highlight_root = true
+            root_style = "bold red"
+
+            [directory]
+            root_style = "bold yellow"
+
+            [time]
+            highlight_root = false
+        });
+        let root_config = context.config.get_root_config();
+        let render = |name: &str, is_root: bool| {
+            let mut module = context.new_module(name);
+            module.get_prefix().set_value("");
+            module.create_segment(
+                "value",
+                &SegmentConfig::new("text").with_style(Some(Color::Blue.normal())),
+            );
+            highlight_root(&mut module, &root_config, || is_root);
+            module.to_string()
+        };
+
+        let paint = |color: Color| format!("{} ", color.bold().paint("text"));
+        assert_eq!(render("hostname", true), paint(Color::Red));
+        assert_eq!(render("directory", true), paint(Color::Yellow));
+        assert_eq!(
+            render("time", true),
+            format!("{} ", Color::Blue.paint("text"))
+        );
+        assert_eq!(
+            render("hostname", false),
+            format!("{} ", Color::Blue.paint("text"))
+        );
+    }
+
+    fn context_with_config(config: toml::Value) -> Context<'static> {
+        let mut context = Context::new_with_dir(ArgMatches::default(), "/");
+        context.config = StarshipConfig {
+            config: Some(config),
+        };
+        context
+    }
+
+    #[test]
+    fn profile_appended_as_json_lines() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let profile_path = dir.path().join("profile.jsonl");
+        let context = context_with_config(toml::Value::Table(Default::default()));
+        context.time_module("line_break", || thread::sleep(Duration::from_millis(1)));
+
+        write_profile(&context, &profile_path)?;
+        write_profile(&context, &profile_path)?;
+
+        let profile = std::fs::read_to_string(&profile_path)?;
+        let lines: Vec<&str> = profile.lines().collect();
+        assert_eq!(lines.len(), 2);
+
+        let render: serde_json::Value = serde_json::from_str(lines[0])?;
+        assert!(render["timestamp"].as_u64().unwrap() > 0);
+        let modules = render["modules"].as_array().unwrap();
+        assert_eq!(modules.len(), 1);
+        assert_eq!(modules[0]["name"], "line_break");
+        assert!(modules[0]["duration_us"].as_u64().unwrap() >= 1000);
+        dir.close()
+    }
+
+    #[test]
+    fn terminal_title_reuses_prompt_modules() {
+        let mut context = context_with_config(toml::toml! {
+            prompt_order = ["jobs", "terminal_title", "line_break"]
+
+            [terminal_title]
+            disabled = false
+            format = "$jobs$line_break"
+        });
+        context.properties.insert("jobs", "3".to_string());
+
+        let modules = compute_modules(&context);
+        let names: Vec<&str> = modules
+            .iter()
+            .map(|module| module.get_name().as_str())
+            .collect();
+        assert_eq!(names, vec!["jobs", "terminal_title", "line_break"]);
+        assert_eq!(modules[1].get_segments().join(""), "\x1b]0;✦3\x07");
+
+        let timings = context.get_module_timings();
+        let computed = |name: &str| timings.iter().filter(|timing| timing.name == name).count();
+        assert_eq!(computed("jobs"), 1);
+        assert_eq!(computed("line_break"), 1);
+        assert_eq!(computed("terminal_title"), 1);
+    }
+}
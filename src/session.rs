@@ -0,0 +1,259 @@
+use std::env;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Counter making the names of temporary files unique within the process
+static TEMP_FILE_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+/// Small pieces of state kept between the renders of a shell session.
+///
+/// The store lives in a dir named after `STARSHIP_SESSION_KEY`, which the init
+/// scripts set once per shell, inside `$XDG_RUNTIME_DIR/starship` or else the
+/// starship cache dir. Only the current user may access it, and a dir owned by
+/// anyone else is never used. Every key is kept in its own file, so modules setting
+/// different keys while rendering in parallel don't overwrite each other.
+pub struct SessionStore {
+    dir: PathBuf,
+}
+
+impl SessionStore {
+    /// Returns the store of the current shell session, or `None` if there isn't one
+    pub fn new() -> Option<Self> {
+        let session_key = env::var("STARSHIP_SESSION_KEY").ok()?;
+        Self::for_session(&session_key)
+    }
+
+    /// Returns the store of the session identified by `session_key`
+    pub fn for_session(session_key: &str) -> Option<Self> {
+        if !is_valid_name(session_key) {
+            log::debug!("Invalid session key: {:?}", session_key);
+            return None;
+        }
+
+        let dir = get_sessions_dir()?.join(format!("session-{}", session_key));
+        Some(SessionStore { dir })
+    }
+
+    /// Returns a store kept in `dir`, independently of any shell session
+    #[cfg(test)]
+    pub fn in_dir(dir: &Path) -> Self {
+        SessionStore {
+            dir: dir.join("session"),
+        }
+    }
+
+    /// Returns the value last stored under `key` in this session
+    pub fn get(&self, key: &str) -> Option<String> {
+        if !is_valid_name(key) {
+            return None;
+        }
+
+        if let Err(error) = check_private_dir(&self.dir) {
+            if error.kind() != io::ErrorKind::NotFound {
+                log::warn!("Not using the session store in {:?}: {}", self.dir, error);
+            }
+            return None;
+        }
+
+        fs::read_to_string(self.dir.join(key)).ok()
+    }
+
+    /// Stores `value` under `key`. The value is written to a temporary file which is
+    /// then renamed over the old one, so a reader never sees a partial write.
+    pub fn set(&self, key: &str, value: &str) -> io::Result<()> {
+        if !is_valid_name(key) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("Invalid session store key: {:?}", key),
+            ));
+        }
+
+        create_private_dir(&self.dir)?;
+        check_private_dir(&self.dir)?;
+
+        let temp_path = self.dir.join(format!(
+            ".{}.{}.{}",
+            key,
+            process::id(),
+            TEMP_FILE_COUNTER.fetch_add(1, Ordering::Relaxed)
+        ));
+        fs::write(&temp_path, value)?;
+        fs::rename(&temp_path, self.dir.join(key)).map_err(|error| {
+            let _ = fs::remove_file(&temp_path);
+            error
+        })
+    }
+}
+
+/// The dir holding the stores of all sessions: `$XDG_RUNTIME_DIR/starship`, which
+/// is private to the user and cleared on logout, or else the starship cache dir
+fn get_sessions_dir() -> Option<PathBuf> {
+    env::var_os("XDG_RUNTIME_DIR")
+        .map(PathBuf::from)
+        .filter(|dir| dir.is_absolute())
+        .or_else(dirs::cache_dir)
+        .map(|dir| dir.join("starship"))
+}
+
+/// Creates `dir` and its missing parents, readable by the current user only
+#[cfg(unix)]
+fn create_private_dir(dir: &Path) -> io::Result<()> {
+    use std::os::unix::fs::DirBuilderExt;
+    fs::DirBuilder::new()
+        .recursive(true)
+        .mode(0o700)
+        .create(dir)
+}
+
+#[cfg(not(unix))]
+fn create_private_dir(dir: &Path) -> io::Result<()> {
+    fs::create_dir_all(dir)
+}
+
+/// Fails unless `dir` is a dir, rather than a symlink, owned by the current user
+#[cfg(unix)]
+fn check_private_dir(dir: &Path) -> io::Result<()> {
+    use std::os::unix::fs::MetadataExt;
+
+    let metadata = fs::symlink_metadata(dir)?;
+    if !metadata.is_dir() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "the session store is not a directory",
+        ));
+    }
+    // Safe: geteuid can't fail and has no side effects
+    if metadata.uid() != unsafe { libc::geteuid() } {
+        return Err(io::Error::new(
+            io::ErrorKind::PermissionDenied,
+            "the session store is owned by another user",
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(not(unix))]
+fn check_private_dir(dir: &Path) -> io::Result<()> {
+    if !fs::symlink_metadata(dir)?.is_dir() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "the session store is not a directory",
+        ));
+    }
+    Ok(())
+}
+
+/// Only allow names which can't escape the store's dir or clash with temporary files
+fn is_valid_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn store_in(dir: &tempfile::TempDir) -> SessionStore {
+        SessionStore::in_dir(dir.path())
+    }
+
+    #[test]
+    fn test_set_then_get_across_renders() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+
+        let first_render = store_in(&dir);
+        assert_eq!(first_render.get("prompt_count"), None);
+        first_render.set("prompt_count", "1")?;
+
+        let second_render = store_in(&dir);
+        assert_eq!(second_render.get("prompt_count"), Some("1".to_string()));
+        second_render.set("prompt_count", "2")?;
+
+        let third_render = store_in(&dir);
+        assert_eq!(third_render.get("prompt_count"), Some("2".to_string()));
+        dir.close()
+    }
+
+    #[test]
+    fn test_keys_are_independent() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let store = store_in(&dir);
+
+        store.set("battery", "42")?;
+        store.set("network", "1024")?;
+
+        assert_eq!(store.get("battery"), Some("42".to_string()));
+        assert_eq!(store.get("network"), Some("1024".to_string()));
+        dir.close()
+    }
+
+    #[test]
+    fn test_concurrent_writes() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+
+        let handles: Vec<_> = (0..8)
+            .map(|i| {
+                let store = store_in(&dir);
+                std::thread::spawn(move || store.set("shared", &i.to_string().repeat(1000)))
+            })
+            .collect();
+        for handle in handles {
+            handle.join().unwrap()?;
+        }
+
+        // Whichever write landed last, the value must be one of them in full
+        let value = store_in(&dir).get("shared").unwrap();
+        assert!((0..8).any(|i| value == i.to_string().repeat(1000)));
+        dir.close()
+    }
+
+    #[test]
+    fn test_invalid_names() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let store = store_in(&dir);
+
+        assert!(store.set("../escape", "value").is_err());
+        assert!(store.set("", "value").is_err());
+        assert_eq!(store.get("../escape"), None);
+        assert!(SessionStore::for_session("../tmp").is_none());
+        assert!(SessionStore::for_session("1234567890").is_some());
+        dir.close()
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_dir_private_to_user() -> io::Result<()> {
+        use std::os::unix::fs::PermissionsExt;
+
+        let dir = tempfile::tempdir()?;
+        let store = store_in(&dir);
+        store.set("prompt_count", "1")?;
+
+        let mode = fs::metadata(dir.path().join("session"))?
+            .permissions()
+            .mode();
+        assert_eq!(mode & 0o777, 0o700);
+        dir.close()
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn test_symlinked_dir_refused() -> io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let target = dir.path().join("elsewhere");
+        fs::create_dir(&target)?;
+        fs::write(target.join("prompt_count"), "1")?;
+        std::os::unix::fs::symlink(&target, dir.path().join("session"))?;
+
+        let store = store_in(&dir);
+        assert_eq!(store.get("prompt_count"), None);
+        assert!(store.set("prompt_count", "2").is_err());
+        assert_eq!(fs::read_to_string(target.join("prompt_count"))?, "1");
+        dir.close()
+    }
+}
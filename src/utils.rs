@@ -1,7 +1,10 @@
+use std::cmp::Ordering;
 use std::fs::File;
-use std::io::{Read, Result};
+use std::io::{self, Read, Result};
 use std::path::Path;
-use std::process::Command;
+use std::process::{Command, Output, Stdio};
+use std::thread;
+use std::time::{Duration, Instant};
 
 use crate::context::Shell;
 
@@ -18,22 +21,71 @@ pub fn read_file<P: AsRef<Path>>(file_name: P) -> Result<String> {
 pub struct CommandOutput {
     pub stdout: String,
     pub stderr: String,
+    /// The exit code of the command, `None` if it was terminated by a signal
+    pub exit_code: Option<i32>,
 }
 
 impl PartialEq for CommandOutput {
     fn eq(&self, other: &Self) -> bool {
-        self.stdout == other.stdout && self.stderr == other.stderr
+        self.stdout == other.stdout
+            && self.stderr == other.stderr
+            && self.exit_code == other.exit_code
     }
 }
 
-/// Execute a command and return the output on stdout and stderr if sucessful
+impl From<Output> for CommandOutput {
+    fn from(output: Output) -> Self {
+        CommandOutput {
+            stdout: String::from_utf8_lossy(&output.stdout).into_owned(),
+            stderr: String::from_utf8_lossy(&output.stderr).into_owned(),
+            exit_code: output.status.code(),
+        }
+    }
+}
+
+/// Execute a command and return the output on stdout and stderr if sucessful within
+/// `time_limit`. A command taking longer is killed and treated as having no output.
 #[cfg(not(test))]
-pub fn exec_cmd(cmd: &str, args: &[&str]) -> Option<CommandOutput> {
-    internal_exec_cmd(&cmd, &args)
+pub fn exec_cmd_with_timeout(
+    cmd: &str,
+    args: &[&str],
+    time_limit: Duration,
+) -> Option<CommandOutput> {
+    internal_exec_cmd(&cmd, &args, Some(time_limit))
+}
+
+/// Execute a command and return its raw output, including its exit status, even if it
+/// failed: some tools report what a module needs on stderr along with an error status.
+/// A command taking longer than `time_limit` is killed and treated as having no output.
+pub fn exec_cmd_full_with_timeout(
+    cmd: &str,
+    args: &[&str],
+    time_limit: Duration,
+) -> Option<Output> {
+    log::trace!("Executing command {:?} with args {:?}", cmd, args);
+    match output_with_timeout(Command::new(cmd).args(args), time_limit) {
+        Ok(output) => {
+            log::trace!("exit code: \"{:?}\"", output.status.code());
+            Some(output)
+        }
+        Err(error) => {
+            log::trace!("Executing command {:?} failed by: {:?}", cmd, error);
+            None
+        }
+    }
 }
 
 #[cfg(test)]
-pub fn exec_cmd(cmd: &str, args: &[&str]) -> Option<CommandOutput> {
+pub fn exec_cmd_with_timeout(
+    cmd: &str,
+    args: &[&str],
+    time_limit: Duration,
+) -> Option<CommandOutput> {
+    mock_cmd(cmd, args).or_else(|| internal_exec_cmd(&cmd, &args, Some(time_limit)))
+}
+
+#[cfg(test)]
+fn mock_cmd(cmd: &str, args: &[&str]) -> Option<CommandOutput> {
     let command = match args.len() {
         0 => String::from(cmd),
         _ => format!("{} {}", cmd, args.join(" ")),
@@ -42,40 +94,101 @@ pub fn exec_cmd(cmd: &str, args: &[&str]) -> Option<CommandOutput> {
         "crystal --version" => Some(CommandOutput {
             stdout: String::from("Crystal 0.32.1 (2019-12-18)"),
             stderr: String::default(),
+            exit_code: Some(0),
         }),
         "dummy_command" => Some(CommandOutput {
             stdout: String::from("stdout ok!"),
             stderr: String::from("stderr ok!"),
+            exit_code: Some(0),
         }),
         "elm --version" => Some(CommandOutput {
             stdout: String::from("0.19.1"),
             stderr: String::default(),
+            exit_code: Some(0),
+        }),
+        "ansible --version" => Some(CommandOutput {
+            stdout: String::from(
+                "\
+ansible [core 2.15.0]
+  config file = /etc/ansible/ansible.cfg
+  python version = 3.11.3 (main, Apr  5 2023, 00:00:00) [GCC 13.0.1]
+  jinja version = 3.1.2\n",
+            ),
+            stderr: String::default(),
+            exit_code: Some(0),
+        }),
+        "pulumi version" => Some(CommandOutput {
+            stdout: String::from("v3.5.1\n"),
+            stderr: String::default(),
+            exit_code: Some(0),
+        }),
+        "flutter --version" => Some(CommandOutput {
+            stdout: String::from(
+                "\
+Flutter 3.7.0 • channel stable • https://github.com/flutter/flutter.git
+Framework • revision b06b8b2710 (3 weeks ago) • 2023-01-23 16:55:55 -0800
+Engine • revision b24591ed32
+Tools • Dart 2.19.0 • DevTools 2.20.1\n",
+            ),
+            stderr: String::default(),
+            exit_code: Some(0),
+        }),
+        "gleam --version" => Some(CommandOutput {
+            stdout: String::from("gleam 0.9.0"),
+            stderr: String::default(),
+            exit_code: Some(0),
         }),
         "go version" => Some(CommandOutput {
             stdout: String::from("go version go1.12.1 linux/amd64"),
             stderr: String::default(),
+            exit_code: Some(0),
         }),
         "julia --version" => Some(CommandOutput {
             stdout: String::from("julia version 1.4.0"),
             stderr: String::default(),
+            exit_code: Some(0),
         }),
         "node --version" => Some(CommandOutput {
             stdout: String::from("v12.0.0"),
             stderr: String::default(),
+            exit_code: Some(0),
+        }),
+        "ocaml -vnum" => Some(CommandOutput {
+            stdout: String::from("4.10.0\n"),
+            stderr: String::default(),
+            exit_code: Some(0),
+        }),
+        "opam switch show" => Some(CommandOutput {
+            stdout: String::from("default\n"),
+            stderr: String::default(),
+            exit_code: Some(0),
         }),
         "php -r echo PHP_MAJOR_VERSION.'.'.PHP_MINOR_VERSION.'.'.PHP_RELEASE_VERSION;" => {
             Some(CommandOutput {
                 stdout: String::from("7.3.8"),
                 stderr: String::default(),
+                exit_code: Some(0),
             })
         }
+        "raku --version" => Some(CommandOutput {
+            stdout: String::from(
+                "\
+Welcome to Rakudo™ v2022.04.
+Implementing the Raku® Programming Language v6.d.
+Built on MoarVM version 2022.04.\n",
+            ),
+            stderr: String::default(),
+            exit_code: Some(0),
+        }),
         "ruby -v" => Some(CommandOutput {
             stdout: String::from("ruby 2.5.1p57 (2018-03-29 revision 63029) [x86_64-linux-gnu]"),
             stderr: String::default(),
+            exit_code: Some(0),
         }),
         "stack ghc -- --numeric-version --no-install-ghc" => Some(CommandOutput {
             stdout: String::from("8.6.5"),
             stderr: String::default(),
+            exit_code: Some(0),
         }),
         "elixir --version" => Some(CommandOutput {
             stdout: String::from(
@@ -85,17 +198,143 @@ Erlang/OTP 22 [erts-10.6.4] [source] [64-bit] [smp:8:8] [ds:8:8:10] [async-threa
 Elixir 1.10 (compiled with Erlang/OTP 22)",
             ),
             stderr: String::default(),
+            exit_code: Some(0),
         }),
-        // If we don't have a mocked command fall back to executing the command
-        _ => internal_exec_cmd(&cmd, &args),
+        // If we don't have a mocked command the caller falls back to executing the command
+        _ => None,
+    }
+}
+
+/// Renders a byte count in the largest unit it reaches, with at most one decimal,
+/// e.g. `1.5 GiB`. Binary units are powers of 1024, decimal units powers of 1000.
+pub fn format_bytes(bytes: f64, binary: bool) -> String {
+    format_bytes_with_precision(bytes, binary, 1)
+}
+
+/// Like `format_bytes`, with at most `precision` decimals, e.g. `2 GiB` for none
+pub fn format_bytes_with_precision(bytes: f64, binary: bool, precision: usize) -> String {
+    const BINARY_UNITS: &[&str] = &["B", "KiB", "MiB", "GiB", "TiB", "PiB", "EiB"];
+    const DECIMAL_UNITS: &[&str] = &["B", "kB", "MB", "GB", "TB", "PB", "EB"];
+
+    let (base, units) = if binary {
+        (1024.0, BINARY_UNITS)
+    } else {
+        (1000.0, DECIMAL_UNITS)
+    };
+
+    let mut value = bytes;
+    let mut unit = 0;
+    while value.abs() >= base && unit < units.len() - 1 {
+        value /= base;
+        unit += 1;
     }
+
+    let rendered = format!("{:.*}", precision, value);
+    let rendered = if rendered.contains('.') {
+        rendered.trim_end_matches('0').trim_end_matches('.')
+    } else {
+        &rendered
+    };
+    format!("{} {}", rendered, units[unit])
 }
 
 /// Wraps ANSI color escape sequences in the shell-appropriate wrappers.
+///
+/// OSC sequences (`ESC ]`), such as the ones setting the terminal title, are
+/// wrapped up to their BEL terminator instead of the next `m`.
 pub fn wrap_colorseq_for_shell(ansi: String, shell: Shell) -> String {
     const ESCAPE_BEGIN: char = '\u{1b}';
     const ESCAPE_END: char = 'm';
-    wrap_seq_for_shell(ansi, shell, ESCAPE_BEGIN, ESCAPE_END)
+    const OSC_BEGIN: &str = "\u{1b}]";
+    const OSC_END: char = '\u{7}';
+
+    if !ansi.contains(OSC_BEGIN) {
+        return wrap_seq_for_shell(ansi, shell, ESCAPE_BEGIN, ESCAPE_END);
+    }
+
+    // Split the string right after each OSC terminator, so that every chunk holds
+    // at most one OSC sequence, at its end
+    let mut chunks = Vec::new();
+    let mut start = 0;
+    for (index, _) in ansi.match_indices(OSC_END) {
+        chunks.push(&ansi[start..=index]);
+        start = index + 1;
+    }
+    chunks.push(&ansi[start..]);
+
+    let mut result = String::with_capacity(ansi.len());
+    for chunk in chunks {
+        match chunk.find(OSC_BEGIN) {
+            Some(index) if chunk.ends_with(OSC_END) => {
+                let (colors, osc) = chunk.split_at(index);
+                result.push_str(&wrap_seq_for_shell(
+                    colors.to_string(),
+                    shell.clone(),
+                    ESCAPE_BEGIN,
+                    ESCAPE_END,
+                ));
+                result.push_str(&wrap_seq_for_shell(
+                    osc.to_string(),
+                    shell.clone(),
+                    ESCAPE_BEGIN,
+                    OSC_END,
+                ));
+            }
+            _ => result.push_str(&wrap_seq_for_shell(
+                chunk.to_string(),
+                shell.clone(),
+                ESCAPE_BEGIN,
+                ESCAPE_END,
+            )),
+        }
+    }
+    result
+}
+
+/// Compare two dotted versions such as `v1.10` and `1.9` component by component, so
+/// that modules can tell whether a version is older than a required minimum.
+///
+/// The numbers leading each component are compared numerically and anything after
+/// them byte-wise, independently of the locale, e.g. `1.10 > 1.9` and `1.2rc1 < 1.2rc2`.
+/// Missing components count as `0`, so `1.2` equals `1.2.0`.
+pub fn compare_versions(a: &str, b: &str) -> Ordering {
+    let mut a_components = version_components(a);
+    let mut b_components = version_components(b);
+
+    loop {
+        let ordering = match (a_components.next(), b_components.next()) {
+            (None, None) => return Ordering::Equal,
+            (a, b) => compare_components(a.unwrap_or("0"), b.unwrap_or("0")),
+        };
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+    }
+}
+
+fn version_components(version: &str) -> impl Iterator<Item = &str> {
+    let version = version.trim();
+    let version = if version.starts_with('v') {
+        &version[1..]
+    } else {
+        version
+    };
+    version.split('.')
+}
+
+fn compare_components(a: &str, b: &str) -> Ordering {
+    let (a_number, a_rest) = split_leading_number(a);
+    let (b_number, b_rest) = split_leading_number(b);
+
+    match (a_number.parse::<u64>(), b_number.parse::<u64>()) {
+        (Ok(a_number), Ok(b_number)) => a_number.cmp(&b_number).then_with(|| a_rest.cmp(b_rest)),
+        _ => a.cmp(b),
+    }
+}
+
+fn split_leading_number(component: &str) -> (&str, &str) {
+    let digits = component.bytes().take_while(u8::is_ascii_digit).count();
+    component.split_at(digits)
 }
 
 /// Many shells cannot deal with raw unprintable characters and miscompute the cursor position,
@@ -140,9 +379,17 @@ pub fn wrap_seq_for_shell(
     final_string
 }
 
-fn internal_exec_cmd(cmd: &str, args: &[&str]) -> Option<CommandOutput> {
+fn internal_exec_cmd(
+    cmd: &str,
+    args: &[&str],
+    time_limit: Option<Duration>,
+) -> Option<CommandOutput> {
     log::trace!("Executing command {:?} with args {:?}", cmd, args);
-    match Command::new(cmd).args(args).output() {
+    let output = match time_limit {
+        Some(time_limit) => output_with_timeout(Command::new(cmd).args(args), time_limit),
+        None => Command::new(cmd).args(args).output(),
+    };
+    match output {
         Ok(output) => {
             let stdout_string = String::from_utf8(output.stdout).unwrap();
             let stderr_string = String::from_utf8(output.stderr).unwrap();
@@ -158,6 +405,7 @@ fn internal_exec_cmd(cmd: &str, args: &[&str]) -> Option<CommandOutput> {
             Some(CommandOutput {
                 stdout: stdout_string,
                 stderr: stderr_string,
+                exit_code: output.status.code(),
             })
         }
         Err(error) => {
@@ -167,17 +415,115 @@ fn internal_exec_cmd(cmd: &str, args: &[&str]) -> Option<CommandOutput> {
     }
 }
 
+/// Run a command to completion like `Command::output`, killing it if it runs longer
+/// than `time_limit`
+fn output_with_timeout(command: &mut Command, time_limit: Duration) -> io::Result<Output> {
+    let start = Instant::now();
+    let mut child = command
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+
+    // Drain the pipes while waiting, so that a chatty command can't block on a full pipe
+    let stdout_reader = child.stdout.take().map(spawn_pipe_reader);
+    let stderr_reader = child.stderr.take().map(spawn_pipe_reader);
+
+    let status = loop {
+        if let Some(status) = child.try_wait()? {
+            break status;
+        }
+        if start.elapsed() >= time_limit {
+            log::warn!(
+                "Executing command {:?} timed out after {:?}",
+                command,
+                time_limit
+            );
+            child.kill().ok();
+            child.wait().ok();
+            return Err(io::Error::new(io::ErrorKind::TimedOut, "command timed out"));
+        }
+        thread::sleep(Duration::from_millis(1));
+    };
+
+    let join_pipe_reader = |reader: Option<thread::JoinHandle<Vec<u8>>>| {
+        reader
+            .and_then(|reader| reader.join().ok())
+            .unwrap_or_default()
+    };
+
+    Ok(Output {
+        status,
+        stdout: join_pipe_reader(stdout_reader),
+        stderr: join_pipe_reader(stderr_reader),
+    })
+}
+
+fn spawn_pipe_reader<R: Read + Send + 'static>(mut pipe: R) -> thread::JoinHandle<Vec<u8>> {
+    thread::spawn(move || {
+        let mut buffer = Vec::new();
+        pipe.read_to_end(&mut buffer).ok();
+        buffer
+    })
+}
+
 #[cfg(test)]
 #[cfg(not(windows))] // While the exec_cmd should work on Windows these tests assume a Unix-like environment.
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_format_bytes_binary() {
+        assert_eq!(format_bytes(1023.0, true), "1023 B");
+        assert_eq!(format_bytes(1024.0, true), "1 KiB");
+        assert_eq!(format_bytes(1536.0, true), "1.5 KiB");
+        assert_eq!(
+            format_bytes(1.5 * 1024.0 * 1024.0 * 1024.0, true),
+            "1.5 GiB"
+        );
+    }
+
+    #[test]
+    fn test_format_bytes_with_precision() {
+        let gib = 1024.0 * 1024.0 * 1024.0;
+        assert_eq!(format_bytes_with_precision(7.5 * gib, true, 0), "8 GiB");
+        assert_eq!(format_bytes_with_precision(7.25 * gib, true, 2), "7.25 GiB");
+        assert_eq!(format_bytes_with_precision(7.0 * gib, true, 2), "7 GiB");
+        assert_eq!(format_bytes_with_precision(1000.0, true, 0), "1000 B");
+    }
+
+    #[test]
+    fn test_format_bytes_decimal() {
+        assert_eq!(format_bytes(1023.0, false), "1 kB");
+        assert_eq!(format_bytes(1024.0, false), "1 kB");
+        assert_eq!(format_bytes(1536.0, false), "1.5 kB");
+        assert_eq!(format_bytes(999.0, false), "999 B");
+    }
+
+    #[test]
+    fn test_compare_versions() {
+        assert_eq!(compare_versions("1.10", "1.9"), Ordering::Greater);
+        assert_eq!(compare_versions("v1.9.1", "1.10"), Ordering::Less);
+        assert_eq!(compare_versions("v1.2.3", "1.2.3"), Ordering::Equal);
+        assert_eq!(compare_versions("1.2", "1.2.0"), Ordering::Equal);
+        assert_eq!(compare_versions("1.2.1", "1.2"), Ordering::Greater);
+    }
+
+    #[test]
+    fn test_compare_versions_mixed_components() {
+        assert_eq!(compare_versions("1.2rc1", "1.2rc2"), Ordering::Less);
+        assert_eq!(compare_versions("1.10rc1", "1.9"), Ordering::Greater);
+        assert_eq!(compare_versions("1.x", "1.y"), Ordering::Less);
+        assert_eq!(compare_versions("1.x", "1.2"), Ordering::Greater);
+    }
+
     #[test]
     fn exec_mocked_command() {
-        let result = exec_cmd("dummy_command", &[]);
+        let result = exec_cmd_with_timeout("dummy_command", &[], Duration::from_secs(10));
         let expected = Some(CommandOutput {
             stdout: String::from("stdout ok!"),
             stderr: String::from("stderr ok!"),
+            exit_code: Some(0),
         });
 
         assert_eq!(result, expected)
@@ -185,10 +531,11 @@ mod tests {
 
     #[test]
     fn exec_no_output() {
-        let result = internal_exec_cmd("true", &[]);
+        let result = internal_exec_cmd("true", &[], None);
         let expected = Some(CommandOutput {
             stdout: String::from(""),
             stderr: String::from(""),
+            exit_code: Some(0),
         });
 
         assert_eq!(result, expected)
@@ -196,10 +543,11 @@ mod tests {
 
     #[test]
     fn exec_with_output_stdout() {
-        let result = internal_exec_cmd("/bin/sh", &["-c", "echo hello"]);
+        let result = internal_exec_cmd("/bin/sh", &["-c", "echo hello"], None);
         let expected = Some(CommandOutput {
             stdout: String::from("hello\n"),
             stderr: String::from(""),
+            exit_code: Some(0),
         });
 
         assert_eq!(result, expected)
@@ -207,10 +555,11 @@ mod tests {
 
     #[test]
     fn exec_with_output_stderr() {
-        let result = internal_exec_cmd("/bin/sh", &["-c", "echo hello >&2"]);
+        let result = internal_exec_cmd("/bin/sh", &["-c", "echo hello >&2"], None);
         let expected = Some(CommandOutput {
             stdout: String::from(""),
             stderr: String::from("hello\n"),
+            exit_code: Some(0),
         });
 
         assert_eq!(result, expected)
@@ -218,18 +567,81 @@ mod tests {
 
     #[test]
     fn exec_with_output_both() {
-        let result = internal_exec_cmd("/bin/sh", &["-c", "echo hello; echo world >&2"]);
+        let result = internal_exec_cmd("/bin/sh", &["-c", "echo hello; echo world >&2"], None);
         let expected = Some(CommandOutput {
             stdout: String::from("hello\n"),
             stderr: String::from("world\n"),
+            exit_code: Some(0),
         });
 
         assert_eq!(result, expected)
     }
 
+    #[test]
+    fn exec_full_with_output_stderr_and_non_zero_exit_code() {
+        let result = exec_cmd_full_with_timeout(
+            "/bin/sh",
+            &["-c", "echo version 1.2.3 >&2; exit 1"],
+            Duration::from_secs(10),
+        )
+        .unwrap();
+
+        assert!(!result.status.success());
+        assert_eq!(result.stdout, b"");
+        assert_eq!(result.stderr, b"version 1.2.3\n");
+    }
+
+    #[test]
+    fn exec_full_with_non_zero_exit_code_captured() {
+        let result = exec_cmd_full_with_timeout(
+            "/bin/sh",
+            &["-c", "echo invalid >&2; exit 3"],
+            Duration::from_secs(10),
+        )
+        .map(CommandOutput::from);
+        let expected = Some(CommandOutput {
+            stdout: String::from(""),
+            stderr: String::from("invalid\n"),
+            exit_code: Some(3),
+        });
+
+        assert_eq!(result, expected)
+    }
+
+    #[test]
+    fn exec_full_with_missing_command() {
+        let result =
+            exec_cmd_full_with_timeout("starship_missing_command", &[], Duration::from_secs(10));
+        assert!(result.is_none());
+    }
+
     #[test]
     fn exec_with_non_zero_exit_code() {
-        let result = internal_exec_cmd("false", &[]);
+        let result = internal_exec_cmd("false", &[], None);
+        let expected = None;
+
+        assert_eq!(result, expected)
+    }
+
+    #[test]
+    fn exec_with_output_within_time_limit() {
+        let result = internal_exec_cmd(
+            "/bin/sh",
+            &["-c", "echo hello; echo world >&2"],
+            Some(Duration::from_secs(5)),
+        );
+        let expected = Some(CommandOutput {
+            stdout: String::from("hello\n"),
+            stderr: String::from("world\n"),
+            exit_code: Some(0),
+        });
+
+        assert_eq!(result, expected)
+    }
+
+    #[test]
+    fn exec_exceeding_time_limit() {
+        let result = internal_exec_cmd("sleep", &["1"], Some(Duration::from_millis(1)));
         let expected = None;
 
         assert_eq!(result, expected)
@@ -272,4 +684,23 @@ mod tests {
         assert_eq!(&bresult4, "herpaderp");
         assert_eq!(&bresult5, "");
     }
+
+    #[test]
+    fn test_osc_sequence_wrappers() {
+        let test0 = "\x1b]0;my rocket\x07";
+        let test1 = "\x1b[31mred\x1b]2;title\x07\x1b[0m";
+
+        assert_eq!(
+            wrap_colorseq_for_shell(test0.to_string(), Shell::Zsh),
+            "%{\x1b]0;my rocket\x07%}"
+        );
+        assert_eq!(
+            wrap_colorseq_for_shell(test1.to_string(), Shell::Bash),
+            "\\[\x1b[31m\\]red\\[\x1b]2;title\x07\\]\\[\x1b[0m\\]"
+        );
+        assert_eq!(
+            wrap_colorseq_for_shell(test0.to_string(), Shell::Fish),
+            test0
+        );
+    }
 }
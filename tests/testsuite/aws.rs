@@ -43,6 +43,156 @@ fn region_set_with_alias() -> io::Result<()> {
     Ok(())
 }
 
+#[test]
+fn region_set_with_style() -> io::Result<()> {
+    let output = common::render_module("aws")
+        .env("AWS_REGION", "us-east-1")
+        .use_config(toml::toml! {
+            [aws.region_styles]
+            us-east-1 = "bold red"
+        })
+        .output()?;
+    let expected = format!("on {} ", Color::Red.bold().paint("☁️  us-east-1"));
+    let actual = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(expected, actual);
+    Ok(())
+}
+
+#[test]
+fn region_set_with_style_for_alias() -> io::Result<()> {
+    let output = common::render_module("aws")
+        .env("AWS_REGION", "ap-southeast-2")
+        .use_config(toml::toml! {
+            [aws.region_aliases]
+            ap-southeast-2 = "au"
+            [aws.region_styles]
+            au = "green"
+        })
+        .output()?;
+    let expected = format!("on {} ", Color::Green.paint("☁️  au"));
+    let actual = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(expected, actual);
+    Ok(())
+}
+
+#[test]
+fn account_set() -> io::Result<()> {
+    let output = common::render_module("aws")
+        .env("AWS_REGION", "ap-northeast-2")
+        .env("AWS_ACCOUNT_ID", "123456789012")
+        .use_config(toml::toml! {
+            [aws]
+            show_account = true
+        })
+        .output()?;
+    let expected = format!(
+        "on {} ",
+        Color::Yellow
+            .bold()
+            .paint("☁️  ap-northeast-2 [123456789012]")
+    );
+    let actual = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(expected, actual);
+    Ok(())
+}
+
+#[test]
+fn account_from_sso_profile() -> io::Result<()> {
+    let dir = tempfile::tempdir()?;
+    let config_path = dir.path().join("config");
+    let mut file = File::create(&config_path)?;
+    file.write_all(
+        "[profile astronauts]
+sso_account_id = 123456789012
+
+[profile cosmonauts]
+sso_account_id = 210987654321
+"
+        .as_bytes(),
+    )?;
+    file.sync_all()?;
+
+    let output = common::render_module("aws")
+        .env("AWS_CONFIG_FILE", config_path.to_string_lossy().as_ref())
+        .env("AWS_PROFILE", "cosmonauts")
+        .env("AWS_REGION", "ap-northeast-2")
+        .env("HOME", dir.path())
+        .use_config(toml::toml! {
+            [aws]
+            show_account = true
+        })
+        .output()?;
+    let expected = format!(
+        "on {} ",
+        Color::Yellow
+            .bold()
+            .paint("☁️  cosmonauts(ap-northeast-2) [210987654321]")
+    );
+    let actual = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(expected, actual);
+    dir.close()
+}
+
+#[test]
+fn account_unavailable() -> io::Result<()> {
+    let dir = tempfile::tempdir()?;
+    let output = common::render_module("aws")
+        .env("AWS_REGION", "ap-northeast-2")
+        .env("HOME", dir.path())
+        .use_config(toml::toml! {
+            [aws]
+            show_account = true
+        })
+        .output()?;
+    let expected = format!("on {} ", Color::Yellow.bold().paint("☁️  ap-northeast-2"));
+    let actual = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(expected, actual);
+    dir.close()
+}
+
+#[test]
+fn vault_backend_set() -> io::Result<()> {
+    let output = common::render_module("aws")
+        .env("AWS_VAULT", "astronauts-vault")
+        .env("AWS_VAULT_BACKEND", "keychain")
+        .env("AWS_REGION", "ap-northeast-2")
+        .use_config(toml::toml! {
+            [aws]
+            show_vault_backend = true
+        })
+        .output()?;
+    let expected = format!(
+        "on {} ",
+        Color::Yellow
+            .bold()
+            .paint("☁️  astronauts-vault(ap-northeast-2) [keychain]")
+    );
+    let actual = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(expected, actual);
+    Ok(())
+}
+
+#[test]
+fn vault_backend_unset() -> io::Result<()> {
+    let output = common::render_module("aws")
+        .env("AWS_VAULT", "astronauts-vault")
+        .env("AWS_REGION", "ap-northeast-2")
+        .use_config(toml::toml! {
+            [aws]
+            show_vault_backend = true
+        })
+        .output()?;
+    let expected = format!(
+        "on {} ",
+        Color::Yellow
+            .bold()
+            .paint("☁️  astronauts-vault(ap-northeast-2)")
+    );
+    let actual = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(expected, actual);
+    Ok(())
+}
+
 #[test]
 fn default_region_set() -> io::Result<()> {
     let output = common::render_module("aws")
@@ -150,6 +300,57 @@ region = us-east-2
     dir.close()
 }
 
+#[test]
+fn profile_config_and_region_set() -> io::Result<()> {
+    test_region_precedence(false, "astronauts(ap-northeast-2)")
+}
+
+#[test]
+fn profile_config_and_region_set_with_prefer_config_region() -> io::Result<()> {
+    test_region_precedence(true, "astronauts(us-east-2)")
+}
+
+fn test_region_precedence(prefer_config_region: bool, expected_segment: &str) -> io::Result<()> {
+    let dir = tempfile::tempdir()?;
+    let config_path = dir.path().join("config");
+    let mut file = File::create(&config_path)?;
+
+    file.write_all(
+        "[default]
+region = us-east-1
+
+[profile astronauts]
+region = us-east-2
+"
+        .as_bytes(),
+    )?;
+
+    let output = common::render_module("aws")
+        .env("AWS_CONFIG_FILE", config_path.to_string_lossy().as_ref())
+        .env("AWS_PROFILE", "astronauts")
+        .env("AWS_REGION", "ap-northeast-2")
+        .use_config(
+            toml::from_str(&format!(
+                "
+                    [aws]
+                        prefer_config_region = {}
+                ",
+                prefer_config_region
+            ))
+            .unwrap(),
+        )
+        .output()?;
+    let expected = format!(
+        "on {} ",
+        Color::Yellow
+            .bold()
+            .paint(format!("☁️  {}", expected_segment))
+    );
+    let actual = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(expected, actual);
+    dir.close()
+}
+
 #[test]
 fn profile_and_region_set_with_display_all() -> io::Result<()> {
     let output = common::render_module("aws")
@@ -199,6 +400,299 @@ fn region_set_with_display_all() -> io::Result<()> {
     Ok(())
 }
 
+#[test]
+fn profile_and_region_set_with_separator() -> io::Result<()> {
+    let output = common::render_module("aws")
+        .env("AWS_PROFILE", "astronauts")
+        .env("AWS_REGION", "ap-northeast-1")
+        .use_config(toml::toml! {
+            [aws]
+            separator = " @ "
+        })
+        .output()?;
+    let expected = format!(
+        "on {} ",
+        Color::Yellow
+            .bold()
+            .paint("☁️  astronauts @ ap-northeast-1")
+    );
+    let actual = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(expected, actual);
+    Ok(())
+}
+
+#[test]
+fn profile_set_with_separator() -> io::Result<()> {
+    let output = common::render_module("aws")
+        .env("AWS_PROFILE", "astronauts")
+        .use_config(toml::toml! {
+            [aws]
+            separator = " @ "
+        })
+        .output()?;
+    let expected = format!("on {} ", Color::Yellow.bold().paint("☁️  astronauts"));
+    let actual = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(expected, actual);
+    Ok(())
+}
+
+#[test]
+fn region_set_with_separator() -> io::Result<()> {
+    let output = common::render_module("aws")
+        .env("AWS_REGION", "ap-northeast-1")
+        .use_config(toml::toml! {
+            [aws]
+            separator = " @ "
+        })
+        .output()?;
+    let expected = format!("on {} ", Color::Yellow.bold().paint("☁️  ap-northeast-1"));
+    let actual = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(expected, actual);
+    Ok(())
+}
+
+#[test]
+fn profile_named_after_region_shown_once() -> io::Result<()> {
+    let output = common::render_module("aws")
+        .env("AWS_PROFILE", "us-east-1")
+        .env("AWS_REGION", "us-east-1")
+        .output()?;
+    let expected = format!("on {} ", Color::Yellow.bold().paint("☁️  us-east-1"));
+    let actual = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(expected, actual);
+    Ok(())
+}
+
+#[test]
+fn symbol_before_each() -> io::Result<()> {
+    let output = common::render_module("aws")
+        .env("AWS_PROFILE", "astronauts")
+        .env("AWS_REGION", "us-east-1")
+        .use_config(toml::toml! {
+            [aws]
+            symbol_before_each = true
+        })
+        .output()?;
+    let expected = format!(
+        "on {} ",
+        Color::Yellow.bold().paint("☁️  astronauts ☁️  us-east-1")
+    );
+    let actual = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(expected, actual);
+    Ok(())
+}
+
+#[test]
+fn symbol_before_each_shown_once_for_duplicates() -> io::Result<()> {
+    let output = common::render_module("aws")
+        .env("AWS_PROFILE", "va")
+        .env("AWS_REGION", "us-east-1")
+        .use_config(toml::toml! {
+            [aws]
+            symbol_before_each = true
+            [aws.region_aliases]
+            us-east-1 = "va"
+        })
+        .output()?;
+    let expected = format!("on {} ", Color::Yellow.bold().paint("☁️  va"));
+    let actual = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(expected, actual);
+    Ok(())
+}
+
+#[test]
+fn profile_account_suffix_stripped() -> io::Result<()> {
+    test_strip_account_suffix("AdministratorAccess-123456789012", "AdministratorAccess")
+}
+
+#[test]
+fn profile_short_account_suffix_kept() -> io::Result<()> {
+    test_strip_account_suffix("Developer-12345678901", "Developer-12345678901")
+}
+
+#[test]
+fn profile_long_account_suffix_kept() -> io::Result<()> {
+    test_strip_account_suffix("Developer-1234567890123", "Developer-1234567890123")
+}
+
+fn test_strip_account_suffix(profile: &str, expected_profile: &str) -> io::Result<()> {
+    let output = common::render_module("aws")
+        .env("AWS_PROFILE", profile)
+        .use_config(toml::toml! {
+            [aws]
+            displayed_items = "profile"
+            strip_account_suffix = true
+        })
+        .output()?;
+    let expected = format!(
+        "on {} ",
+        Color::Yellow
+            .bold()
+            .paint(format!("☁️  {}", expected_profile))
+    );
+    let actual = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(expected, actual);
+    Ok(())
+}
+
+#[test]
+fn multiple_profiles_show_primary() -> io::Result<()> {
+    let output = common::render_module("aws")
+        .env("AWS_PROFILES", "base,admin")
+        .output()?;
+    let expected = format!("on {} ", Color::Yellow.bold().paint("☁️  base"));
+    let actual = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(expected, actual);
+    Ok(())
+}
+
+#[test]
+fn multiple_profiles_show_all() -> io::Result<()> {
+    let output = common::render_module("aws")
+        .env("AWS_PROFILE", "base, admin")
+        .use_config(toml::toml! {
+            [aws]
+            show_all_profiles = true
+            profile_separator = " > "
+        })
+        .output()?;
+    let expected = format!("on {} ", Color::Yellow.bold().paint("☁️  base > admin"));
+    let actual = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(expected, actual);
+    Ok(())
+}
+
+#[test]
+fn multiple_profiles_show_all_profile_only() -> io::Result<()> {
+    let output = common::render_module("aws")
+        .env("AWS_PROFILE", "base,admin")
+        .env("AWS_REGION", "ap-northeast-2")
+        .use_config(toml::toml! {
+            [aws]
+            displayed_items = "profile"
+            show_all_profiles = true
+        })
+        .output()?;
+    let expected = format!("on {} ", Color::Yellow.bold().paint("☁️  base, admin"));
+    let actual = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(expected, actual);
+    Ok(())
+}
+
+#[test]
+fn no_color_renders_plain_text() -> io::Result<()> {
+    let output = common::render_module("aws")
+        .env("AWS_PROFILE", "astronauts")
+        .env("NO_COLOR", "1")
+        .output()?;
+    let expected = "on ☁️  astronauts ";
+    let actual = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(expected, actual);
+    Ok(())
+}
+
+#[test]
+fn force_color_overrides_no_color() -> io::Result<()> {
+    let output = common::render_module("aws")
+        .env("AWS_PROFILE", "astronauts")
+        .env("NO_COLOR", "1")
+        .env("STARSHIP_FORCE_COLOR", "1")
+        .output()?;
+    let expected = format!("on {} ", Color::Yellow.bold().paint("☁️  astronauts"));
+    let actual = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(expected, actual);
+    Ok(())
+}
+
+#[test]
+fn expired_credentials() -> io::Result<()> {
+    let output = common::render_module("aws")
+        .env("AWS_PROFILE", "astronauts")
+        .env("AWS_SESSION_EXPIRATION", "2000-01-01T00:00:00Z")
+        .output()?;
+    let expected = format!("on {} ", Color::Red.bold().paint("⌛ astronauts"));
+    let actual = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(expected, actual);
+    Ok(())
+}
+
+#[test]
+fn valid_credentials() -> io::Result<()> {
+    let output = common::render_module("aws")
+        .env("AWS_PROFILE", "astronauts")
+        .env("AWS_CREDENTIAL_EXPIRATION", "2999-01-01T00:00:00+01:00")
+        .output()?;
+    let expected = format!("on {} ", Color::Yellow.bold().paint("☁️  astronauts"));
+    let actual = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(expected, actual);
+    Ok(())
+}
+
+#[test]
+fn unparseable_credentials_expiration() -> io::Result<()> {
+    let output = common::render_module("aws")
+        .env("AWS_PROFILE", "astronauts")
+        .env("AWS_SESSION_EXPIRATION", "yesterday")
+        .output()?;
+    let expected = format!("on {} ", Color::Yellow.bold().paint("☁️  astronauts"));
+    let actual = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(expected, actual);
+    Ok(())
+}
+
+#[test]
+fn home_region_hidden() -> io::Result<()> {
+    let output = common::render_module("aws")
+        .env("AWS_PROFILE", "astronauts")
+        .env("AWS_REGION", "us-east-1")
+        .use_config(toml::toml! {
+            [aws]
+            home_region = "us-east-1"
+        })
+        .output()?;
+    let expected = format!("on {} ", Color::Yellow.bold().paint("☁️  astronauts"));
+    let actual = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(expected, actual);
+    Ok(())
+}
+
+#[test]
+fn home_region_other_region_shown() -> io::Result<()> {
+    let output = common::render_module("aws")
+        .env("AWS_PROFILE", "astronauts")
+        .env("AWS_REGION", "eu-west-1")
+        .use_config(toml::toml! {
+            [aws]
+            home_region = "us-east-1"
+        })
+        .output()?;
+    let expected = format!(
+        "on {} ",
+        Color::Yellow.bold().paint("☁️  astronauts(eu-west-1)")
+    );
+    let actual = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(expected, actual);
+    Ok(())
+}
+
+#[test]
+fn home_region_matched_by_alias() -> io::Result<()> {
+    let output = common::render_module("aws")
+        .env("AWS_REGION", "us-east-1")
+        .use_config(toml::toml! {
+            [aws]
+            displayed_items = "region"
+            home_region = "va"
+            [aws.region_aliases]
+            us-east-1 = "va"
+        })
+        .output()?;
+    let expected = "";
+    let actual = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(expected, actual);
+    Ok(())
+}
+
 #[test]
 fn profile_and_region_set_with_display_region() -> io::Result<()> {
     let output = common::render_module("aws")
@@ -260,3 +754,100 @@ fn region_not_set_with_display_region() -> io::Result<()> {
     assert_eq!(expected, actual);
     Ok(())
 }
+
+#[test]
+fn detect_extensions_match() -> io::Result<()> {
+    let dir = tempfile::tempdir()?;
+    File::create(dir.path().join("main.tf"))?.sync_all()?;
+
+    let output = common::render_module("aws")
+        .env("AWS_PROFILE", "astronauts")
+        .use_config(toml::toml! {
+            [aws]
+            detect_extensions = ["tf"]
+        })
+        .arg("--path")
+        .arg(dir.path())
+        .output()?;
+    let expected = format!("on {} ", Color::Yellow.bold().paint("☁️  astronauts"));
+    let actual = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(expected, actual);
+    dir.close()
+}
+
+#[test]
+fn detect_extensions_no_match() -> io::Result<()> {
+    let dir = tempfile::tempdir()?;
+
+    let output = common::render_module("aws")
+        .env("AWS_PROFILE", "astronauts")
+        .use_config(toml::toml! {
+            [aws]
+            detect_extensions = ["tf"]
+        })
+        .arg("--path")
+        .arg(dir.path())
+        .output()?;
+    let expected = "";
+    let actual = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(expected, actual);
+    dir.close()
+}
+
+#[test]
+fn profile_count_without_active_profile() -> io::Result<()> {
+    let dir = tempfile::tempdir()?;
+    let config_path = dir.path().join("config");
+    let mut file = File::create(&config_path)?;
+
+    file.write_all(
+        "[default]
+output = json
+
+[profile astronauts]
+region = us-east-2
+
+[profile cosmonauts]
+region = eu-central-1
+"
+        .as_bytes(),
+    )?;
+
+    let output = common::render_module("aws")
+        .env("AWS_CONFIG_FILE", config_path.to_string_lossy().as_ref())
+        .use_config(toml::toml! {
+            [aws]
+            show_profile_count = true
+        })
+        .output()?;
+    let expected = format!("on {} ", Color::Yellow.bold().paint("☁️  3 profiles"));
+    let actual = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(expected, actual);
+    dir.close()
+}
+
+#[test]
+fn profile_count_hidden_by_default() -> io::Result<()> {
+    let dir = tempfile::tempdir()?;
+    let config_path = dir.path().join("config");
+    let mut file = File::create(&config_path)?;
+
+    file.write_all(
+        "[default]
+output = json
+
+[profile astronauts]
+
+[profile cosmonauts]
+"
+        .as_bytes(),
+    )?;
+
+    let output = common::render_module("aws")
+        .env("AWS_CONFIG_FILE", config_path.to_string_lossy().as_ref())
+        .output()?;
+    let expected = "";
+    let actual = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(expected, actual);
+    dir.close()
+}
@@ -0,0 +1,57 @@
+use ansi_term::Color;
+use std::fs;
+use std::io;
+
+use crate::common;
+
+#[test]
+fn shows_environment() -> io::Result<()> {
+    let dir = tempfile::tempdir()?;
+    fs::write(dir.path().join("wrangler.toml"), "name = \"my-worker\"\n")?;
+
+    let output = common::render_module("cloudflare")
+        .env("CLOUDFLARE_ENV", "staging")
+        .arg("--path")
+        .arg(dir.path())
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+
+    let expected = format!(
+        "on {} ",
+        Color::Fixed(208).bold().paint("⛅️ my-worker (staging)")
+    );
+    assert_eq!(expected, actual);
+    dir.close()
+}
+
+#[test]
+fn shows_environment_without_name() -> io::Result<()> {
+    let dir = tempfile::tempdir()?;
+    fs::write(dir.path().join("wrangler.jsonc"), "{}")?;
+
+    let output = common::render_module("cloudflare")
+        .env("CLOUDFLARE_ENV", "production")
+        .arg("--path")
+        .arg(dir.path())
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+
+    let expected = format!("on {} ", Color::Fixed(208).bold().paint("⛅️ (production)"));
+    assert_eq!(expected, actual);
+    dir.close()
+}
+
+#[test]
+fn no_manifest() -> io::Result<()> {
+    let dir = tempfile::tempdir()?;
+
+    let output = common::render_module("cloudflare")
+        .env("CLOUDFLARE_ENV", "staging")
+        .arg("--path")
+        .arg(dir.path())
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+
+    assert_eq!("", actual);
+    dir.close()
+}
@@ -90,3 +90,21 @@ fn config_5s_duration_prefix_underwent() -> io::Result<()> {
     assert_eq!(expected, actual);
     Ok(())
 }
+
+#[test]
+fn config_unit_seconds_with_precision() -> io::Result<()> {
+    let output = common::render_module("cmd_duration")
+        .use_config(toml::toml! {
+            [cmd_duration]
+            min_time = 0
+            unit = "s"
+            precision = 1
+        })
+        .arg("--cmd-duration=1500")
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+
+    let expected = format!("took {} ", Color::Yellow.bold().paint("1.5s"));
+    assert_eq!(expected, actual);
+    Ok(())
+}
@@ -39,3 +39,21 @@ fn add_newline_configuration() -> io::Result<()> {
 
     Ok(())
 }
+
+#[test]
+fn raw_escape_configuration() -> io::Result<()> {
+    let output = common::render_prompt()
+        .use_config(toml::toml! {
+            add_newline = false
+            prompt_order = ["raw_escape.title", "raw_escape.unlisted", "raw_escape.clear"]
+            [raw_escapes]
+            title = "\u{1b}]0;starship\u{7}"
+            clear = "\u{1b}[2J"
+        })
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+    let expected = "\u{1b}]0;starship\u{7}";
+    assert_eq!(expected, actual);
+
+    Ok(())
+}
@@ -467,3 +467,124 @@ fn git_repo_in_home_directory_truncate_to_repo_true() -> io::Result<()> {
     assert_eq!(expected, actual);
     tmp_dir.close()
 }
+
+#[test]
+#[ignore]
+fn project_name_from_git_repo() -> io::Result<()> {
+    let tmp_dir = TempDir::new_in(dirs::home_dir().unwrap())?;
+    let repo_dir = tmp_dir.path().join("rocket-controls");
+    let dir = repo_dir.join("src/meters");
+    fs::create_dir_all(&dir)?;
+    Repository::init(&repo_dir).unwrap();
+
+    let output = common::render_module("directory")
+        .use_config(toml::toml! {
+            [directory]
+            use_project_name = true
+        })
+        .arg("--path")
+        .arg(dir)
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+
+    let expected = format!("in {} ", Color::Cyan.bold().paint("rocket-controls"));
+    assert_eq!(expected, actual);
+    tmp_dir.close()
+}
+
+#[test]
+#[ignore]
+fn project_name_from_manifest() -> io::Result<()> {
+    let tmp_dir = TempDir::new_in(dirs::home_dir().unwrap())?;
+    let repo_dir = tmp_dir.path().join("rocket-controls");
+    let package_dir = repo_dir.join("packages/fuel-gauge");
+    let dir = package_dir.join("src");
+    fs::create_dir_all(&dir)?;
+    Repository::init(&repo_dir).unwrap();
+    fs::write(
+        package_dir.join("package.json"),
+        r#"{ "name": "@rocket/fuel-gauge" }"#,
+    )?;
+
+    let output = common::render_module("directory")
+        .use_config(toml::toml! {
+            [directory]
+            use_project_name = true
+        })
+        .arg("--path")
+        .arg(dir)
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+
+    let expected = format!("in {} ", Color::Cyan.bold().paint("@rocket/fuel-gauge"));
+    assert_eq!(expected, actual);
+    tmp_dir.close()
+}
+
+#[test]
+fn project_name_falls_back_to_path() -> io::Result<()> {
+    let output = common::render_module("directory")
+        .use_config(toml::toml! {
+            [directory]
+            use_project_name = true
+        })
+        .arg("--path=/")
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+
+    let expected = format!("in {} ", Color::Cyan.bold().paint("/"));
+    assert_eq!(expected, actual);
+    Ok(())
+}
+
+#[test]
+#[cfg(not(target_os = "windows"))]
+fn symlinked_directory_shows_target() -> io::Result<()> {
+    let tmp_dir = TempDir::new()?;
+    let target = tmp_dir.path().join("launchpad");
+    let link = tmp_dir.path().join("pad");
+    fs::create_dir_all(&target)?;
+    std::os::unix::fs::symlink(&target, &link)?;
+
+    let output = common::render_module("directory")
+        .use_config(toml::toml! {
+            [directory]
+            truncation_length = 1
+            show_symlink_target = true
+        })
+        .arg("--path")
+        .arg(&link)
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+
+    let expected = format!(
+        "in {} ",
+        Color::Cyan
+            .bold()
+            .paint(format!("pad -> {}", target.to_str().unwrap()))
+    );
+    assert_eq!(expected, actual);
+    tmp_dir.close()
+}
+
+#[test]
+fn regular_directory_shows_no_symlink_target() -> io::Result<()> {
+    let tmp_dir = TempDir::new()?;
+    let dir = tmp_dir.path().join("launchpad");
+    fs::create_dir_all(&dir)?;
+
+    let output = common::render_module("directory")
+        .use_config(toml::toml! {
+            [directory]
+            truncation_length = 1
+            show_symlink_target = true
+        })
+        .arg("--path")
+        .arg(&dir)
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+
+    let expected = format!("in {} ", Color::Cyan.bold().paint("launchpad"));
+    assert_eq!(expected, actual);
+    tmp_dir.close()
+}
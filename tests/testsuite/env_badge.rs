@@ -0,0 +1,70 @@
+use ansi_term::Color;
+use std::io;
+
+use crate::common::{self, TestCommand};
+
+#[test]
+fn matching_value() -> io::Result<()> {
+    let output = common::render_module("env_badge")
+        .env("APP_ENV", "production")
+        .use_config(toml::toml! {
+            [env_badge]
+            variable = "APP_ENV"
+            rules = [
+                { value = "staging", label = "STAGING" },
+                { value = "production", label = "PROD", style = "bold red" },
+            ]
+        })
+        .output()?;
+    let expected = format!("in {} ", Color::Red.bold().paint("PROD"));
+    let actual = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(expected, actual);
+    Ok(())
+}
+
+#[test]
+fn matching_value_without_style_or_label() -> io::Result<()> {
+    let output = common::render_module("env_badge")
+        .env("APP_ENV", "staging")
+        .use_config(toml::toml! {
+            [env_badge]
+            variable = "APP_ENV"
+            rules = [{ value = "staging" }]
+        })
+        .output()?;
+    let expected = format!("in {} ", Color::White.bold().paint("staging"));
+    let actual = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(expected, actual);
+    Ok(())
+}
+
+#[test]
+fn non_matching_value() -> io::Result<()> {
+    let output = common::render_module("env_badge")
+        .env("APP_ENV", "development")
+        .use_config(toml::toml! {
+            [env_badge]
+            variable = "APP_ENV"
+            rules = [{ value = "production", label = "PROD", style = "bold red" }]
+        })
+        .output()?;
+    let expected = "";
+    let actual = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(expected, actual);
+    Ok(())
+}
+
+#[test]
+fn unset_variable() -> io::Result<()> {
+    let output = common::render_module("env_badge")
+        .use_config(toml::toml! {
+            [env_badge]
+            variable = "APP_ENV"
+            rules = [{ value = "production", label = "PROD" }]
+        })
+        .output()?;
+    let expected = "";
+    let actual = String::from_utf8(output.stdout).unwrap();
+    assert_eq!(expected, actual);
+    Ok(())
+}
@@ -0,0 +1,49 @@
+use std::io;
+
+use crate::common::{self, TestCommand};
+
+#[test]
+fn fill_pads_to_width() -> io::Result<()> {
+    let output = common::render_prompt()
+        .arg("--width=20")
+        .env("NO_COLOR", "1")
+        .use_config(toml::toml! {
+            add_newline = false
+            prompt_order = ["custom.left", "fill", "custom.right", "line_break", "fill", "custom.right"]
+            [custom.left]
+            command = "echo left"
+            when = "true"
+            [custom.right]
+            command = "echo right"
+            when = "true"
+            prefix = "at "
+        })
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+    let expected = "left       at right \n              right ";
+    assert_eq!(expected, actual);
+    assert!(actual.lines().all(|line| line.chars().count() == 20));
+    Ok(())
+}
+
+#[test]
+fn fill_empty_without_room() -> io::Result<()> {
+    let output = common::render_prompt()
+        .arg("--width=8")
+        .env("NO_COLOR", "1")
+        .use_config(toml::toml! {
+            add_newline = false
+            prompt_order = ["custom.left", "fill", "custom.right"]
+            [custom.left]
+            command = "echo left"
+            when = "true"
+            [custom.right]
+            command = "echo right"
+            when = "true"
+        })
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+    let expected = "left via right ";
+    assert_eq!(expected, actual);
+    Ok(())
+}
@@ -20,6 +20,24 @@ fn show_nothing_on_empty_dir() -> io::Result<()> {
     repo_dir.close()
 }
 
+#[test]
+#[ignore]
+fn shows_branch_without_commits() -> io::Result<()> {
+    let repo_dir = tempfile::tempdir()?;
+    let repo = git2::Repository::init(repo_dir.path()).unwrap();
+    repo.set_head("refs/heads/trunk").unwrap();
+
+    let output = common::render_module("git_branch")
+        .arg("--path")
+        .arg(repo_dir.path())
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+
+    let expected = format!("on {} ", Color::Purple.bold().paint("\u{e0a0} trunk"));
+    assert_eq!(expected, actual);
+    repo_dir.close()
+}
+
 #[test]
 fn test_changed_truncation_symbol() -> io::Result<()> {
     test_truncate_length_with_config(
@@ -98,6 +116,97 @@ fn test_japanese_truncation() -> io::Result<()> {
     test_truncate_length("がんばってね", 4, "がんばっ", "…")
 }
 
+#[test]
+fn test_pr_branch() -> io::Result<()> {
+    test_pr_number("pr-123", " #123")
+}
+
+#[test]
+fn test_normal_branch_has_no_pr() -> io::Result<()> {
+    test_pr_number("feature", "")
+}
+
+#[test]
+fn test_branch_with_description() -> io::Result<()> {
+    test_description(Some("Prepare the rocket\n"), true, " (Prepare the rocket)")
+}
+
+#[test]
+fn test_branch_without_description() -> io::Result<()> {
+    test_description(None, true, "")
+}
+
+#[test]
+fn test_branch_description_hidden_by_default() -> io::Result<()> {
+    test_description(Some("Prepare the rocket\n"), false, "")
+}
+
+fn test_description(
+    description: Option<&str>,
+    show_description: bool,
+    expected_description: &str,
+) -> io::Result<()> {
+    let repo_dir = common::create_fixture_repo()?;
+
+    Command::new("git")
+        .args(&["checkout", "-b", "launch"])
+        .current_dir(repo_dir.as_path())
+        .output()?;
+    if let Some(description) = description {
+        Command::new("git")
+            .args(&["config", "branch.launch.description", description])
+            .current_dir(repo_dir.as_path())
+            .output()?;
+    }
+
+    let mut render = common::render_module("git_branch");
+    if show_description {
+        render.use_config(toml::toml! {
+            [git_branch]
+                show_description = true
+        });
+    }
+    let output = render.arg("--path").arg(&repo_dir).output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+
+    let expected = format!(
+        "on {} ",
+        Color::Purple
+            .bold()
+            .paint(format!("\u{e0a0} launch{}", expected_description)),
+    );
+    assert_eq!(expected, actual);
+    remove_dir_all(repo_dir)
+}
+
+fn test_pr_number(branch_name: &str, expected_pr: &str) -> io::Result<()> {
+    let repo_dir = common::create_fixture_repo()?;
+
+    Command::new("git")
+        .args(&["checkout", "-b", branch_name])
+        .current_dir(repo_dir.as_path())
+        .output()?;
+
+    let output = common::render_module("git_branch")
+        .use_config(toml::toml! {
+            [git_branch]
+                show_pr = true
+        })
+        .arg("--path")
+        .arg(&repo_dir)
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+
+    let expected = format!(
+        "on {} ",
+        Color::Purple
+            .bold()
+            .paint(format!("\u{e0a0} {}{}", branch_name, expected_pr)),
+    );
+    assert_eq!(expected, actual);
+    remove_dir_all(repo_dir)
+}
+
 fn test_truncate_length(
     branch_name: &str,
     truncate_length: i64,
@@ -130,3 +130,43 @@ fn test_render_commit_hash_only_detached_on_detached() -> io::Result<()> {
     assert_eq!(expected, actual);
     remove_dir_all(repo_dir)
 }
+
+#[test]
+fn test_render_time_ago() -> io::Result<()> {
+    let repo_dir = common::create_fixture_repo()?;
+
+    // Only the authored time counts, e.g. not the time the commit was rebased
+    let three_days_ago = chrono::Local::now().timestamp() - (3 * 24 + 1) * 60 * 60;
+    Command::new("git")
+        .args(&["commit", "--allow-empty", "-m", "Stale commit"])
+        .env("GIT_AUTHOR_DATE", format!("{} +0000", three_days_ago))
+        .current_dir(repo_dir.as_path())
+        .output()?;
+
+    let mut git_output = Command::new("git")
+        .args(&["rev-parse", "HEAD"])
+        .current_dir(repo_dir.as_path())
+        .output()?
+        .stdout;
+    git_output.truncate(7);
+    let expected_hash = str::from_utf8(&git_output).unwrap();
+
+    let output = common::render_module("git_commit")
+        .use_config(toml::toml! {
+            [git_commit]
+                only_detached = false
+                show_time_ago = true
+        })
+        .arg("--path")
+        .arg(&repo_dir)
+        .output()?;
+
+    let actual = String::from_utf8(output.stdout).unwrap();
+    let expected = Color::Green
+        .bold()
+        .paint(format!("({} 3d1h ago) ", expected_hash))
+        .to_string();
+
+    assert_eq!(expected, actual);
+    remove_dir_all(repo_dir)
+}
@@ -34,6 +34,41 @@ fn show_nothing_on_empty_dir() -> io::Result<()> {
     repo_dir.close()
 }
 
+#[test]
+#[ignore]
+fn shows_no_commits() -> io::Result<()> {
+    let repo_dir = tempfile::tempdir()?;
+    git2::Repository::init(repo_dir.path()).unwrap();
+
+    let output = common::render_module("git_status")
+        .arg("--path")
+        .arg(repo_dir.path())
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+    let expected = Color::Red.bold().paint(format!("[{}] ", "∅")).to_string();
+
+    assert_eq!(expected, actual);
+    repo_dir.close()
+}
+
+#[test]
+#[ignore]
+fn shows_no_commits_with_untracked_file() -> io::Result<()> {
+    let repo_dir = tempfile::tempdir()?;
+    git2::Repository::init(repo_dir.path()).unwrap();
+    File::create(repo_dir.path().join("readme.md"))?.sync_all()?;
+
+    let output = common::render_module("git_status")
+        .arg("--path")
+        .arg(repo_dir.path())
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+    let expected = Color::Red.bold().paint(format!("[{}] ", "∅?")).to_string();
+
+    assert_eq!(expected, actual);
+    repo_dir.close()
+}
+
 #[test]
 #[ignore]
 fn shows_behind() -> io::Result<()> {
@@ -120,6 +155,48 @@ fn shows_ahead_with_count() -> io::Result<()> {
     remove_dir_all(repo_dir)
 }
 
+#[test]
+#[ignore]
+fn doesnt_show_nudge_below_ahead_threshold() -> io::Result<()> {
+    test_ahead_threshold(2, "⇡")
+}
+
+#[test]
+#[ignore]
+fn shows_nudge_at_ahead_threshold() -> io::Result<()> {
+    test_ahead_threshold(1, "⇡⚑")
+}
+
+fn test_ahead_threshold(threshold: i64, expected_status: &str) -> io::Result<()> {
+    let repo_dir = common::create_fixture_repo()?;
+
+    ahead(&repo_dir)?;
+
+    let output = common::render_module("git_status")
+        .use_config(
+            toml::from_str(&format!(
+                "
+                    [git_status]
+                        ahead_threshold = {}
+                ",
+                threshold
+            ))
+            .unwrap(),
+        )
+        .arg("--path")
+        .arg(&repo_dir)
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+    let expected = Color::Red
+        .bold()
+        .paint(format!("[{}] ", expected_status))
+        .to_string();
+
+    assert_eq!(expected, actual);
+
+    remove_dir_all(repo_dir)
+}
+
 #[test]
 #[ignore]
 fn shows_diverged() -> io::Result<()> {
@@ -207,6 +284,119 @@ fn shows_conflicted_with_count() -> io::Result<()> {
     remove_dir_all(repo_dir)
 }
 
+#[test]
+#[ignore]
+fn shows_conflict_progress() -> io::Result<()> {
+    let repo_dir = common::create_fixture_repo()?;
+
+    create_merge_conflicts(&repo_dir)?;
+
+    // Resolve one of the two conflicts
+    fs::write(repo_dir.join("notes.md"), "resolved")?;
+    Command::new("git")
+        .args(&["add", "notes.md"])
+        .current_dir(repo_dir.as_path())
+        .output()?;
+    barrier();
+
+    let output = common::render_module("git_status")
+        .use_config(toml::toml! {
+            [git_status]
+            show_conflict_progress = true
+        })
+        .arg("--path")
+        .arg(&repo_dir)
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+    let expected = Color::Red
+        .bold()
+        .paint(format!("[{}] ", "=1/1⇡+"))
+        .to_string();
+
+    assert_eq!(expected, actual);
+
+    remove_dir_all(repo_dir)
+}
+
+#[test]
+#[ignore]
+fn shows_conflict_progress_without_cleanly_merged_files() -> io::Result<()> {
+    let repo_dir = common::create_fixture_repo()?;
+
+    create_merge_conflicts_with_clean_file(&repo_dir, Some("changelog.md"))?;
+
+    let render = || {
+        common::render_module("git_status")
+            .use_config(toml::toml! {
+                [git_status]
+                show_conflict_progress = true
+            })
+            .arg("--path")
+            .arg(&repo_dir)
+            .output()
+    };
+    let actual = String::from_utf8(render()?.stdout).unwrap();
+    let expected = Color::Red
+        .bold()
+        .paint(format!("[{}] ", "=0/2⇡+"))
+        .to_string();
+    assert_eq!(expected, actual);
+
+    // Resolve one of the two conflicts
+    fs::write(repo_dir.join("notes.md"), "resolved")?;
+    Command::new("git")
+        .args(&["add", "notes.md"])
+        .current_dir(repo_dir.as_path())
+        .output()?;
+    barrier();
+
+    let actual = String::from_utf8(render()?.stdout).unwrap();
+    let expected = Color::Red
+        .bold()
+        .paint(format!("[{}] ", "=1/1⇡+"))
+        .to_string();
+    assert_eq!(expected, actual);
+
+    remove_dir_all(repo_dir)
+}
+
+#[test]
+#[ignore]
+fn shows_conflict_progress_during_cherry_pick() -> io::Result<()> {
+    let repo_dir = common::create_fixture_repo()?;
+
+    create_merge_conflicts(&repo_dir)?;
+    Command::new("git")
+        .args(&["merge", "--abort"])
+        .current_dir(repo_dir.as_path())
+        .output()?;
+    barrier();
+
+    Command::new("git")
+        .args(&["cherry-pick", "other"])
+        .current_dir(repo_dir.as_path())
+        .output()?;
+    barrier();
+
+    let output = common::render_module("git_status")
+        .use_config(toml::toml! {
+            [git_status]
+            show_conflict_progress = true
+        })
+        .arg("--path")
+        .arg(&repo_dir)
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+    let expected = Color::Red
+        .bold()
+        .paint(format!("[{}] ", "=0/2⇡"))
+        .to_string();
+
+    assert_eq!(expected, actual);
+
+    remove_dir_all(repo_dir)
+}
+
 #[test]
 #[ignore]
 fn shows_untracked_file() -> io::Result<()> {
@@ -249,6 +439,58 @@ fn shows_untracked_file_with_count() -> io::Result<()> {
     remove_dir_all(repo_dir)
 }
 
+#[test]
+#[ignore]
+fn shows_untracked_directory_summary() -> io::Result<()> {
+    let repo_dir = common::create_fixture_repo()?;
+
+    create_untracked_in_directory(&repo_dir)?;
+
+    let output = common::render_module("git_status")
+        .use_config(toml::toml! {
+            [git_status]
+            summarize_untracked = true
+            untracked_count.enabled = true
+        })
+        .arg("--path")
+        .arg(&repo_dir)
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+    let expected = Color::Red
+        .bold()
+        .paint(format!("[{}] ", "?build/"))
+        .to_string();
+
+    assert_eq!(expected, actual);
+
+    remove_dir_all(repo_dir)
+}
+
+#[test]
+#[ignore]
+fn shows_untracked_summary_count() -> io::Result<()> {
+    let repo_dir = common::create_fixture_repo()?;
+
+    create_untracked(&repo_dir)?;
+    create_untracked_in_directory(&repo_dir)?;
+
+    let output = common::render_module("git_status")
+        .use_config(toml::toml! {
+            [git_status]
+            summarize_untracked = true
+            untracked_count.enabled = true
+        })
+        .arg("--path")
+        .arg(&repo_dir)
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+    let expected = Color::Red.bold().paint(format!("[{}] ", "?2")).to_string();
+
+    assert_eq!(expected, actual);
+
+    remove_dir_all(repo_dir)
+}
+
 #[test]
 #[ignore]
 fn doesnt_show_untracked_file_if_disabled() -> io::Result<()> {
@@ -347,6 +589,99 @@ fn shows_modified() -> io::Result<()> {
     remove_dir_all(repo_dir)
 }
 
+#[test]
+#[ignore]
+fn shows_large_repo_above_threshold() -> io::Result<()> {
+    test_large_repo_threshold(1, "…")
+}
+
+#[test]
+#[ignore]
+fn shows_status_below_large_repo_threshold() -> io::Result<()> {
+    test_large_repo_threshold(100_000, "!")
+}
+
+fn test_large_repo_threshold(threshold: i64, expected_status: &str) -> io::Result<()> {
+    let repo_dir = common::create_fixture_repo()?;
+
+    create_modified(&repo_dir)?;
+
+    let output = common::render_module("git_status")
+        .use_config(
+            toml::from_str(&format!(
+                "
+                    [git_status]
+                        large_repo_threshold = {}
+                ",
+                threshold
+            ))
+            .unwrap(),
+        )
+        .arg("--path")
+        .arg(&repo_dir)
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+    let expected = Color::Red
+        .bold()
+        .paint(format!("[{}] ", expected_status))
+        .to_string();
+
+    assert_eq!(expected, actual);
+    remove_dir_all(repo_dir)
+}
+
+#[test]
+#[ignore]
+fn shows_describe_at_tag() -> io::Result<()> {
+    test_describe(0, |_| "v1.2.0".to_string())
+}
+
+#[test]
+#[ignore]
+fn shows_describe_ahead_of_tag() -> io::Result<()> {
+    test_describe(3, |hash| format!("⇡ v1.2.0-3-g{}", hash))
+}
+
+fn test_describe(commits: usize, expected_status: impl Fn(&str) -> String) -> io::Result<()> {
+    let repo_dir = common::create_fixture_repo()?;
+
+    Command::new("git")
+        .args(&["tag", "v1.2.0"])
+        .current_dir(&repo_dir)
+        .output()?;
+    for _ in 0..commits {
+        Command::new("git")
+            .args(&["commit", "--allow-empty", "-m", "Empty commit"])
+            .current_dir(&repo_dir)
+            .output()?;
+    }
+    barrier();
+
+    let hash = Command::new("git")
+        .args(&["rev-parse", "--short=7", "HEAD"])
+        .current_dir(&repo_dir)
+        .output()?
+        .stdout;
+    let hash = String::from_utf8(hash).unwrap();
+
+    let output = common::render_module("git_status")
+        .use_config(toml::toml! {
+            [git_status]
+            show_describe = true
+        })
+        .arg("--path")
+        .arg(&repo_dir)
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+    let expected = Color::Red
+        .bold()
+        .paint(format!("[{}] ", expected_status(hash.trim())))
+        .to_string();
+
+    assert_eq!(expected, actual);
+    remove_dir_all(repo_dir)
+}
+
 #[test]
 #[ignore]
 fn shows_modified_with_count() -> io::Result<()> {
@@ -616,6 +951,61 @@ fn create_conflict(repo_dir: &PathBuf) -> io::Result<()> {
     Ok(())
 }
 
+/// Merges a branch changing the same two files as the current one, `readme.md` and
+/// `notes.md`, leaving both conflicted
+fn create_merge_conflicts(repo_dir: &PathBuf) -> io::Result<()> {
+    create_merge_conflicts_with_clean_file(repo_dir, None)
+}
+
+/// Like `create_merge_conflicts`, with the merged branch also adding `clean_file`,
+/// which merges cleanly
+fn create_merge_conflicts_with_clean_file(
+    repo_dir: &PathBuf,
+    clean_file: Option<&str>,
+) -> io::Result<()> {
+    Command::new("git")
+        .args(&["checkout", "-b", "other"])
+        .current_dir(repo_dir.as_path())
+        .output()?;
+    barrier();
+
+    for branch in &["other", "master"] {
+        if *branch == "master" {
+            Command::new("git")
+                .args(&["checkout", "master"])
+                .current_dir(repo_dir.as_path())
+                .output()?;
+            barrier();
+        }
+
+        fs::write(repo_dir.join("readme.md"), format!("# {}", branch))?;
+        fs::write(repo_dir.join("notes.md"), branch)?;
+        if let (Some(clean_file), "other") = (clean_file, *branch) {
+            fs::write(repo_dir.join(clean_file), "merges cleanly")?;
+        }
+
+        Command::new("git")
+            .args(&["add", "."])
+            .current_dir(repo_dir.as_path())
+            .output()?;
+        barrier();
+
+        Command::new("git")
+            .args(&["commit", "-m", "Change readme and notes"])
+            .current_dir(repo_dir.as_path())
+            .output()?;
+        barrier();
+    }
+
+    Command::new("git")
+        .args(&["merge", "other"])
+        .current_dir(repo_dir.as_path())
+        .output()?;
+    barrier();
+
+    Ok(())
+}
+
 fn create_stash(repo_dir: &PathBuf) -> io::Result<()> {
     File::create(repo_dir.join("readme.md"))?.sync_all()?;
     barrier();
@@ -635,6 +1025,16 @@ fn create_untracked(repo_dir: &PathBuf) -> io::Result<()> {
     Ok(())
 }
 
+fn create_untracked_in_directory(repo_dir: &PathBuf) -> io::Result<()> {
+    let build_dir = repo_dir.join("build");
+    fs::create_dir(&build_dir)?;
+    for name in &["rocket.o", "engine.o", "fuel.o"] {
+        File::create(build_dir.join(name))?.sync_all()?;
+    }
+
+    Ok(())
+}
+
 fn create_modified(repo_dir: &PathBuf) -> io::Result<()> {
     File::create(repo_dir.join("readme.md"))?.sync_all()?;
 
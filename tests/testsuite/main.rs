@@ -1,12 +1,15 @@
 mod aws;
 mod character;
+mod cloudflare;
 mod cmd_duration;
 mod common;
 mod conda;
 mod configuration;
 mod directory;
 mod dotnet;
+mod env_badge;
 mod env_var;
+mod fill;
 mod git_branch;
 mod git_commit;
 mod git_state;
@@ -17,6 +20,7 @@ mod jobs;
 mod line_break;
 mod modules;
 mod nix_shell;
+mod pulumi;
 mod python;
 mod singularity;
 mod terraform;
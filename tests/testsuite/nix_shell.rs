@@ -1,7 +1,8 @@
 use ansi_term::Color;
+use std::fs::File;
 use std::io;
 
-use crate::common;
+use crate::common::{self, TestCommand};
 
 #[test]
 fn no_env_variables() -> io::Result<()> {
@@ -21,6 +22,16 @@ fn invalid_env_variables() -> io::Result<()> {
     Ok(())
 }
 
+#[test]
+fn empty_env_variable() -> io::Result<()> {
+    let output = common::render_module("nix_shell")
+        .env("IN_NIX_SHELL", "")
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+    assert_eq!("", actual);
+    Ok(())
+}
+
 #[test]
 fn pure_shell() -> io::Result<()> {
     let output = common::render_module("nix_shell")
@@ -56,3 +67,62 @@ fn lorri_shell() -> io::Result<()> {
     assert_eq!(expected, actual);
     Ok(())
 }
+
+#[test]
+fn pure_flake_shell() -> io::Result<()> {
+    let dir = tempfile::tempdir()?;
+    File::create(dir.path().join("flake.nix"))?.sync_all()?;
+
+    let output = common::render_module("nix_shell")
+        .env("IN_NIX_SHELL", "pure")
+        .arg("--path")
+        .arg(dir.path())
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+
+    let expected = format!("via {} ", Color::Blue.bold().paint("❆ pure"));
+    assert_eq!(expected, actual);
+    dir.close()
+}
+
+#[test]
+fn impure_flake_shell_with_name() -> io::Result<()> {
+    let dir = tempfile::tempdir()?;
+    File::create(dir.path().join("flake.nix"))?.sync_all()?;
+
+    let output = common::render_module("nix_shell")
+        .env("IN_NIX_SHELL", "impure")
+        .env("name", "rocket-shell")
+        .use_config(toml::toml! {
+            [nix_shell]
+            use_name = true
+        })
+        .arg("--path")
+        .arg(dir.path())
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+
+    let expected = format!(
+        "via {} ",
+        Color::Blue.bold().paint("❆ rocket-shell (impure)")
+    );
+    assert_eq!(expected, actual);
+    dir.close()
+}
+
+#[test]
+fn shell_nix_uses_default_symbol() -> io::Result<()> {
+    let dir = tempfile::tempdir()?;
+    File::create(dir.path().join("shell.nix"))?.sync_all()?;
+
+    let output = common::render_module("nix_shell")
+        .env("IN_NIX_SHELL", "impure")
+        .arg("--path")
+        .arg(dir.path())
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+
+    let expected = format!("via {} ", Color::Blue.bold().paint("❄️  impure"));
+    assert_eq!(expected, actual);
+    dir.close()
+}
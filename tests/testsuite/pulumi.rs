@@ -0,0 +1,50 @@
+use ansi_term::Color;
+use std::fs;
+use std::io;
+
+use crate::common;
+use crate::common::TestCommand;
+
+#[test]
+fn stack_from_env() -> io::Result<()> {
+    let dir = tempfile::tempdir()?;
+    fs::write(
+        dir.path().join("Pulumi.yaml"),
+        "name: rocket\nruntime: go\n",
+    )?;
+
+    let output = common::render_module("pulumi")
+        .env("PULUMI_STACK", "staging")
+        .arg("--path")
+        .arg(dir.path())
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+
+    let expected = format!("via {} ", Color::Purple.bold().paint("🧊 rocket/staging"));
+    assert_eq!(expected, actual);
+    dir.close()
+}
+
+#[test]
+fn stack_from_env_without_project() -> io::Result<()> {
+    let dir = tempfile::tempdir()?;
+    fs::write(
+        dir.path().join("Pulumi.yaml"),
+        "name: rocket\nruntime: go\n",
+    )?;
+
+    let output = common::render_module("pulumi")
+        .env("PULUMI_STACK", "staging")
+        .use_config(toml::toml! {
+            [pulumi]
+            show_project = false
+        })
+        .arg("--path")
+        .arg(dir.path())
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+
+    let expected = format!("via {} ", Color::Purple.bold().paint("🧊 staging"));
+    assert_eq!(expected, actual);
+    dir.close()
+}
@@ -1,7 +1,8 @@
-use std::fs::File;
+use std::fs::{self, File};
 use std::io;
+use std::path::{Path, PathBuf};
 
-use ansi_term::Color;
+use ansi_term::{ANSIStrings, Color};
 
 use crate::common::{self, TestCommand};
 
@@ -161,8 +162,9 @@ fn folder_with_py_file() -> io::Result<()> {
 fn with_virtual_env() -> io::Result<()> {
     let dir = tempfile::tempdir()?;
     File::create(dir.path().join("main.py"))?.sync_all()?;
+    let venv_dir = create_venv(dir.path())?;
     let output = common::render_module("python")
-        .env("VIRTUAL_ENV", "/foo/bar/my_venv")
+        .env("VIRTUAL_ENV", &venv_dir)
         .arg("--path")
         .arg(dir.path())
         .output()?;
@@ -177,9 +179,10 @@ fn with_virtual_env() -> io::Result<()> {
 #[ignore]
 fn with_active_venv() -> io::Result<()> {
     let dir = tempfile::tempdir()?;
+    let venv_dir = create_venv(dir.path())?;
 
     let output = common::render_module("python")
-        .env("VIRTUAL_ENV", "/foo/bar/my_venv")
+        .env("VIRTUAL_ENV", &venv_dir)
         .arg("--path")
         .arg(dir.path())
         .output()?;
@@ -230,3 +233,64 @@ fn disabled_scan_for_pyfiles_and_folder_with_setup_py() -> io::Result<()> {
     assert_eq!(expected, actual);
     Ok(())
 }
+
+#[test]
+fn with_broken_virtual_env() -> io::Result<()> {
+    let dir = tempfile::tempdir()?;
+    File::create(dir.path().join("main.py"))?.sync_all()?;
+    let output = common::render_module("python")
+        .env("VIRTUAL_ENV", dir.path().join("my_venv"))
+        .arg("--path")
+        .arg(dir.path())
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+
+    let expected = format!(
+        "via {} ",
+        ANSIStrings(&[
+            Color::Yellow.bold().paint("🐍 "),
+            Color::Red.bold().paint("broken"),
+            Color::Yellow.bold().paint(" (my_venv)"),
+        ])
+    );
+    assert_eq!(expected, actual);
+    dir.close()
+}
+
+#[test]
+fn with_virtual_env_missing_python() -> io::Result<()> {
+    let dir = tempfile::tempdir()?;
+    let venv_dir = dir.path().join("my_venv");
+    fs::create_dir_all(venv_dir.join("bin"))?;
+    let output = common::render_module("python")
+        .env("VIRTUAL_ENV", &venv_dir)
+        .arg("--path")
+        .arg(dir.path())
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+
+    let expected = format!(
+        "via {} ",
+        ANSIStrings(&[
+            Color::Yellow.bold().paint("🐍 "),
+            Color::Red.bold().paint("broken"),
+            Color::Yellow.bold().paint(" (my_venv)"),
+        ])
+    );
+    assert_eq!(expected, actual);
+    dir.close()
+}
+
+/// Creates a virtual environment named `my_venv` containing a python interpreter
+fn create_venv(dir: &Path) -> io::Result<PathBuf> {
+    let venv_dir = dir.join("my_venv");
+    let bin_dir = venv_dir.join(if cfg!(windows) { "Scripts" } else { "bin" });
+    fs::create_dir_all(&bin_dir)?;
+    let python = if cfg!(windows) {
+        "python.exe"
+    } else {
+        "python"
+    };
+    File::create(bin_dir.join(python))?.sync_all()?;
+    Ok(venv_dir)
+}
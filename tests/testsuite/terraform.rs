@@ -37,6 +37,23 @@ fn folder_with_tf_file() -> io::Result<()> {
     dir.close()
 }
 
+#[test]
+#[ignore]
+fn folder_with_tfvars_file() -> io::Result<()> {
+    let dir = tempfile::tempdir()?;
+    File::create(dir.path().join("prod.tfvars"))?;
+
+    let output = common::render_module("terraform")
+        .arg("--path")
+        .arg(dir.path())
+        .output()?;
+    let actual = String::from_utf8(output.stdout).unwrap();
+
+    let expected = format!("via {} ", Color::Fixed(105).bold().paint("💠 default"));
+    assert_eq!(expected, actual);
+    dir.close()
+}
+
 #[test]
 #[ignore]
 fn folder_with_workspace_override() -> io::Result<()> {